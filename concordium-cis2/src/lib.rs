@@ -29,7 +29,9 @@
 //!
 //! This crate has features `std` and `u256_amount`. The former one is default.
 //! When `u256_amount` feature is enabled the type [`TokenAmountU256`] is defined
-//! and implements the [`IsTokenAmount`] interface.
+//! and implements the [`IsTokenAmount`] interface. When `std` is enabled,
+//! [`TokenMetadata`] and [`MetadataUrl`] implement serde's `Serialize` and
+//! `Deserialize`.
 #![cfg_attr(not(feature = "std"), no_std)]
 use concordium_std::{collections::BTreeMap, *};
 #[cfg(not(feature = "std"))]
@@ -51,6 +53,16 @@ pub const CIS1_STANDARD_IDENTIFIER: StandardIdentifier<'static> =
 pub const CIS2_STANDARD_IDENTIFIER: StandardIdentifier<'static> =
     StandardIdentifier::new_unchecked("CIS-2");
 
+/// The standard identifier for the allowances extension of CIS-2, see
+/// [`Allowances`].
+pub const CIS2_ALLOWANCES_STANDARD_IDENTIFIER: StandardIdentifier<'static> =
+    StandardIdentifier::new_unchecked("CIS-2-Allowances");
+
+/// The standard identifier for the royalties extension of CIS-2, see
+/// [`Royalties`].
+pub const CIS2_ROYALTIES_STANDARD_IDENTIFIER: StandardIdentifier<'static> =
+    StandardIdentifier::new_unchecked("CIS-2-Royalties");
+
 /// Tag for the CIS2 Transfer event.
 pub const TRANSFER_EVENT_TAG: u8 = u8::MAX;
 /// Tag for the CIS2 Mint event.
@@ -61,19 +73,31 @@ pub const BURN_EVENT_TAG: u8 = u8::MAX - 2;
 pub const UPDATE_OPERATOR_EVENT_TAG: u8 = u8::MAX - 3;
 /// Tag for the CIS2 TokenMetadata event.
 pub const TOKEN_METADATA_EVENT_TAG: u8 = u8::MAX - 4;
+/// Tag for the CIS3 Nonce event.
+pub const NONCE_EVENT_TAG: u8 = u8::MAX - 5;
+/// Tag for the UpdateAllowance event of the CIS2 allowances extension.
+pub const UPDATE_ALLOWANCE_EVENT_TAG: u8 = u8::MAX - 6;
 
 /// Sha256 digest
 pub type Sha256 = [u8; 32];
 
 /// The location of the metadata and an optional hash of the content.
+///
+/// With the `std` feature, it can be (de)serialized with serde as the JSON
+/// object used for URLs in [`TokenMetadata`], where the hash is hex encoded.
 // Note: For the serialization to be derived according to the CIS2
 // specification, the order of the fields cannot be changed.
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 pub struct MetadataUrl {
     /// The URL following the specification RFC1738.
     #[concordium(size_length = 2)]
     pub url:  String,
     /// A optional hash of the content.
+    #[cfg_attr(
+        feature = "std",
+        serde(default, skip_serializing_if = "Option::is_none", with = "hex_hash")
+    )]
     pub hash: Option<Sha256>,
 }
 
@@ -86,6 +110,136 @@ impl schema::SchemaType for MetadataUrl {
     }
 }
 
+impl MetadataUrl {
+    /// Construct a metadata URL with the SHA-256 hash of the content it points
+    /// to.
+    pub fn with_hash(
+        url: String,
+        content: &[u8],
+        crypto_primitives: &impl HasCryptoPrimitives,
+    ) -> Self {
+        MetadataUrl {
+            url,
+            hash: Some(crypto_primitives.hash_sha2_256(content).0),
+        }
+    }
+
+    /// Check whether the content fetched from the URL matches the hash.
+    /// Content always matches a URL without a hash.
+    pub fn verify_hash(
+        &self,
+        content: &[u8],
+        crypto_primitives: &impl HasCryptoPrimitives,
+    ) -> bool {
+        match self.hash {
+            Some(hash) => crypto_primitives.hash_sha2_256(content).0 == hash,
+            None => true,
+        }
+    }
+}
+
+/// Serialization of optional hashes as hexadecimal strings with serde.
+#[cfg(feature = "std")]
+mod hex_hash {
+    use super::Sha256;
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        hash: &Option<Sha256>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match hash {
+            Some(hash) => {
+                let hex: String = hash.iter().map(|byte| format!("{:02x}", byte)).collect();
+                serializer.serialize_some(&hex)
+            }
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Sha256>, D::Error> {
+        let hex: Option<String> = Option::deserialize(deserializer)?;
+        hex.map(|hex| {
+            parse(&hex).ok_or_else(|| {
+                de::Error::custom("expected a SHA-256 hash of 64 hexadecimal digits")
+            })
+        })
+        .transpose()
+    }
+
+    fn parse(hex: &str) -> Option<Sha256> {
+        if hex.len() != 64 || !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return None;
+        }
+        let mut hash = [0u8; 32];
+        for (byte, digits) in hash.iter_mut().zip(hex.as_bytes().chunks(2)) {
+            // The digits are ASCII, so they are valid UTF-8.
+            *byte = u8::from_str_radix(std::str::from_utf8(digits).ok()?, 16).ok()?;
+        }
+        Some(hash)
+    }
+}
+
+/// The metadata of a token, which is the JSON document the [`MetadataUrl`] of
+/// the token points to, as specified by CIS2. All the fields are optional.
+///
+/// With the `std` feature, it can be (de)serialized with serde, e.g., using
+/// `serde_json`, where fields which are not set are omitted.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "std", serde(default))]
+pub struct TokenMetadata {
+    /// The name to display for the token.
+    #[cfg_attr(feature = "std", serde(skip_serializing_if = "Option::is_none"))]
+    pub name:         Option<String>,
+    /// The short name to display for the token.
+    #[cfg_attr(feature = "std", serde(skip_serializing_if = "Option::is_none"))]
+    pub symbol:       Option<String>,
+    /// Whether the token is unique, i.e., at most one of it exists.
+    #[cfg_attr(feature = "std", serde(skip_serializing_if = "Option::is_none"))]
+    pub unique:       Option<bool>,
+    /// The number of decimals to use when displaying token amounts, e.g., 6
+    /// to display the amount 1000000 as 1.000000.
+    #[cfg_attr(feature = "std", serde(skip_serializing_if = "Option::is_none"))]
+    pub decimals:     Option<u8>,
+    /// A description of the token.
+    #[cfg_attr(feature = "std", serde(skip_serializing_if = "Option::is_none"))]
+    pub description:  Option<String>,
+    /// A small image to display for the token.
+    #[cfg_attr(feature = "std", serde(skip_serializing_if = "Option::is_none"))]
+    pub thumbnail:    Option<MetadataUrl>,
+    /// A large image to display for the token.
+    #[cfg_attr(feature = "std", serde(skip_serializing_if = "Option::is_none"))]
+    pub display:      Option<MetadataUrl>,
+    /// An asset, such as an image or a document, the token represents.
+    #[cfg_attr(feature = "std", serde(skip_serializing_if = "Option::is_none"))]
+    pub artifact:     Option<MetadataUrl>,
+    /// The metadata of other tokens the token is composed of.
+    #[cfg_attr(feature = "std", serde(skip_serializing_if = "Vec::is_empty"))]
+    pub assets:       Vec<TokenMetadata>,
+    /// Additional attributes of the token.
+    #[cfg_attr(feature = "std", serde(skip_serializing_if = "Vec::is_empty"))]
+    pub attributes:   Vec<TokenAttribute>,
+    /// Translations of the metadata, by language code, e.g., "en-US".
+    #[cfg_attr(feature = "std", serde(skip_serializing_if = "BTreeMap::is_empty"))]
+    pub localization: BTreeMap<String, MetadataUrl>,
+}
+
+/// An attribute of a token in its [`TokenMetadata`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct TokenAttribute {
+    /// The type of the value, e.g., "string", "number" or "date".
+    #[cfg_attr(feature = "std", serde(rename = "type"))]
+    pub attribute_type: String,
+    /// The name of the attribute.
+    pub name:           String,
+    /// The value of the attribute.
+    pub value:          String,
+}
+
 /// Trait for marking types as CIS2 token IDs.
 /// For a type to be a valid CIS2 token ID it must implement SchemaType and
 /// Serialize, such that the first byte indicates how many bytes is used to
@@ -106,6 +260,57 @@ pub trait IsTokenId: Serialize + schema::SchemaType {}
 /// token amount implementations avoiding doing arithmetics of large integers.
 pub trait IsTokenAmount: Serialize + schema::SchemaType {}
 
+/// Arithmetic on CIS2 token amounts which reports overflow and underflow
+/// instead of trapping or wrapping, as the plain operators do. It is
+/// implemented for all the token amount types in this crate, so that code
+/// which is generic over the token amount can do safe arithmetic.
+///
+/// ```
+/// # use concordium_cis2::*;
+/// fn debit<A: TokenAmountArithmetic>(balance: A, amount: A) -> Result<A, Cis2Error<()>> {
+///     balance.sub_or_insufficient_funds(amount)
+/// }
+/// assert_eq!(debit(TokenAmountU8(3), TokenAmountU8(2)), Ok(TokenAmountU8(1)));
+/// assert_eq!(debit(TokenAmountU8(1), TokenAmountU8(2)), Err(Cis2Error::InsufficientFunds));
+/// ```
+pub trait TokenAmountArithmetic: IsTokenAmount + Copy + Ord {
+    /// The integer type of the amount, which amounts can be multiplied by.
+    type Integer;
+
+    /// The amount zero.
+    fn zero() -> Self;
+
+    /// Add two amounts, returning `None` on overflow.
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+
+    /// Subtract an amount, returning `None` if `rhs` is larger than `self`.
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+
+    /// Multiply the amount by an integer, returning `None` on overflow.
+    fn checked_mul(self, rhs: Self::Integer) -> Option<Self>;
+
+    /// Add two amounts, returning the maximal amount on overflow.
+    fn saturating_add(self, rhs: Self) -> Self;
+
+    /// Subtract an amount, returning zero if `rhs` is larger than `self`.
+    fn saturating_sub(self, rhs: Self) -> Self;
+
+    /// Multiply the amount by an integer, returning the maximal amount on
+    /// overflow.
+    fn saturating_mul(self, rhs: Self::Integer) -> Self;
+
+    /// Sum the amounts, returning `None` on overflow.
+    fn try_sum<I: IntoIterator<Item = Self>>(iter: I) -> Option<Self> {
+        iter.into_iter().try_fold(Self::zero(), Self::checked_add)
+    }
+
+    /// Subtract an amount, returning [`Cis2Error::InsufficientFunds`] if `rhs`
+    /// is larger than `self`, e.g., when debiting the balance of an address.
+    fn sub_or_insufficient_funds<R>(self, rhs: Self) -> Result<Self, Cis2Error<R>> {
+        self.checked_sub(rhs).ok_or(Cis2Error::InsufficientFunds)
+    }
+}
+
 /// Token Identifier, which combined with the address of the contract instance,
 /// forms the unique identifier of a token type.
 ///
@@ -514,6 +719,33 @@ macro_rules! token_amount_wrapper {
 
         impl IsTokenAmount for $name {}
 
+        impl TokenAmountArithmetic for $name {
+            type Integer = $wrapped;
+
+            #[inline(always)]
+            fn zero() -> Self { $name(0) }
+
+            #[inline(always)]
+            fn checked_add(self, rhs: Self) -> Option<Self> { self.0.checked_add(rhs.0).map($name) }
+
+            #[inline(always)]
+            fn checked_sub(self, rhs: Self) -> Option<Self> { self.0.checked_sub(rhs.0).map($name) }
+
+            #[inline(always)]
+            fn checked_mul(self, rhs: $wrapped) -> Option<Self> {
+                self.0.checked_mul(rhs).map($name)
+            }
+
+            #[inline(always)]
+            fn saturating_add(self, rhs: Self) -> Self { $name(self.0.saturating_add(rhs.0)) }
+
+            #[inline(always)]
+            fn saturating_sub(self, rhs: Self) -> Self { $name(self.0.saturating_sub(rhs.0)) }
+
+            #[inline(always)]
+            fn saturating_mul(self, rhs: $wrapped) -> Self { $name(self.0.saturating_mul(rhs)) }
+        }
+
         /// Uses the ULeb128 encoding with up to 37 bytes for the encoding as
         /// according to CIS-2 specification.
         impl schema::SchemaType for $name {
@@ -544,7 +776,15 @@ macro_rules! token_amount_wrapper {
                 for i in 0..37 {
                     let byte = source.read_u8()?;
                     let value_byte = (byte & 0b0111_1111) as $wrapped;
-                    result = result.checked_add(value_byte << (i * 7)).ok_or(ParseError {})?;
+                    if value_byte != 0 {
+                        // Fail if any bits of the byte are shifted out of the integer.
+                        let shift = i * 7;
+                        let value = value_byte
+                            .checked_shl(shift)
+                            .filter(|value| value >> shift == value_byte)
+                            .ok_or(ParseError {})?;
+                        result = result.checked_add(value).ok_or(ParseError {})?;
+                    }
 
                     if byte & 0b1000_0000 == 0 {
                         return Ok($name(result));
@@ -632,6 +872,37 @@ mod u256_token {
 
     impl IsTokenAmount for TokenAmountU256 {}
 
+    impl TokenAmountArithmetic for TokenAmountU256 {
+        type Integer = U256;
+
+        #[inline(always)]
+        fn zero() -> Self { TokenAmountU256(U256::zero()) }
+
+        #[inline(always)]
+        fn checked_add(self, rhs: Self) -> Option<Self> {
+            self.0.checked_add(rhs.0).map(TokenAmountU256)
+        }
+
+        #[inline(always)]
+        fn checked_sub(self, rhs: Self) -> Option<Self> {
+            self.0.checked_sub(rhs.0).map(TokenAmountU256)
+        }
+
+        #[inline(always)]
+        fn checked_mul(self, rhs: U256) -> Option<Self> {
+            self.0.checked_mul(rhs).map(TokenAmountU256)
+        }
+
+        #[inline(always)]
+        fn saturating_add(self, rhs: Self) -> Self { TokenAmountU256(self.0.saturating_add(rhs.0)) }
+
+        #[inline(always)]
+        fn saturating_sub(self, rhs: Self) -> Self { TokenAmountU256(self.0.saturating_sub(rhs.0)) }
+
+        #[inline(always)]
+        fn saturating_mul(self, rhs: U256) -> Self { TokenAmountU256(self.0.saturating_mul(rhs)) }
+    }
+
     /// Uses the ULeb128 encoding with up to 37 bytes for the encoding as
     /// according to CIS-2 specification.
     impl schema::SchemaType for TokenAmountU256 {
@@ -712,6 +983,21 @@ mod u256_token {
             let bytes = to_bytes(&v);
             assert_eq!(Ok(v), from_bytes(&bytes));
         }
+        #[test]
+        fn token_amount256_checked_arithmetic_test() {
+            let max = TokenAmountU256(U256([u64::MAX; 4]));
+            let one = TokenAmountU256(U256::one());
+            assert_eq!(max.checked_add(one), None);
+            assert_eq!(TokenAmountU256::zero().checked_sub(one), None);
+            assert_eq!(max.checked_mul(U256::from(2u64)), None);
+            assert_eq!(max.saturating_add(one), max);
+            assert_eq!(TokenAmountU256::zero().saturating_sub(one), TokenAmountU256::zero());
+            assert_eq!(
+                TokenAmountU256::try_sum(vec![one, one]),
+                Some(TokenAmountU256(U256::from(2u64)))
+            );
+        }
+
         #[test]
         fn serial_token_amount_invalid() {
             // fail if overflowing.
@@ -905,6 +1191,25 @@ impl<T: IsTokenId, A: IsTokenAmount> Serial for Cis2Event<T, A> {
 impl<T: IsTokenId, A: IsTokenAmount> Deserial for Cis2Event<T, A> {
     fn deserial<R: Read>(source: &mut R) -> ParseResult<Self> {
         let tag = source.read_u8()?;
+        Self::deserial_tagged(tag, source)
+    }
+}
+
+/// Allows flattening `Cis2Event` into the event type of a contract deriving
+/// `Event`.
+impl<T: IsTokenId, A: IsTokenAmount> TaggedEvent for Cis2Event<T, A> {
+    const TAGS: EventTags = EventTags {
+        tags:      &[
+            TRANSFER_EVENT_TAG,
+            MINT_EVENT_TAG,
+            BURN_EVENT_TAG,
+            UPDATE_OPERATOR_EVENT_TAG,
+            TOKEN_METADATA_EVENT_TAG,
+        ],
+        flattened: &[],
+    };
+
+    fn deserial_tagged<R: Read>(tag: u8, source: &mut R) -> ParseResult<Self> {
         match tag {
             TRANSFER_EVENT_TAG => TransferEvent::<T, A>::deserial(source).map(Cis2Event::Transfer),
             MINT_EVENT_TAG => MintEvent::<T, A>::deserial(source).map(Cis2Event::Mint),
@@ -959,6 +1264,18 @@ impl<R: Serial> Serial for Cis2Error<R> {
     }
 }
 
+impl<R: Deserial> Deserial for Cis2Error<R> {
+    fn deserial<S: Read>(source: &mut S) -> ParseResult<Self> {
+        match source.read_u8()? {
+            0 => Ok(Cis2Error::InvalidTokenId),
+            1 => Ok(Cis2Error::InsufficientFunds),
+            2 => Ok(Cis2Error::Unauthorized),
+            3 => Ok(Cis2Error::Custom(R::deserial(source)?)),
+            _ => Err(ParseError::default()),
+        }
+    }
+}
+
 /// Convert `Cis2Error` into a reject with error code:
 /// - InvalidTokenId: -42000001
 /// - InsufficientFunds: -42000002
@@ -1040,6 +1357,21 @@ where
     fn from(err: NewContractNameError) -> Self { Cis2Error::Custom(X::from(err)) }
 }
 
+/// Error for when minting would make the total supply of a token exceed the
+/// maximal amount of the token amount type. Returned by [`Cis2Ledger::mint`]
+/// wrapped in [`Cis2Error::Custom`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenAmountOverflowError;
+
+impl<X> From<TokenAmountOverflowError> for Cis2Error<X>
+where
+    X: From<TokenAmountOverflowError>,
+{
+    #[inline]
+    /// Converts the error by wrapping it in [Self::Custom].
+    fn from(err: TokenAmountOverflowError) -> Self { Cis2Error::Custom(X::from(err)) }
+}
+
 /// The receiving address for a transfer, similar to the Address type, but
 /// contains extra information when the receiver address is a contract.
 // Note: For the serialization to be derived according to the CIS2
@@ -1150,6 +1482,35 @@ impl<T: IsTokenId, A: IsTokenAmount> schema::SchemaType for Transfer<T, A> {
     }
 }
 
+impl<T: IsTokenId, A: IsTokenAmount> Transfer<T, A> {
+    /// If the receiver of the transfer is a contract, invoke the function of
+    /// the receiver with the [`OnReceivingCis2Params`] of the transfer, as
+    /// required by CIS2. This should be called after the balances have been
+    /// updated and the event has been logged, e.g., using
+    /// [`Cis2Ledger::transfer`]. A rejection of the receiver is returned as
+    /// an error, such that the whole transfer is rejected.
+    pub fn notify_receiver<State, H, E>(self, host: &mut H) -> Result<(), Cis2Error<E>>
+    where
+        H: HasHost<State>,
+        E: From<CallContractError<H::ReturnValueType>>, {
+        if let Receiver::Contract(address, function) = self.to {
+            let parameter = OnReceivingCis2Params {
+                token_id: self.token_id,
+                amount:   self.amount,
+                from:     self.from,
+                data:     self.data,
+            };
+            host.invoke_contract(
+                &address,
+                &parameter,
+                function.as_entrypoint_name(),
+                Amount::zero(),
+            )?;
+        }
+        Ok(())
+    }
+}
+
 /// The parameter type for the contract function `transfer`.
 #[derive(Debug, Serialize)]
 pub struct TransferParams<T: IsTokenId, A: IsTokenAmount>(
@@ -1541,148 +1902,2932 @@ impl AsRef<[SupportResult]> for SupportsQueryResponse {
     fn as_ref(&self) -> &[SupportResult] { &self.results }
 }
 
-#[cfg(test)]
-mod test {
-    use super::*;
+/// A client for invoking the entrypoints of a CIS-2 token contract from another
+/// contract.
+///
+/// The return value of a rejection is parsed as a [`Cis2Error`], where `E` is
+/// the type of the custom errors of the token contract. For contracts that
+/// reject with a `Cis2Error`, this gives the standard errors, such as
+/// [`Cis2Error::InsufficientFunds`], when calling [`transfer`](Self::transfer).
+/// If the custom errors are not of interest `E` can be left as `()`.
+///
+/// ```ignore
+/// let token = Cis2Client::<()>::new(token_address);
+/// let response: BalanceOfQueryResponse<TokenAmountU64> = token.balance_of(host, &query)?;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cis2Client<E = ()> {
+    /// The address of the token contract.
+    pub address:   ContractAddress,
+    _custom_error: marker::PhantomData<E>,
+}
 
-    #[test]
-    fn serial_token_amount128_127_test() {
-        let amount = TokenAmountU128::from(127);
-        let bytes = to_bytes(&amount);
-        assert_eq!(bytes, vec![127])
+impl<E> ContractInterface for Cis2Client<E> {
+    fn address(&self) -> ContractAddress { self.address }
+}
+
+impl<E: Deserial> Cis2Client<E> {
+    /// Construct a client for the token contract at the given address.
+    pub fn new(address: ContractAddress) -> Self {
+        Cis2Client {
+            address,
+            _custom_error: marker::PhantomData,
+        }
     }
 
-    #[test]
-    fn serial_token_amount128_max_test() {
-        let amount = TokenAmountU128::from(u128::MAX);
-        let bytes = to_bytes(&amount);
-        assert_eq!(bytes, vec![
-            255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255,
-            255, 0b00000011
-        ])
+    /// Invoke the `transfer` entrypoint of the token contract.
+    pub fn transfer<State, T: IsTokenId, A: IsTokenAmount>(
+        &self,
+        host: &mut impl HasHost<State>,
+        params: &TransferParams<T, A>,
+    ) -> Result<(), CallContractError<Cis2Error<E>>> {
+        self.invoke(host, EntrypointName::new_unchecked("transfer"), params, Amount::zero())
     }
 
-    #[test]
-    fn deserial_token_amount128_127_test() {
-        let amount: TokenAmountU128 = from_bytes(&[127]).expect("Failed to parse bytes");
-        assert_eq!(amount, TokenAmountU128::from(127))
+    /// Invoke the `updateOperator` entrypoint of the token contract.
+    pub fn update_operator<State>(
+        &self,
+        host: &mut impl HasHost<State>,
+        params: &UpdateOperatorParams,
+    ) -> Result<(), CallContractError<Cis2Error<E>>> {
+        self.invoke(host, EntrypointName::new_unchecked("updateOperator"), params, Amount::zero())
     }
 
-    #[test]
-    fn deserial_token_amount128_max_test() {
-        let amount: TokenAmountU128 = from_bytes(&[
-            255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255,
-            255, 0b00000011,
-        ])
-        .expect("Failed to parse bytes");
-        assert_eq!(amount, TokenAmountU128::from(u128::MAX))
+    /// Invoke the `balanceOf` entrypoint of the token contract.
+    pub fn balance_of<State, T: IsTokenId, A: IsTokenAmount>(
+        &self,
+        host: &impl HasHost<State>,
+        params: &BalanceOfQueryParams<T>,
+    ) -> Result<BalanceOfQueryResponse<A>, CallContractError<Cis2Error<E>>> {
+        self.invoke_read_only(
+            host,
+            EntrypointName::new_unchecked("balanceOf"),
+            params,
+            Amount::zero(),
+        )
     }
 
-    #[test]
-    fn serial_token_amount64_127_test() {
-        let amount = TokenAmountU64::from(127);
-        let bytes = to_bytes(&amount);
-        assert_eq!(bytes, vec![127])
+    /// Invoke the `operatorOf` entrypoint of the token contract.
+    pub fn operator_of<State>(
+        &self,
+        host: &impl HasHost<State>,
+        params: &OperatorOfQueryParams,
+    ) -> Result<OperatorOfQueryResponse, CallContractError<Cis2Error<E>>> {
+        self.invoke_read_only(
+            host,
+            EntrypointName::new_unchecked("operatorOf"),
+            params,
+            Amount::zero(),
+        )
     }
 
-    #[test]
-    fn serial_token_amount64_max_test() {
-        let amount = TokenAmountU64::from(u64::MAX);
-        let bytes = to_bytes(&amount);
-        assert_eq!(bytes, vec![255, 255, 255, 255, 255, 255, 255, 255, 255, 0b00000001])
+    /// Invoke the `tokenMetadata` entrypoint of the token contract.
+    pub fn token_metadata<State, T: IsTokenId>(
+        &self,
+        host: &impl HasHost<State>,
+        params: &TokenMetadataQueryParams<T>,
+    ) -> Result<TokenMetadataQueryResponse, CallContractError<Cis2Error<E>>> {
+        self.invoke_read_only(
+            host,
+            EntrypointName::new_unchecked("tokenMetadata"),
+            params,
+            Amount::zero(),
+        )
     }
 
-    #[test]
-    fn deserial_token_amount64_127_test() {
-        let amount: TokenAmountU64 = from_bytes(&[127]).expect("Failed to parse bytes");
-        assert_eq!(amount, TokenAmountU64::from(127))
+    /// Invoke the `supports` entrypoint of the token contract, as specified by
+    /// CIS-0.
+    pub fn supports<State>(
+        &self,
+        host: &impl HasHost<State>,
+        params: &SupportsQueryParams,
+    ) -> Result<SupportsQueryResponse, CallContractError<Cis2Error<E>>> {
+        self.invoke_read_only(
+            host,
+            EntrypointName::new_unchecked("supports"),
+            params,
+            Amount::zero(),
+        )
     }
+}
 
-    #[test]
-    fn deserial_token_amount64_max_test() {
-        let amount: TokenAmountU64 =
-            from_bytes(&[255, 255, 255, 255, 255, 255, 255, 255, 255, 0b00000001])
-                .expect("Failed to parse bytes");
-        assert_eq!(amount, TokenAmountU64::from(u64::MAX))
+/// The balances and operators of an address in a [`Cis2Ledger`].
+struct AddressState<T, A, S> {
+    /// The amount of tokens owned by the address, by token ID.
+    balances:  StateMap<T, A, S>,
+    /// The addresses which are operators of the address.
+    operators: StateSet<Address, S>,
+}
+
+impl<T, A, S: HasStateApi> AddressState<T, A, S> {
+    fn new(state_builder: &mut StateBuilder<S>) -> Self {
+        AddressState {
+            balances:  state_builder.new_map(),
+            operators: state_builder.new_set(),
+        }
     }
+}
 
-    #[test]
-    fn serial_token_amount32_127_test() {
-        let amount = TokenAmountU32::from(127);
-        let bytes = to_bytes(&amount);
-        assert_eq!(bytes, vec![127])
+impl<T, A, S> Serial for AddressState<T, A, S> {
+    fn serial<W: Write>(&self, out: &mut W) -> Result<(), W::Err> {
+        self.balances.serial(out)?;
+        self.operators.serial(out)
     }
+}
 
-    #[test]
-    fn serial_token_amount32_max_test() {
-        let amount = TokenAmountU32::from(u32::MAX);
-        let bytes = to_bytes(&amount);
-        assert_eq!(bytes, vec![255, 255, 255, 255, 0b00001111])
+impl<T, A, S: HasStateApi> DeserialWithState<S> for AddressState<T, A, S> {
+    fn deserial_with_state<R: Read>(state: &S, source: &mut R) -> ParseResult<Self> {
+        Ok(AddressState {
+            balances:  StateMap::deserial_with_state(state, source)?,
+            operators: StateSet::deserial_with_state(state, source)?,
+        })
     }
+}
 
-    #[test]
-    fn deserial_token_amount32_127_test() {
-        let amount: TokenAmountU32 = from_bytes(&[127]).expect("Failed to parse bytes");
-        assert_eq!(amount, TokenAmountU32::from(127))
+impl<T: Serialize, A: Serialize, S: HasStateApi> Deletable for AddressState<T, A, S> {
+    fn delete(self) {
+        self.balances.delete();
+        self.operators.delete();
     }
+}
 
-    #[test]
-    fn deserial_token_amount32_max_test() {
-        let amount: TokenAmountU32 =
-            from_bytes(&[255, 255, 255, 255, 0b00001111]).expect("Failed to parse bytes");
-        assert_eq!(amount, TokenAmountU32::from(u32::MAX))
+unsafe impl<T, A, S: HasStateApi> StateClone<S> for AddressState<T, A, S> {
+    unsafe fn clone_state(&self, cloned_state_api: &S) -> Self {
+        Self {
+            balances:  self.balances.clone_state(cloned_state_api),
+            operators: self.operators.clone_state(cloned_state_api),
+        }
     }
+}
 
-    #[test]
-    fn serial_token_amount16_127_test() {
-        let amount = TokenAmountU16::from(127);
-        let bytes = to_bytes(&amount);
-        assert_eq!(bytes, vec![127])
+/// The balances, operators and total supply of the tokens of a CIS2 contract,
+/// which is meant to be used as part of the contract state.
+///
+/// The ledger implements the standard behaviour of CIS2: it checks that the
+/// tokens exist, that the sender is authorized and that the owner has
+/// sufficient funds, returning the corresponding [`Cis2Error`] otherwise, and
+/// it logs the [`Cis2Event`]s of the changes. A token exists once it has been
+/// minted, even if the amount minted was zero. Who may mint and burn tokens,
+/// and the metadata of the tokens, is up to the contract.
+///
+/// Since the ledger cannot invoke other contracts while it is borrowed from
+/// the state, the receivers of a transfer are notified separately with
+/// [`Transfer::notify_receiver`].
+///
+/// Deleting the ledger with [`Deletable::delete`] removes the balances,
+/// operators and supply of all tokens from the state.
+///
+/// ```ignore
+/// #[derive(Serial, DeserialWithState, Deletable, StateClone)]
+/// #[concordium(state_parameter = "S")]
+/// struct State<S: HasStateApi> {
+///     ledger: Cis2Ledger<TokenIdU8, TokenAmountU64, S>,
+/// }
+///
+/// #[receive(
+///     contract = "token",
+///     name = "transfer",
+///     parameter = "TransferParams<TokenIdU8, TokenAmountU64>",
+///     error = "ContractError",
+///     enable_logger,
+///     mutable
+/// )]
+/// fn contract_transfer<S: HasStateApi>(
+///     ctx: &impl HasReceiveContext,
+///     host: &mut impl HasHost<State<S>, StateApiType = S>,
+///     logger: &mut impl HasLogger,
+/// ) -> Result<(), ContractError> {
+///     let TransferParams(transfers): TransferParams<TokenIdU8, TokenAmountU64> =
+///         ctx.parameter_cursor().get()?;
+///     for transfer in transfers {
+///         let (state, state_builder) = host.state_and_builder();
+///         state.ledger.transfer(&ctx.sender(), &transfer, state_builder, logger)?;
+///         transfer.notify_receiver(host)?;
+///     }
+///     Ok(())
+/// }
+/// ```
+pub struct Cis2Ledger<T, A, S> {
+    /// The state of each address which owns tokens or has operators.
+    addresses: StateMap<Address, AddressState<T, A, S>, S>,
+    /// The total supply of each token that exists.
+    supply:    StateMap<T, A, S>,
+}
+
+impl<T, A, S> Serial for Cis2Ledger<T, A, S> {
+    fn serial<W: Write>(&self, out: &mut W) -> Result<(), W::Err> {
+        self.addresses.serial(out)?;
+        self.supply.serial(out)
     }
+}
 
-    #[test]
-    fn serial_token_amount16_max_test() {
-        let amount = TokenAmountU16::from(u16::MAX);
-        let bytes = to_bytes(&amount);
-        assert_eq!(bytes, vec![255, 255, 0b00000011])
+impl<T, A, S: HasStateApi> DeserialWithState<S> for Cis2Ledger<T, A, S> {
+    fn deserial_with_state<R: Read>(state: &S, source: &mut R) -> ParseResult<Self> {
+        Ok(Cis2Ledger {
+            addresses: StateMap::deserial_with_state(state, source)?,
+            supply:    StateMap::deserial_with_state(state, source)?,
+        })
     }
+}
 
-    #[test]
-    fn deserial_token_amount16_127_test() {
-        let amount: TokenAmountU16 = from_bytes(&[127]).expect("Failed to parse bytes");
-        assert_eq!(amount, TokenAmountU16::from(127))
+impl<T: Serialize, A: Serialize, S: HasStateApi> Deletable for Cis2Ledger<T, A, S> {
+    fn delete(self) {
+        self.addresses.delete();
+        self.supply.delete();
     }
+}
 
-    #[test]
-    fn deserial_token_amount16_max_test() {
-        let amount: TokenAmountU16 =
-            from_bytes(&[255, 255, 0b00000011]).expect("Failed to parse bytes");
-        assert_eq!(amount, TokenAmountU16::from(u16::MAX))
+unsafe impl<T, A, S: HasStateApi> StateClone<S> for Cis2Ledger<T, A, S> {
+    unsafe fn clone_state(&self, cloned_state_api: &S) -> Self {
+        Self {
+            addresses: self.addresses.clone_state(cloned_state_api),
+            supply:    self.supply.clone_state(cloned_state_api),
+        }
     }
+}
 
-    #[test]
-    fn serial_token_amount8_127_test() {
-        let amount = TokenAmountU8::from(127);
-        let bytes = to_bytes(&amount);
-        assert_eq!(bytes, vec![127])
+impl<T, A, S> Cis2Ledger<T, A, S>
+where
+    T: IsTokenId + Clone,
+    A: TokenAmountArithmetic,
+    S: HasStateApi,
+{
+    /// Create a new ledger without any tokens.
+    pub fn new(state_builder: &mut StateBuilder<S>) -> Self {
+        Cis2Ledger {
+            addresses: state_builder.new_map(),
+            supply:    state_builder.new_map(),
+        }
     }
 
-    #[test]
-    fn serial_token_amount8_max_test() {
-        let amount = TokenAmountU8::from(u8::MAX);
-        let bytes = to_bytes(&amount);
-        assert_eq!(bytes, vec![255, 0b00000001])
+    /// Return whether the token exists, i.e., has been minted.
+    pub fn contains_token(&self, token_id: &T) -> bool { self.supply.get(token_id).is_some() }
+
+    /// Get the total supply of the token, which is zero if the token does not
+    /// exist.
+    pub fn total_supply(&self, token_id: &T) -> A {
+        self.supply.get(token_id).map_or_else(A::zero, |supply| *supply)
     }
 
-    #[test]
-    fn deserial_token_amount8_127_test() {
-        let amount: TokenAmountU8 = from_bytes(&[127]).expect("Failed to parse bytes");
-        assert_eq!(amount, TokenAmountU8::from(127))
+    /// Get the balance of the token owned by the address, which is zero if
+    /// the token does not exist.
+    pub fn balance(&self, token_id: &T, address: &Address) -> A {
+        self.addresses
+            .get(address)
+            .and_then(|address_state| address_state.balances.get(token_id).map(|balance| *balance))
+            .unwrap_or_else(A::zero)
     }
 
-    #[test]
-    fn deserial_token_amount8_max_test() {
-        let amount: TokenAmountU8 = from_bytes(&[255, 0b00000001]).expect("Failed to parse bytes");
-        assert_eq!(amount, TokenAmountU8::from(u8::MAX))
+    /// Return whether `address` is an operator of `owner`.
+    pub fn is_operator(&self, owner: &Address, address: &Address) -> bool {
+        self.addresses
+            .get(owner)
+            .map_or(false, |address_state| address_state.operators.contains(address))
+    }
+
+    /// Return whether the `sender` is allowed to transfer the tokens of the
+    /// `owner`, i.e., is the owner or an operator of the owner.
+    pub fn is_authorized(&self, sender: &Address, owner: &Address) -> bool {
+        sender == owner || self.is_operator(owner, sender)
+    }
+
+    /// Mint an amount of the token to the `owner`, creating the token if it
+    /// does not exist, and log a [`Cis2Event::Mint`]. Returns
+    /// [`TokenAmountOverflowError`] if the total supply of the token would
+    /// overflow. The contract must check that the sender is allowed to mint.
+    pub fn mint<E>(
+        &mut self,
+        token_id: T,
+        amount: A,
+        owner: Address,
+        state_builder: &mut StateBuilder<S>,
+        logger: &mut impl HasLogger,
+    ) -> Result<(), Cis2Error<E>>
+    where
+        E: From<LogError> + From<TokenAmountOverflowError>, {
+        let supply =
+            self.total_supply(&token_id).checked_add(amount).ok_or(TokenAmountOverflowError)?;
+        self.supply.insert(token_id.clone(), supply);
+        self.credit(&token_id, amount, owner, state_builder);
+        logger.log(&Cis2Event::Mint(MintEvent {
+            token_id,
+            amount,
+            owner,
+        }))?;
+        Ok(())
+    }
+
+    /// Burn an amount of the token owned by the `owner` and log a
+    /// [`Cis2Event::Burn`]. Returns [`Cis2Error::InvalidTokenId`] if the token
+    /// does not exist and [`Cis2Error::InsufficientFunds`] if the owner has
+    /// fewer tokens than the amount. The contract must check that the sender
+    /// is allowed to burn, e.g., using [`is_authorized`](Self::is_authorized).
+    pub fn burn<E>(
+        &mut self,
+        token_id: T,
+        amount: A,
+        owner: Address,
+        logger: &mut impl HasLogger,
+    ) -> Result<(), Cis2Error<E>>
+    where
+        E: From<LogError>, {
+        ensure!(self.contains_token(&token_id), Cis2Error::InvalidTokenId);
+        self.debit(&token_id, amount, &owner)?;
+        // Cannot underflow, since the balance of the owner is at most the total supply.
+        let supply = self.total_supply(&token_id).saturating_sub(amount);
+        self.supply.insert(token_id.clone(), supply);
+        logger.log(&Cis2Event::Burn(BurnEvent {
+            token_id,
+            amount,
+            owner,
+        }))?;
+        Ok(())
+    }
+
+    /// Make the transfer on behalf of the `sender` and log a
+    /// [`Cis2Event::Transfer`]. Returns [`Cis2Error::InvalidTokenId`] if the
+    /// token does not exist, [`Cis2Error::Unauthorized`] if the sender is
+    /// neither the owner of the tokens nor an operator of the owner, and
+    /// [`Cis2Error::InsufficientFunds`] if the owner has fewer tokens than the
+    /// amount.
+    ///
+    /// The receiver is not notified, which must be done afterwards with
+    /// [`Transfer::notify_receiver`].
+    pub fn transfer<E>(
+        &mut self,
+        sender: &Address,
+        transfer: &Transfer<T, A>,
+        state_builder: &mut StateBuilder<S>,
+        logger: &mut impl HasLogger,
+    ) -> Result<(), Cis2Error<E>>
+    where
+        E: From<LogError>, {
+        ensure!(self.contains_token(&transfer.token_id), Cis2Error::InvalidTokenId);
+        ensure!(self.is_authorized(sender, &transfer.from), Cis2Error::Unauthorized);
+        self.transfer_authorized(transfer, state_builder, logger)
+    }
+
+    /// Make the transfer on behalf of the `sender` like
+    /// [`transfer`](Self::transfer), except that if the sender is neither the
+    /// owner of the tokens nor an operator of the owner, the amount is spent
+    /// from the allowance of the sender using [`Allowances::spend`], which
+    /// returns [`Cis2Error::Unauthorized`] if the allowance is insufficient.
+    pub fn transfer_with_allowance<E>(
+        &mut self,
+        sender: &Address,
+        transfer: &Transfer<T, A>,
+        allowances: &mut Allowances<T, A, S>,
+        now: Timestamp,
+        state_builder: &mut StateBuilder<S>,
+        logger: &mut impl HasLogger,
+    ) -> Result<(), Cis2Error<E>>
+    where
+        E: From<LogError>, {
+        ensure!(self.contains_token(&transfer.token_id), Cis2Error::InvalidTokenId);
+        if !self.is_authorized(sender, &transfer.from) {
+            allowances.spend(
+                &transfer.token_id,
+                transfer.from,
+                *sender,
+                transfer.amount,
+                now,
+                logger,
+            )?;
+        }
+        self.transfer_authorized(transfer, state_builder, logger)
+    }
+
+    /// Add or remove an operator of the `owner`, which is the sender of the
+    /// update, and log a [`Cis2Event::UpdateOperator`].
+    pub fn update_operator<E>(
+        &mut self,
+        owner: Address,
+        update: UpdateOperator,
+        state_builder: &mut StateBuilder<S>,
+        logger: &mut impl HasLogger,
+    ) -> Result<(), Cis2Error<E>>
+    where
+        E: From<LogError>, {
+        match update.update {
+            OperatorUpdate::Add => {
+                self.addresses
+                    .entry(owner)
+                    .or_insert_with(|| AddressState::new(state_builder))
+                    .modify(|address_state| address_state.operators.insert(update.operator));
+            }
+            OperatorUpdate::Remove => {
+                if let Some(mut address_state) = self.addresses.get_mut(&owner) {
+                    address_state.operators.remove(&update.operator);
+                }
+            }
+        }
+        logger.log(&Cis2Event::<T, A>::UpdateOperator(UpdateOperatorEvent {
+            update: update.update,
+            owner,
+            operator: update.operator,
+        }))?;
+        Ok(())
+    }
+
+    /// Answer the queries of the `balanceOf` function of CIS2. Returns
+    /// [`Cis2Error::InvalidTokenId`] if any of the tokens does not exist.
+    pub fn balance_of<E>(
+        &self,
+        params: &BalanceOfQueryParams<T>,
+    ) -> Result<BalanceOfQueryResponse<A>, Cis2Error<E>> {
+        let mut response = Vec::with_capacity(params.queries.len());
+        for query in params.queries.iter() {
+            ensure!(self.contains_token(&query.token_id), Cis2Error::InvalidTokenId);
+            response.push(self.balance(&query.token_id, &query.address));
+        }
+        Ok(BalanceOfQueryResponse::from(response))
+    }
+
+    /// Answer the queries of the `operatorOf` function of CIS2.
+    pub fn operator_of(&self, params: &OperatorOfQueryParams) -> OperatorOfQueryResponse {
+        let response = params
+            .queries
+            .iter()
+            .map(|query| self.is_operator(&query.owner, &query.address))
+            .collect::<Vec<_>>();
+        OperatorOfQueryResponse::from(response)
+    }
+
+    /// Make the transfer of an existing token, which the sender is authorized
+    /// to make, and log it.
+    fn transfer_authorized<E>(
+        &mut self,
+        transfer: &Transfer<T, A>,
+        state_builder: &mut StateBuilder<S>,
+        logger: &mut impl HasLogger,
+    ) -> Result<(), Cis2Error<E>>
+    where
+        E: From<LogError>, {
+        self.debit(&transfer.token_id, transfer.amount, &transfer.from)?;
+        let to = transfer.to.address();
+        self.credit(&transfer.token_id, transfer.amount, to, state_builder);
+        logger.log(&Cis2Event::Transfer(TransferEvent {
+            token_id: transfer.token_id.clone(),
+            amount: transfer.amount,
+            from: transfer.from,
+            to,
+        }))?;
+        Ok(())
+    }
+
+    /// Subtract the amount from the balance of the token owned by the
+    /// address.
+    fn debit<E>(&mut self, token_id: &T, amount: A, address: &Address) -> Result<(), Cis2Error<E>> {
+        if amount == A::zero() {
+            return Ok(());
+        }
+        let address_state = self.addresses.get(address).ok_or(Cis2Error::InsufficientFunds)?;
+        let mut balance =
+            address_state.balances.get_mut(token_id).ok_or(Cis2Error::InsufficientFunds)?;
+        *balance = balance.sub_or_insufficient_funds(amount)?;
+        Ok(())
+    }
+
+    /// Add the amount to the balance of the token owned by the address.
+    fn credit(
+        &mut self,
+        token_id: &T,
+        amount: A,
+        address: Address,
+        state_builder: &mut StateBuilder<S>,
+    ) {
+        self.addresses.entry(address).or_insert_with(|| AddressState::new(state_builder)).modify(
+            |address_state| {
+                address_state.balances.entry(token_id.clone()).or_insert_with(A::zero).modify(
+                    // Cannot overflow, since the balance is at most the total supply.
+                    |balance| *balance = balance.saturating_add(amount),
+                )
+            },
+        );
+    }
+}
+
+/// An allowance of a spender to transfer up to an amount of a token on behalf
+/// of the owner of the token, until the allowance expires.
+// Note: The order of the fields cannot be changed, since it determines the
+// serialization.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub struct Allowance<A: IsTokenAmount> {
+    /// The amount of tokens the spender may still transfer.
+    pub amount: A,
+    /// The time after which the allowance can no longer be spent.
+    pub expiry: Timestamp,
+}
+
+impl<A: IsTokenAmount> schema::SchemaType for Allowance<A> {
+    fn get_type() -> schema::Type {
+        schema::Type::Struct(schema::Fields::Named(vec![
+            (String::from("amount"), A::get_type()),
+            (String::from("expiry"), Timestamp::get_type()),
+        ]))
+    }
+}
+
+/// An approval of a spender to transfer up to an amount of a token of the
+/// sender until the expiry, replacing any previous allowance of the spender.
+// Note: The order of the fields cannot be changed, since it determines the
+// serialization.
+#[derive(Debug, Serialize)]
+pub struct Approve<T: IsTokenId, A: IsTokenAmount> {
+    /// The ID of the token.
+    pub token_id: T,
+    /// The address which may transfer the tokens.
+    pub spender:  Address,
+    /// The amount of tokens the spender may transfer.
+    pub amount:   A,
+    /// The time after which the allowance can no longer be spent.
+    pub expiry:   Timestamp,
+}
+
+impl<T: IsTokenId, A: IsTokenAmount> schema::SchemaType for Approve<T, A> {
+    fn get_type() -> schema::Type {
+        schema::Type::Struct(schema::Fields::Named(vec![
+            (String::from("token_id"), T::get_type()),
+            (String::from("spender"), Address::get_type()),
+            (String::from("amount"), A::get_type()),
+            (String::from("expiry"), Timestamp::get_type()),
+        ]))
+    }
+}
+
+/// The parameter type for the contract function `approve` of the allowances
+/// extension.
+#[derive(Debug, Serialize)]
+pub struct ApproveParams<T: IsTokenId, A: IsTokenAmount>(
+    #[concordium(size_length = 2)] pub Vec<Approve<T, A>>,
+);
+
+impl<T: IsTokenId, A: IsTokenAmount> schema::SchemaType for ApproveParams<T, A> {
+    fn get_type() -> schema::Type {
+        schema::Type::List(schema::SizeLength::U16, Box::new(Approve::<T, A>::get_type()))
+    }
+}
+
+impl<T: IsTokenId, A: IsTokenAmount> From<Vec<Approve<T, A>>> for ApproveParams<T, A> {
+    fn from(approvals: Vec<Approve<T, A>>) -> Self { ApproveParams(approvals) }
+}
+
+impl<T: IsTokenId, A: IsTokenAmount> AsRef<[Approve<T, A>]> for ApproveParams<T, A> {
+    fn as_ref(&self) -> &[Approve<T, A>] { &self.0 }
+}
+
+/// A decrease of the allowance of a spender to transfer a token of the
+/// sender. The allowance is decreased to zero if it is less than the amount.
+// Note: The order of the fields cannot be changed, since it determines the
+// serialization.
+#[derive(Debug, Serialize)]
+pub struct DecreaseAllowance<T: IsTokenId, A: IsTokenAmount> {
+    /// The ID of the token.
+    pub token_id: T,
+    /// The address whose allowance is decreased.
+    pub spender:  Address,
+    /// The amount to decrease the allowance by.
+    pub amount:   A,
+}
+
+impl<T: IsTokenId, A: IsTokenAmount> schema::SchemaType for DecreaseAllowance<T, A> {
+    fn get_type() -> schema::Type {
+        schema::Type::Struct(schema::Fields::Named(vec![
+            (String::from("token_id"), T::get_type()),
+            (String::from("spender"), Address::get_type()),
+            (String::from("amount"), A::get_type()),
+        ]))
+    }
+}
+
+/// The parameter type for the contract function `decreaseAllowance` of the
+/// allowances extension.
+#[derive(Debug, Serialize)]
+pub struct DecreaseAllowanceParams<T: IsTokenId, A: IsTokenAmount>(
+    #[concordium(size_length = 2)] pub Vec<DecreaseAllowance<T, A>>,
+);
+
+impl<T: IsTokenId, A: IsTokenAmount> schema::SchemaType for DecreaseAllowanceParams<T, A> {
+    fn get_type() -> schema::Type {
+        schema::Type::List(schema::SizeLength::U16, Box::new(DecreaseAllowance::<T, A>::get_type()))
+    }
+}
+
+impl<T: IsTokenId, A: IsTokenAmount> From<Vec<DecreaseAllowance<T, A>>>
+    for DecreaseAllowanceParams<T, A>
+{
+    fn from(decreases: Vec<DecreaseAllowance<T, A>>) -> Self { DecreaseAllowanceParams(decreases) }
+}
+
+impl<T: IsTokenId, A: IsTokenAmount> AsRef<[DecreaseAllowance<T, A>]>
+    for DecreaseAllowanceParams<T, A>
+{
+    fn as_ref(&self) -> &[DecreaseAllowance<T, A>] { &self.0 }
+}
+
+/// A query for the allowance of a spender to transfer a token of an owner.
+// Note: The order of the fields cannot be changed, since it determines the
+// serialization.
+#[derive(Debug, Serialize)]
+pub struct AllowanceQuery<T: IsTokenId> {
+    /// The ID of the token.
+    pub token_id: T,
+    /// The owner of the tokens.
+    pub owner:    Address,
+    /// The address whose allowance to query.
+    pub spender:  Address,
+}
+
+impl<T: IsTokenId> schema::SchemaType for AllowanceQuery<T> {
+    fn get_type() -> schema::Type {
+        schema::Type::Struct(schema::Fields::Named(vec![
+            (String::from("token_id"), T::get_type()),
+            (String::from("owner"), Address::get_type()),
+            (String::from("spender"), Address::get_type()),
+        ]))
+    }
+}
+
+/// The parameter type for the contract function `allowance` of the
+/// allowances extension.
+#[derive(Debug, Serialize)]
+pub struct AllowanceQueryParams<T: IsTokenId> {
+    /// List of allowance queries.
+    #[concordium(size_length = 2)]
+    pub queries: Vec<AllowanceQuery<T>>,
+}
+
+impl<T: IsTokenId> schema::SchemaType for AllowanceQueryParams<T> {
+    fn get_type() -> schema::Type {
+        schema::Type::Struct(schema::Fields::Named(vec![(
+            String::from("queries"),
+            schema::Type::List(schema::SizeLength::U16, Box::new(AllowanceQuery::<T>::get_type())),
+        )]))
+    }
+}
+
+/// The response which is sent back when calling the contract function
+/// `allowance` of the allowances extension. It consists of the list of
+/// remaining allowances, which are zero if expired, in the same order as the
+/// queries.
+#[derive(Debug, Serialize)]
+pub struct AllowanceQueryResponse<A: IsTokenAmount>(#[concordium(size_length = 2)] pub Vec<A>);
+
+impl<A: IsTokenAmount> schema::SchemaType for AllowanceQueryResponse<A> {
+    fn get_type() -> schema::Type {
+        schema::Type::List(schema::SizeLength::U16, Box::new(A::get_type()))
+    }
+}
+
+impl<A: IsTokenAmount> From<Vec<A>> for AllowanceQueryResponse<A> {
+    fn from(results: Vec<A>) -> Self { AllowanceQueryResponse(results) }
+}
+
+impl<A: IsTokenAmount> AsRef<[A]> for AllowanceQueryResponse<A> {
+    fn as_ref(&self) -> &[A] { &self.0 }
+}
+
+/// An untagged event of an update to the allowance of a spender, either by
+/// the owner or by the spender transferring tokens. For a tagged version, use
+/// `Cis2AllowanceEvent`.
+// Note: The order of the fields cannot be changed, since it determines the
+// serialization.
+#[derive(Debug, Serialize, SchemaType)]
+pub struct UpdateAllowanceEvent<T: IsTokenId, A: IsTokenAmount> {
+    /// The ID of the token.
+    pub token_id: T,
+    /// The owner of the tokens.
+    pub owner:    Address,
+    /// The address whose allowance is updated.
+    pub spender:  Address,
+    /// The allowance after the update.
+    pub amount:   A,
+    /// The time after which the allowance can no longer be spent.
+    pub expiry:   Timestamp,
+}
+
+/// Tagged event of the allowances extension to be serialized for the event
+/// log.
+#[derive(Debug)]
+pub enum Cis2AllowanceEvent<T: IsTokenId, A: IsTokenAmount> {
+    /// An update to the allowance of a spender.
+    UpdateAllowance(UpdateAllowanceEvent<T, A>),
+}
+
+impl<T: IsTokenId, A: IsTokenAmount> schema::SchemaType for Cis2AllowanceEvent<T, A> {
+    fn get_type() -> schema::Type {
+        let mut event_map = BTreeMap::new();
+        event_map.insert(
+            UPDATE_ALLOWANCE_EVENT_TAG,
+            (
+                "UpdateAllowance".to_string(),
+                schema::Fields::Named(vec![
+                    (String::from("token_id"), T::get_type()),
+                    (String::from("owner"), Address::get_type()),
+                    (String::from("spender"), Address::get_type()),
+                    (String::from("amount"), A::get_type()),
+                    (String::from("expiry"), Timestamp::get_type()),
+                ]),
+            ),
+        );
+        schema::Type::TaggedEnum(event_map)
+    }
+}
+
+impl<T: IsTokenId, A: IsTokenAmount> Serial for Cis2AllowanceEvent<T, A> {
+    fn serial<W: Write>(&self, out: &mut W) -> Result<(), W::Err> {
+        match self {
+            Cis2AllowanceEvent::UpdateAllowance(event) => {
+                out.write_u8(UPDATE_ALLOWANCE_EVENT_TAG)?;
+                event.serial(out)
+            }
+        }
+    }
+}
+
+impl<T: IsTokenId, A: IsTokenAmount> Deserial for Cis2AllowanceEvent<T, A> {
+    fn deserial<R: Read>(source: &mut R) -> ParseResult<Self> {
+        let tag = source.read_u8()?;
+        Self::deserial_tagged(tag, source)
+    }
+}
+
+/// Allows flattening `Cis2AllowanceEvent` into the event type of a contract
+/// deriving `Event`.
+impl<T: IsTokenId, A: IsTokenAmount> TaggedEvent for Cis2AllowanceEvent<T, A> {
+    const TAGS: EventTags = EventTags {
+        tags:      &[UPDATE_ALLOWANCE_EVENT_TAG],
+        flattened: &[],
+    };
+
+    fn deserial_tagged<R: Read>(tag: u8, source: &mut R) -> ParseResult<Self> {
+        match tag {
+            UPDATE_ALLOWANCE_EVENT_TAG => UpdateAllowanceEvent::<T, A>::deserial(source)
+                .map(Cis2AllowanceEvent::UpdateAllowance),
+            _ => Err(ParseError::default()),
+        }
+    }
+}
+
+/// The key of an allowance in [`Allowances`].
+#[derive(Serialize)]
+struct AllowanceKey<T: IsTokenId> {
+    token_id: T,
+    owner:    Address,
+    spender:  Address,
+}
+
+/// The allowances of spenders to transfer tokens on behalf of their owners,
+/// which is meant to be used as part of the state of a contract supporting the
+/// allowances extension of CIS2. Such a contract should report support of
+/// [`CIS2_ALLOWANCES_STANDARD_IDENTIFIER`] in the CIS-0 function `supports`.
+///
+/// Unlike operators, which may transfer any amount of any token of the owner,
+/// an allowance is for an amount of a single token and expires. Transfers
+/// spending allowances are made with [`Cis2Ledger::transfer_with_allowance`].
+/// Expired allowances count as zero, and the time to check expiry against,
+/// usually the slot time, is passed to the methods as `now`. Allowances that
+/// are zero or expired are deleted when they are updated, so they do not take
+/// up space in the state.
+///
+/// Deleting the allowances with [`Deletable::delete`] also removes expired
+/// allowances that were never updated.
+pub struct Allowances<T: IsTokenId, A: IsTokenAmount, S> {
+    /// The allowance of each spender, by token and owner.
+    allowances: StateMap<AllowanceKey<T>, Allowance<A>, S>,
+}
+
+impl<T: IsTokenId, A: IsTokenAmount, S> Serial for Allowances<T, A, S> {
+    fn serial<W: Write>(&self, out: &mut W) -> Result<(), W::Err> { self.allowances.serial(out) }
+}
+
+impl<T: IsTokenId, A: IsTokenAmount, S: HasStateApi> DeserialWithState<S> for Allowances<T, A, S> {
+    fn deserial_with_state<R: Read>(state: &S, source: &mut R) -> ParseResult<Self> {
+        Ok(Allowances {
+            allowances: StateMap::deserial_with_state(state, source)?,
+        })
+    }
+}
+
+impl<T: IsTokenId, A: IsTokenAmount, S: HasStateApi> Deletable for Allowances<T, A, S> {
+    fn delete(self) { self.allowances.delete(); }
+}
+
+unsafe impl<T: IsTokenId, A: IsTokenAmount, S: HasStateApi> StateClone<S> for Allowances<T, A, S> {
+    unsafe fn clone_state(&self, cloned_state_api: &S) -> Self {
+        Self {
+            allowances: self.allowances.clone_state(cloned_state_api),
+        }
+    }
+}
+
+impl<T, A, S> Allowances<T, A, S>
+where
+    T: IsTokenId + Clone,
+    A: TokenAmountArithmetic,
+    S: HasStateApi,
+{
+    /// Create a new collection without any allowances.
+    pub fn new(state_builder: &mut StateBuilder<S>) -> Self {
+        Allowances {
+            allowances: state_builder.new_map(),
+        }
+    }
+
+    /// Get the allowance of the `spender` to transfer the token of the
+    /// `owner`, which is zero if there is none or it has expired.
+    pub fn allowance(&self, token_id: &T, owner: &Address, spender: &Address, now: Timestamp) -> A {
+        self.get(token_id, owner, spender, now).map_or_else(A::zero, |allowance| allowance.amount)
+    }
+
+    /// Set the allowance of the `spender` to transfer the token of the
+    /// `owner`, which is the sender of the approval, and log a
+    /// [`Cis2AllowanceEvent::UpdateAllowance`]. Returns
+    /// [`Cis2Error::InvalidTokenId`] if the token does not exist in the
+    /// `ledger`.
+    pub fn approve<E>(
+        &mut self,
+        ledger: &Cis2Ledger<T, A, S>,
+        owner: Address,
+        approve: Approve<T, A>,
+        now: Timestamp,
+        logger: &mut impl HasLogger,
+    ) -> Result<(), Cis2Error<E>>
+    where
+        E: From<LogError>, {
+        ensure!(ledger.contains_token(&approve.token_id), Cis2Error::InvalidTokenId);
+        let allowance = Allowance {
+            amount: approve.amount,
+            expiry: approve.expiry,
+        };
+        self.set(approve.token_id, owner, approve.spender, allowance, now, logger)
+    }
+
+    /// Decrease the allowance of the `spender` to transfer the token of the
+    /// `owner`, which is the sender of the decrease, keeping its expiry, and
+    /// log a [`Cis2AllowanceEvent::UpdateAllowance`]. Nothing happens if there
+    /// is no allowance or it has expired.
+    pub fn decrease<E>(
+        &mut self,
+        owner: Address,
+        decrease: DecreaseAllowance<T, A>,
+        now: Timestamp,
+        logger: &mut impl HasLogger,
+    ) -> Result<(), Cis2Error<E>>
+    where
+        E: From<LogError>, {
+        if let Some(allowance) = self.get_or_prune(&decrease.token_id, owner, decrease.spender, now)
+        {
+            let allowance = Allowance {
+                amount: allowance.amount.saturating_sub(decrease.amount),
+                expiry: allowance.expiry,
+            };
+            self.set(decrease.token_id, owner, decrease.spender, allowance, now, logger)?;
+        }
+        Ok(())
+    }
+
+    /// Spend an amount of the allowance of the `spender` to transfer the token
+    /// of the `owner` and log a [`Cis2AllowanceEvent::UpdateAllowance`].
+    /// Returns [`Cis2Error::Unauthorized`] if the spender has no allowance, it
+    /// has expired, or it is less than the amount.
+    pub fn spend<E>(
+        &mut self,
+        token_id: &T,
+        owner: Address,
+        spender: Address,
+        amount: A,
+        now: Timestamp,
+        logger: &mut impl HasLogger,
+    ) -> Result<(), Cis2Error<E>>
+    where
+        E: From<LogError>, {
+        let allowance =
+            self.get_or_prune(token_id, owner, spender, now).ok_or(Cis2Error::Unauthorized)?;
+        let amount = allowance.amount.checked_sub(amount).ok_or(Cis2Error::Unauthorized)?;
+        let allowance = Allowance {
+            amount,
+            expiry: allowance.expiry,
+        };
+        self.set(token_id.clone(), owner, spender, allowance, now, logger)
+    }
+
+    /// Answer the queries of the `allowance` function of the allowances
+    /// extension.
+    pub fn allowance_of(
+        &self,
+        params: &AllowanceQueryParams<T>,
+        now: Timestamp,
+    ) -> AllowanceQueryResponse<A> {
+        let response = params
+            .queries
+            .iter()
+            .map(|query| self.allowance(&query.token_id, &query.owner, &query.spender, now))
+            .collect::<Vec<_>>();
+        AllowanceQueryResponse::from(response)
+    }
+
+    /// Get the allowance, unless there is none or it has expired.
+    fn get(
+        &self,
+        token_id: &T,
+        owner: &Address,
+        spender: &Address,
+        now: Timestamp,
+    ) -> Option<Allowance<A>> {
+        let key = AllowanceKey {
+            token_id: token_id.clone(),
+            owner:    *owner,
+            spender:  *spender,
+        };
+        self.allowances
+            .get(&key)
+            .map(|allowance| *allowance)
+            .filter(|allowance| allowance.expiry >= now)
+    }
+
+    /// Get the allowance like [`get`](Self::get), but remove it if it has
+    /// expired.
+    fn get_or_prune(
+        &mut self,
+        token_id: &T,
+        owner: Address,
+        spender: Address,
+        now: Timestamp,
+    ) -> Option<Allowance<A>> {
+        let key = AllowanceKey {
+            token_id: token_id.clone(),
+            owner,
+            spender,
+        };
+        let allowance = *self.allowances.get(&key)?;
+        if allowance.expiry < now {
+            self.allowances.remove(&key);
+            None
+        } else {
+            Some(allowance)
+        }
+    }
+
+    /// Set the allowance, removing it if it is zero or has expired, and log the
+    /// update.
+    fn set<E>(
+        &mut self,
+        token_id: T,
+        owner: Address,
+        spender: Address,
+        allowance: Allowance<A>,
+        now: Timestamp,
+        logger: &mut impl HasLogger,
+    ) -> Result<(), Cis2Error<E>>
+    where
+        E: From<LogError>, {
+        let key = AllowanceKey {
+            token_id: token_id.clone(),
+            owner,
+            spender,
+        };
+        if allowance.amount == A::zero() || allowance.expiry < now {
+            self.allowances.remove(&key);
+        } else {
+            self.allowances.insert(key, allowance);
+        }
+        logger.log(&Cis2AllowanceEvent::UpdateAllowance(UpdateAllowanceEvent {
+            token_id,
+            owner,
+            spender,
+            amount: allowance.amount,
+            expiry: allowance.expiry,
+        }))?;
+        Ok(())
+    }
+}
+
+/// The maximal royalty in basis points, which is 100% of the sale price.
+pub const MAX_ROYALTY_BASIS_POINTS: u16 = 10_000;
+
+/// A royalty to be paid to the receiver when a token is sold, as a fraction
+/// of the sale price in basis points, i.e., hundredths of a percent. The
+/// royalty is at most [`MAX_ROYALTY_BASIS_POINTS`], which is checked when it
+/// is constructed with [`Royalty::new`] or parsed.
+// Note: The order of the fields cannot be changed, since it determines the
+// serialization.
+#[derive(Debug, Serial, SchemaType, Clone, Copy, PartialEq, Eq)]
+pub struct Royalty {
+    /// The account to pay the royalty to.
+    receiver:     AccountAddress,
+    /// The royalty in basis points, at most [`MAX_ROYALTY_BASIS_POINTS`].
+    basis_points: u16,
+}
+
+impl Royalty {
+    /// Construct a royalty paid to the receiver. Returns
+    /// [`InvalidRoyaltyError`] if the royalty exceeds
+    /// [`MAX_ROYALTY_BASIS_POINTS`].
+    pub fn new(receiver: AccountAddress, basis_points: u16) -> Result<Self, InvalidRoyaltyError> {
+        ensure!(basis_points <= MAX_ROYALTY_BASIS_POINTS, InvalidRoyaltyError);
+        Ok(Royalty {
+            receiver,
+            basis_points,
+        })
+    }
+
+    /// The account to pay the royalty to.
+    pub fn receiver(&self) -> AccountAddress { self.receiver }
+
+    /// The royalty in basis points, at most [`MAX_ROYALTY_BASIS_POINTS`].
+    pub fn basis_points(&self) -> u16 { self.basis_points }
+
+    /// Compute the royalty to pay for a sale of the token at the price,
+    /// rounded down.
+    pub fn royalty_info(&self, sale_price: Amount) -> RoyaltyInfo {
+        let amount = u128::from(sale_price.micro_ccd) * u128::from(self.basis_points)
+            / u128::from(MAX_ROYALTY_BASIS_POINTS);
+        RoyaltyInfo {
+            receiver: self.receiver,
+            // Cannot fail, since the royalty is at most the sale price.
+            amount:   Amount::from_micro_ccd(u64::try_from(amount).unwrap_abort()),
+        }
+    }
+}
+
+impl Deserial for Royalty {
+    fn deserial<R: Read>(source: &mut R) -> ParseResult<Self> {
+        let receiver = AccountAddress::deserial(source)?;
+        let basis_points = u16::deserial(source)?;
+        Royalty::new(receiver, basis_points).map_err(|_| ParseError::default())
+    }
+}
+
+/// Error for when a royalty exceeds [`MAX_ROYALTY_BASIS_POINTS`]. Returned by
+/// [`Royalty::new`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidRoyaltyError;
+
+impl<X> From<InvalidRoyaltyError> for Cis2Error<X>
+where
+    X: From<InvalidRoyaltyError>,
+{
+    #[inline]
+    /// Converts the error by wrapping it in [Self::Custom].
+    fn from(err: InvalidRoyaltyError) -> Self { Cis2Error::Custom(X::from(err)) }
+}
+
+/// A query for the royalty to pay for a sale of a token at a price.
+// Note: The order of the fields cannot be changed, since it determines the
+// serialization.
+#[derive(Debug, Serialize)]
+pub struct RoyaltyInfoQuery<T: IsTokenId> {
+    /// The ID of the token being sold.
+    pub token_id:   T,
+    /// The price the token is sold for.
+    pub sale_price: Amount,
+}
+
+impl<T: IsTokenId> schema::SchemaType for RoyaltyInfoQuery<T> {
+    fn get_type() -> schema::Type {
+        schema::Type::Struct(schema::Fields::Named(vec![
+            (String::from("token_id"), T::get_type()),
+            (String::from("sale_price"), Amount::get_type()),
+        ]))
+    }
+}
+
+/// The parameter type for the contract function `royaltyInfo` of the
+/// royalties extension.
+#[derive(Debug, Serialize)]
+pub struct RoyaltyInfoQueryParams<T: IsTokenId> {
+    /// List of royalty queries.
+    #[concordium(size_length = 2)]
+    pub queries: Vec<RoyaltyInfoQuery<T>>,
+}
+
+impl<T: IsTokenId> schema::SchemaType for RoyaltyInfoQueryParams<T> {
+    fn get_type() -> schema::Type {
+        schema::Type::Struct(schema::Fields::Named(vec![(
+            String::from("queries"),
+            schema::Type::List(
+                schema::SizeLength::U16,
+                Box::new(RoyaltyInfoQuery::<T>::get_type()),
+            ),
+        )]))
+    }
+}
+
+/// The royalty to pay for a sale of a token.
+// Note: The order of the fields cannot be changed, since it determines the
+// serialization.
+#[derive(Debug, Serialize, SchemaType, Clone, Copy, PartialEq, Eq)]
+pub struct RoyaltyInfo {
+    /// The account to pay the royalty to.
+    pub receiver: AccountAddress,
+    /// The amount to pay.
+    pub amount:   Amount,
+}
+
+/// The response which is sent back when calling the contract function
+/// `royaltyInfo` of the royalties extension. It consists of the list of
+/// royalties, which are `None` for tokens without a royalty, in the same order
+/// as the queries.
+#[derive(Debug, Serialize)]
+pub struct RoyaltyInfoQueryResponse(#[concordium(size_length = 2)] pub Vec<Option<RoyaltyInfo>>);
+
+impl schema::SchemaType for RoyaltyInfoQueryResponse {
+    fn get_type() -> schema::Type {
+        schema::Type::List(schema::SizeLength::U16, Box::new(Option::<RoyaltyInfo>::get_type()))
+    }
+}
+
+impl From<Vec<Option<RoyaltyInfo>>> for RoyaltyInfoQueryResponse {
+    fn from(results: Vec<Option<RoyaltyInfo>>) -> Self { RoyaltyInfoQueryResponse(results) }
+}
+
+impl AsRef<[Option<RoyaltyInfo>]> for RoyaltyInfoQueryResponse {
+    fn as_ref(&self) -> &[Option<RoyaltyInfo>] { &self.0 }
+}
+
+/// The royalties of the tokens of a contract, which is meant to be used as
+/// part of the state of a contract supporting the royalties extension of
+/// CIS2. Such a contract should report support of
+/// [`CIS2_ROYALTIES_STANDARD_IDENTIFIER`] in the CIS-0 function `supports`.
+///
+/// A token has either its own royalty or the default royalty, if any. The
+/// contract must check that the sender is allowed to set royalties, and that
+/// the queried tokens exist.
+///
+/// Deleting the royalties with [`Deletable::delete`] removes the royalties of
+/// the individual tokens from the state.
+pub struct Royalties<T, S> {
+    /// The royalty of the tokens without their own royalty.
+    default: Option<Royalty>,
+    /// The royalty of each token with its own royalty.
+    tokens:  StateMap<T, Royalty, S>,
+}
+
+impl<T, S> Serial for Royalties<T, S> {
+    fn serial<W: Write>(&self, out: &mut W) -> Result<(), W::Err> {
+        self.default.serial(out)?;
+        self.tokens.serial(out)
+    }
+}
+
+impl<T, S: HasStateApi> DeserialWithState<S> for Royalties<T, S> {
+    fn deserial_with_state<R: Read>(state: &S, source: &mut R) -> ParseResult<Self> {
+        Ok(Royalties {
+            default: Option::deserial(source)?,
+            tokens:  StateMap::deserial_with_state(state, source)?,
+        })
+    }
+}
+
+impl<T: Serialize, S: HasStateApi> Deletable for Royalties<T, S> {
+    fn delete(self) { self.tokens.delete(); }
+}
+
+unsafe impl<T, S: HasStateApi> StateClone<S> for Royalties<T, S> {
+    unsafe fn clone_state(&self, cloned_state_api: &S) -> Self {
+        Self {
+            default: self.default,
+            tokens:  self.tokens.clone_state(cloned_state_api),
+        }
+    }
+}
+
+impl<T: IsTokenId, S: HasStateApi> Royalties<T, S> {
+    /// Create new royalties, where no token has a royalty.
+    pub fn new(state_builder: &mut StateBuilder<S>) -> Self {
+        Royalties {
+            default: None,
+            tokens:  state_builder.new_map(),
+        }
+    }
+
+    /// Get the royalty of the token, which is its own royalty if it has one,
+    /// and the default royalty otherwise.
+    pub fn royalty(&self, token_id: &T) -> Option<Royalty> {
+        self.tokens.get(token_id).map(|royalty| *royalty).or(self.default)
+    }
+
+    /// Set the default royalty, or remove it with `None`.
+    pub fn set_default(&mut self, royalty: Option<Royalty>) { self.default = royalty; }
+
+    /// Set the royalty of the token, replacing the default royalty for the
+    /// token.
+    pub fn set_token_royalty(&mut self, token_id: T, royalty: Royalty) {
+        self.tokens.insert(token_id, royalty);
+    }
+
+    /// Remove the royalty of the token, such that the default royalty applies.
+    pub fn remove_token_royalty(&mut self, token_id: &T) { self.tokens.remove(token_id); }
+
+    /// Answer the queries of the `royaltyInfo` function of the royalties
+    /// extension.
+    pub fn royalty_info_of(&self, params: &RoyaltyInfoQueryParams<T>) -> RoyaltyInfoQueryResponse {
+        let response = params
+            .queries
+            .iter()
+            .map(|query| {
+                self.royalty(&query.token_id).map(|royalty| royalty.royalty_info(query.sale_price))
+            })
+            .collect::<Vec<_>>();
+        RoyaltyInfoQueryResponse::from(response)
+    }
+}
+
+/// The state of a CIS2 token contract, to which the entrypoints generated by
+/// [`cis2_entrypoints`] delegate. The balances and operators are kept in a
+/// [`Cis2Ledger`], and the contract provides the metadata of its tokens.
+pub trait Cis2State<T, A, S> {
+    /// Get the ledger of the contract.
+    fn ledger(&self) -> &Cis2Ledger<T, A, S>;
+
+    /// Get a mutable reference to the ledger of the contract.
+    fn ledger_mut(&mut self) -> &mut Cis2Ledger<T, A, S>;
+
+    /// Get the metadata URL of the token, or `None` if the token does not
+    /// exist, in which case `tokenMetadata` rejects with
+    /// [`Cis2Error::InvalidTokenId`].
+    fn token_metadata(&self, token_id: &T) -> Option<MetadataUrl>;
+
+    /// Whether the contract supports the standard, as queried by the CIS-0
+    /// function `supports`. By default only CIS-0 and CIS-2 are supported.
+    fn supports(&self, standard: &StandardIdentifierOwned) -> SupportResult {
+        let standard = standard.as_standard_identifier();
+        if standard == CIS0_STANDARD_IDENTIFIER || standard == CIS2_STANDARD_IDENTIFIER {
+            SupportResult::Support
+        } else {
+            SupportResult::NoSupport
+        }
+    }
+}
+
+/// The custom errors of the entrypoints generated by [`cis2_entrypoints`] when
+/// no error type is given.
+#[derive(Debug, PartialEq, Eq, Serialize, SchemaType, Reject)]
+pub enum Cis2EntrypointError {
+    /// Failed parsing the parameter.
+    #[from(ParseError)]
+    ParseParams,
+    /// Failed logging: Log is full.
+    LogFull,
+    /// Failed logging: Log is malformed.
+    LogMalformed,
+    /// The total supply of a token would overflow.
+    #[from(TokenAmountOverflowError)]
+    Overflow,
+    /// Failed to invoke a contract.
+    InvokeContractError,
+    /// The permit was rejected, see [`permit_with_registered_key`].
+    #[from]
+    Permit(PermitError),
+}
+
+impl From<LogError> for Cis2EntrypointError {
+    fn from(le: LogError) -> Self {
+        match le {
+            LogError::Full => Self::LogFull,
+            LogError::Malformed => Self::LogMalformed,
+        }
+    }
+}
+
+impl<T> From<CallContractError<T>> for Cis2EntrypointError {
+    fn from(_cce: CallContractError<T>) -> Self { Self::InvokeContractError }
+}
+
+/// Generate the standard entrypoints of a CIS2 token contract: `transfer`,
+/// `updateOperator`, `balanceOf`, `operatorOf`, `tokenMetadata` and the CIS-0
+/// function `supports`, including their schemas.
+///
+/// The entrypoints delegate to the [`Cis2State`] implementation of the state
+/// of the contract, which must be a type taking the state API as its only
+/// generic parameter. Transfers notify contract receivers with
+/// [`Transfer::notify_receiver`]. The entrypoints reject with a
+/// [`Cis2Error`] of the custom error type given as `error`, which defaults to
+/// [`Cis2EntrypointError`]. A custom error type must be convertible from
+/// [`ParseError`], [`LogError`] and [`CallContractError`], and implement
+/// `Serial`, `SchemaType` and `Into<Reject>`.
+///
+/// The contract adds its own `init` function, and functions such as minting
+/// on top, e.g., using [`Cis2Ledger::mint`].
+///
+/// ```ignore
+/// #[derive(Serial, DeserialWithState, Deletable, StateClone)]
+/// #[concordium(state_parameter = "S")]
+/// struct State<S: HasStateApi> {
+///     ledger: Cis2Ledger<ContractTokenId, ContractTokenAmount, S>,
+/// }
+///
+/// impl<S: HasStateApi> Cis2State<ContractTokenId, ContractTokenAmount, S> for State<S> {
+///     fn ledger(&self) -> &Cis2Ledger<ContractTokenId, ContractTokenAmount, S> { &self.ledger }
+///
+///     fn ledger_mut(&mut self) -> &mut Cis2Ledger<ContractTokenId, ContractTokenAmount, S> {
+///         &mut self.ledger
+///     }
+///
+///     fn token_metadata(&self, token_id: &ContractTokenId) -> Option<MetadataUrl> { ... }
+/// }
+///
+/// cis2_entrypoints!(
+///     contract = "my_token",
+///     state = State,
+///     token_id = ContractTokenId,
+///     amount = ContractTokenAmount,
+///     error = CustomContractError, // Optional.
+/// );
+/// ```
+///
+/// The macro defines the receive functions `cis2_transfer`,
+/// `cis2_update_operator`, `cis2_balance_of`, `cis2_operator_of`,
+/// `cis2_token_metadata` and `cis2_supports`, which can be called in tests,
+/// and type aliases prefixed with `Cis2` for their parameters, return values
+/// and errors. It can therefore only be used once per module.
+#[macro_export]
+macro_rules! cis2_entrypoints {
+    {
+        contract = $contract:literal,
+        state = $state:ident,
+        token_id = $token_id:ty,
+        amount = $amount:ty $(,)?
+    } => {
+        $crate::cis2_entrypoints!(
+            contract = $contract,
+            state = $state,
+            token_id = $token_id,
+            amount = $amount,
+            error = $crate::Cis2EntrypointError,
+        );
+    };
+    {
+        contract = $contract:literal,
+        state = $state:ident,
+        token_id = $token_id:ty,
+        amount = $amount:ty,
+        error = $error:ty $(,)?
+    } => {
+        // The schema attributes can only refer to types by name.
+        type Cis2TransferParameter = $crate::TransferParams<$token_id, $amount>;
+        type Cis2UpdateOperatorParameter = $crate::UpdateOperatorParams;
+        type Cis2BalanceOfParameter = $crate::BalanceOfQueryParams<$token_id>;
+        type Cis2BalanceOfResponse = $crate::BalanceOfQueryResponse<$amount>;
+        type Cis2OperatorOfParameter = $crate::OperatorOfQueryParams;
+        type Cis2OperatorOfResponse = $crate::OperatorOfQueryResponse;
+        type Cis2TokenMetadataParameter = $crate::TokenMetadataQueryParams<$token_id>;
+        type Cis2TokenMetadataResponse = $crate::TokenMetadataQueryResponse;
+        type Cis2SupportsParameter = $crate::SupportsQueryParams;
+        type Cis2SupportsResponse = $crate::SupportsQueryResponse;
+        type Cis2EntrypointsError = $crate::Cis2Error<$error>;
+
+        /// Execute the transfers of the tokens. The sender must be the owner
+        /// or an operator of the owner of the tokens of each transfer.
+        #[concordium_std::receive(
+            contract = $contract,
+            name = "transfer",
+            parameter = "Cis2TransferParameter",
+            error = "Cis2EntrypointsError",
+            enable_logger,
+            mutable
+        )]
+        fn cis2_transfer<S: concordium_std::HasStateApi>(
+            ctx: &impl concordium_std::HasReceiveContext,
+            host: &mut impl concordium_std::HasHost<$state<S>, StateApiType = S>,
+            logger: &mut impl concordium_std::HasLogger,
+        ) -> Result<(), Cis2EntrypointsError> {
+            #[allow(unused_imports)]
+            use concordium_std::{
+                Get as _, HasCommonData as _, HasHost as _, HasReceiveContext as _,
+            };
+            #[allow(unused_imports)]
+            use $crate::Cis2State as _;
+            let params: Cis2TransferParameter = ctx.parameter_cursor().get()?;
+            let sender = ctx.sender();
+            for transfer in params.0 {
+                let (state, state_builder) = host.state_and_builder();
+                let result: Result<(), Cis2EntrypointsError> =
+                    state.ledger_mut().transfer(&sender, &transfer, state_builder, logger);
+                result?;
+                transfer.notify_receiver::<$state<S>, _, $error>(host)?;
+            }
+            Ok(())
+        }
+
+        /// Add or remove operators of the sender.
+        #[concordium_std::receive(
+            contract = $contract,
+            name = "updateOperator",
+            parameter = "Cis2UpdateOperatorParameter",
+            error = "Cis2EntrypointsError",
+            enable_logger,
+            mutable
+        )]
+        fn cis2_update_operator<S: concordium_std::HasStateApi>(
+            ctx: &impl concordium_std::HasReceiveContext,
+            host: &mut impl concordium_std::HasHost<$state<S>, StateApiType = S>,
+            logger: &mut impl concordium_std::HasLogger,
+        ) -> Result<(), Cis2EntrypointsError> {
+            #[allow(unused_imports)]
+            use concordium_std::{
+                Get as _, HasCommonData as _, HasHost as _, HasReceiveContext as _,
+            };
+            #[allow(unused_imports)]
+            use $crate::Cis2State as _;
+            let params: Cis2UpdateOperatorParameter = ctx.parameter_cursor().get()?;
+            let sender = ctx.sender();
+            let (state, state_builder) = host.state_and_builder();
+            for update in params.0 {
+                let result: Result<(), Cis2EntrypointsError> =
+                    state.ledger_mut().update_operator(sender, update, state_builder, logger);
+                result?;
+            }
+            Ok(())
+        }
+
+        /// Get the balances of tokens owned by addresses.
+        #[concordium_std::view(
+            contract = $contract,
+            name = "balanceOf",
+            parameter = "Cis2BalanceOfParameter",
+            return_value = "Cis2BalanceOfResponse",
+            error = "Cis2EntrypointsError"
+        )]
+        fn cis2_balance_of<S: concordium_std::HasStateApi>(
+            ctx: &impl concordium_std::HasReceiveContext,
+            host: &impl concordium_std::HasViewHost<$state<S>>,
+        ) -> Result<Cis2BalanceOfResponse, Cis2EntrypointsError> {
+            #[allow(unused_imports)]
+            use concordium_std::{Get as _, HasCommonData as _, HasViewHost as _};
+            #[allow(unused_imports)]
+            use $crate::Cis2State as _;
+            let params: Cis2BalanceOfParameter = ctx.parameter_cursor().get()?;
+            host.state().ledger().balance_of(&params)
+        }
+
+        /// Get whether addresses are operators of owners.
+        #[concordium_std::view(
+            contract = $contract,
+            name = "operatorOf",
+            parameter = "Cis2OperatorOfParameter",
+            return_value = "Cis2OperatorOfResponse",
+            error = "Cis2EntrypointsError"
+        )]
+        fn cis2_operator_of<S: concordium_std::HasStateApi>(
+            ctx: &impl concordium_std::HasReceiveContext,
+            host: &impl concordium_std::HasViewHost<$state<S>>,
+        ) -> Result<Cis2OperatorOfResponse, Cis2EntrypointsError> {
+            #[allow(unused_imports)]
+            use concordium_std::{Get as _, HasCommonData as _, HasViewHost as _};
+            #[allow(unused_imports)]
+            use $crate::Cis2State as _;
+            let params: Cis2OperatorOfParameter = ctx.parameter_cursor().get()?;
+            Ok(host.state().ledger().operator_of(&params))
+        }
+
+        /// Get the metadata URLs of tokens.
+        #[concordium_std::view(
+            contract = $contract,
+            name = "tokenMetadata",
+            parameter = "Cis2TokenMetadataParameter",
+            return_value = "Cis2TokenMetadataResponse",
+            error = "Cis2EntrypointsError"
+        )]
+        fn cis2_token_metadata<S: concordium_std::HasStateApi>(
+            ctx: &impl concordium_std::HasReceiveContext,
+            host: &impl concordium_std::HasViewHost<$state<S>>,
+        ) -> Result<Cis2TokenMetadataResponse, Cis2EntrypointsError> {
+            #[allow(unused_imports)]
+            use concordium_std::{Get as _, HasCommonData as _, HasViewHost as _};
+            #[allow(unused_imports)]
+            use $crate::Cis2State as _;
+            let params: Cis2TokenMetadataParameter = ctx.parameter_cursor().get()?;
+            let mut response = Vec::with_capacity(params.queries.len());
+            for token_id in params.queries.iter() {
+                let metadata_url = host
+                    .state()
+                    .token_metadata(token_id)
+                    .ok_or($crate::Cis2Error::InvalidTokenId)?;
+                response.push(metadata_url);
+            }
+            Ok(Cis2TokenMetadataResponse::from(response))
+        }
+
+        /// Get which of the standards the contract supports.
+        #[concordium_std::view(
+            contract = $contract,
+            name = "supports",
+            parameter = "Cis2SupportsParameter",
+            return_value = "Cis2SupportsResponse",
+            error = "Cis2EntrypointsError"
+        )]
+        fn cis2_supports<S: concordium_std::HasStateApi>(
+            ctx: &impl concordium_std::HasReceiveContext,
+            host: &impl concordium_std::HasViewHost<$state<S>>,
+        ) -> Result<Cis2SupportsResponse, Cis2EntrypointsError> {
+            #[allow(unused_imports)]
+            use concordium_std::{Get as _, HasCommonData as _, HasViewHost as _};
+            #[allow(unused_imports)]
+            use $crate::Cis2State as _;
+            let params: Cis2SupportsParameter = ctx.parameter_cursor().get()?;
+            let response = params
+                .queries
+                .iter()
+                .map(|standard| host.state().supports(standard))
+                .collect::<Vec<_>>();
+            Ok(Cis2SupportsResponse::from(response))
+        }
+    };
+}
+
+/// The message an account signs off-chain to authorize an invocation of the
+/// `transfer` or `updateOperator` function of a CIS2 contract on its behalf,
+/// such that anyone can submit it to the contract, modelled on CIS3. The
+/// signer signs the SHA-256 hash of the serialized message, see
+/// [`PermitMessage::hash`].
+// Note: Since the signature is on the serialization of the message, the order
+// of the fields cannot be changed.
+#[derive(Debug, Serialize, Clone)]
+pub struct PermitMessage {
+    /// The contract the message is intended for, which prevents the message
+    /// from being used with other contracts.
+    pub contract_address: ContractAddress,
+    /// The nonce of the signer, which prevents the message from being used
+    /// more than once.
+    pub nonce:            u64,
+    /// The time after which the message can no longer be used.
+    pub expiry:           Timestamp,
+    /// The function to invoke, either `transfer` or `updateOperator`.
+    pub entry_point:      OwnedEntrypointName,
+    /// The serialized parameter of the function.
+    #[concordium(size_length = 2)]
+    pub payload:          Vec<u8>,
+}
+
+impl schema::SchemaType for PermitMessage {
+    fn get_type() -> schema::Type {
+        schema::Type::Struct(schema::Fields::Named(vec![
+            (String::from("contract_address"), ContractAddress::get_type()),
+            (String::from("nonce"), u64::get_type()),
+            (String::from("expiry"), Timestamp::get_type()),
+            (String::from("entry_point"), schema::Type::String(schema::SizeLength::U16)),
+            (String::from("payload"), schema::Type::ByteList(schema::SizeLength::U16)),
+        ]))
+    }
+}
+
+impl PermitMessage {
+    /// Compute the SHA-256 hash of the serialized message, which is what the
+    /// signer signs.
+    pub fn hash(&self, crypto_primitives: &impl HasCryptoPrimitives) -> HashSha2256 {
+        crypto_primitives.hash_sha2_256(&to_bytes(self))
+    }
+}
+
+/// The parameter type for a contract function executing permits, see
+/// [`permit_with_registered_key`]. Unlike in CIS3, the signature is a single
+/// Ed25519 signature, which is verified with the public key the signer has
+/// registered in the [`PermitRegistry`] rather than with the keys of the
+/// account.
+#[derive(Debug, Serialize, SchemaType)]
+pub struct PermitParams {
+    /// The signature of the signer on the hash of the message.
+    pub signature: SignatureEd25519,
+    /// The account which signed the message, on whose behalf the function is
+    /// invoked.
+    pub signer:    AccountAddress,
+    /// The signed message.
+    pub message:   PermitMessage,
+}
+
+/// An untagged event of a nonce being used by a permit.
+/// For a tagged version, use `Cis3Event`.
+// Note: For the serialization to be derived according to the CIS3
+// specification, the order of the fields cannot be changed.
+#[derive(Debug, Serialize, SchemaType)]
+pub struct NonceEvent {
+    /// The nonce of the permit.
+    pub nonce:   u64,
+    /// The account which signed the permit.
+    pub account: AccountAddress,
+}
+
+/// Tagged CIS3 event to be serialized for the event log.
+#[derive(Debug)]
+pub enum Cis3Event {
+    /// A nonce of an account was used by a permit.
+    Nonce(NonceEvent),
+}
+
+impl schema::SchemaType for Cis3Event {
+    fn get_type() -> schema::Type {
+        let mut event_map = BTreeMap::new();
+        event_map.insert(
+            NONCE_EVENT_TAG,
+            (
+                "Nonce".to_string(),
+                schema::Fields::Named(vec![
+                    (String::from("nonce"), u64::get_type()),
+                    (String::from("account"), AccountAddress::get_type()),
+                ]),
+            ),
+        );
+        schema::Type::TaggedEnum(event_map)
+    }
+}
+
+impl Serial for Cis3Event {
+    fn serial<W: Write>(&self, out: &mut W) -> Result<(), W::Err> {
+        match self {
+            Cis3Event::Nonce(event) => {
+                out.write_u8(NONCE_EVENT_TAG)?;
+                event.serial(out)
+            }
+        }
+    }
+}
+
+impl Deserial for Cis3Event {
+    fn deserial<R: Read>(source: &mut R) -> ParseResult<Self> {
+        let tag = source.read_u8()?;
+        Self::deserial_tagged(tag, source)
+    }
+}
+
+/// Allows flattening `Cis3Event` into the event type of a contract deriving
+/// `Event`.
+impl TaggedEvent for Cis3Event {
+    const TAGS: EventTags = EventTags {
+        tags:      &[NONCE_EVENT_TAG],
+        flattened: &[],
+    };
+
+    fn deserial_tagged<R: Read>(tag: u8, source: &mut R) -> ParseResult<Self> {
+        match tag {
+            NONCE_EVENT_TAG => NonceEvent::deserial(source).map(Cis3Event::Nonce),
+            _ => Err(ParseError::default()),
+        }
+    }
+}
+
+/// The reasons a permit is rejected. Returned by [`PermitRegistry::use_permit`]
+/// and [`permit_with_registered_key`] wrapped in [`Cis2Error::Custom`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, SchemaType)]
+pub enum PermitError {
+    /// The message is intended for another contract.
+    WrongContract,
+    /// The message has expired.
+    Expired,
+    /// The nonce of the message is not the current nonce of the signer.
+    NonceMismatch,
+    /// The signer has not registered a public key.
+    MissingPublicKey,
+    /// The signature is not valid for the message and the public key of the
+    /// signer.
+    WrongSignature,
+    /// The message is for a function which cannot be invoked by a permit.
+    WrongEntrypoint,
+    /// The nonce of the signer cannot be incremented, since it is the largest
+    /// `u64`.
+    NonceOverflow,
+}
+
+impl<X> From<PermitError> for Cis2Error<X>
+where
+    X: From<PermitError>,
+{
+    #[inline]
+    /// Converts the error by wrapping it in [Self::Custom].
+    fn from(err: PermitError) -> Self { Cis2Error::Custom(X::from(err)) }
+}
+
+/// The nonces and public keys of the accounts which sign permits, which is
+/// meant to be used as part of the state of a contract supporting permits.
+///
+/// This deviates from CIS3, where a permit is verified with the keys of the
+/// signer account, since contracts cannot access the keys of accounts.
+/// Instead, an account must register the public key it signs permits with,
+/// using [`register_public_key`](Self::register_public_key), in a function of
+/// the contract which checks that the sender is the account. Registering
+/// therefore takes a transaction sent by the account itself, and permits of
+/// accounts which have not registered a key are rejected with
+/// [`PermitError::MissingPublicKey`]. A contract using the registry does not
+/// support CIS3, and must not report support of it.
+///
+/// The nonce of an account starts at zero and is incremented each time a
+/// permit signed by the account is used.
+///
+/// Deleting the registry with [`Deletable::delete`] removes the registered
+/// keys and resets the nonces, so a new registry would accept permits that
+/// were already used.
+pub struct PermitRegistry<S> {
+    /// The next nonce of each account which has used a permit.
+    nonces:      StateMap<AccountAddress, u64, S>,
+    /// The public key of each account which has registered one.
+    public_keys: StateMap<AccountAddress, PublicKeyEd25519, S>,
+}
+
+impl<S> Serial for PermitRegistry<S> {
+    fn serial<W: Write>(&self, out: &mut W) -> Result<(), W::Err> {
+        self.nonces.serial(out)?;
+        self.public_keys.serial(out)
+    }
+}
+
+impl<S: HasStateApi> DeserialWithState<S> for PermitRegistry<S> {
+    fn deserial_with_state<R: Read>(state: &S, source: &mut R) -> ParseResult<Self> {
+        Ok(PermitRegistry {
+            nonces:      StateMap::deserial_with_state(state, source)?,
+            public_keys: StateMap::deserial_with_state(state, source)?,
+        })
+    }
+}
+
+impl<S: HasStateApi> Deletable for PermitRegistry<S> {
+    fn delete(self) {
+        self.nonces.delete();
+        self.public_keys.delete();
+    }
+}
+
+unsafe impl<S: HasStateApi> StateClone<S> for PermitRegistry<S> {
+    unsafe fn clone_state(&self, cloned_state_api: &S) -> Self {
+        Self {
+            nonces:      self.nonces.clone_state(cloned_state_api),
+            public_keys: self.public_keys.clone_state(cloned_state_api),
+        }
+    }
+}
+
+impl<S: HasStateApi> PermitRegistry<S> {
+    /// Create a new registry without any accounts.
+    pub fn new(state_builder: &mut StateBuilder<S>) -> Self {
+        PermitRegistry {
+            nonces:      state_builder.new_map(),
+            public_keys: state_builder.new_map(),
+        }
+    }
+
+    /// Get the nonce the next permit signed by the account must have.
+    pub fn nonce(&self, account: &AccountAddress) -> u64 {
+        self.nonces.get(account).map_or(0, |nonce| *nonce)
+    }
+
+    /// Get the public key registered by the account, if any.
+    pub fn public_key(&self, account: &AccountAddress) -> Option<PublicKeyEd25519> {
+        self.public_keys.get(account).map(|public_key| *public_key)
+    }
+
+    /// Register the public key which the account signs permits with,
+    /// replacing any previously registered key. The contract must check that
+    /// the sender is the account.
+    pub fn register_public_key(&mut self, account: AccountAddress, public_key: PublicKeyEd25519) {
+        self.public_keys.insert(account, public_key);
+    }
+
+    /// Check that the permit is intended for this contract, has not expired,
+    /// has the current nonce of the signer and is signed with the public key
+    /// of the signer, returning the corresponding [`PermitError`] otherwise.
+    /// The nonce of the signer is then incremented, and the [`NonceEvent`]
+    /// which the contract must log as [`Cis3Event::Nonce`] is returned. The
+    /// contract must afterwards invoke the function of the message on behalf
+    /// of the signer, which [`permit_with_registered_key`] does.
+    pub fn use_permit(
+        &mut self,
+        params: &PermitParams,
+        ctx: &impl HasReceiveContext,
+        crypto_primitives: &impl HasCryptoPrimitives,
+    ) -> Result<NonceEvent, PermitError> {
+        let message = &params.message;
+        ensure!(message.contract_address == ctx.self_address(), PermitError::WrongContract);
+        ensure!(message.expiry >= ctx.metadata().slot_time(), PermitError::Expired);
+        let nonce = self.nonce(&params.signer);
+        ensure!(message.nonce == nonce, PermitError::NonceMismatch);
+        let public_key = self.public_key(&params.signer).ok_or(PermitError::MissingPublicKey)?;
+        let hash = message.hash(crypto_primitives);
+        ensure!(
+            crypto_primitives.verify_ed25519_signature(public_key, params.signature, &hash.0),
+            PermitError::WrongSignature
+        );
+        let next_nonce = nonce.checked_add(1).ok_or(PermitError::NonceOverflow)?;
+        self.nonces.insert(params.signer, next_nonce);
+        Ok(NonceEvent {
+            nonce,
+            account: params.signer,
+        })
+    }
+}
+
+/// The state of a CIS2 token contract which also supports permits signed
+/// with registered keys, which [`permit_with_registered_key`] delegates to.
+/// The state must also implement [`Cis2State`] for the same token types.
+pub trait PermitState<S> {
+    /// The type of the token IDs of the contract.
+    type TokenId: IsTokenId + Clone;
+    /// The type of the token amounts of the contract.
+    type TokenAmount: TokenAmountArithmetic;
+
+    /// Get the permit registry of the contract.
+    fn permits(&self) -> &PermitRegistry<S>;
+
+    /// Get a mutable reference to the permit registry of the contract.
+    fn permits_mut(&mut self) -> &mut PermitRegistry<S>;
+}
+
+/// Execute a permit signed with a registered key: parse the
+/// [`PermitParams`], check the permit with [`PermitRegistry::use_permit`],
+/// and invoke the `transfer` or `updateOperator` function of the message on
+/// behalf of the signer, with the same behaviour as the functions generated
+/// by [`cis2_entrypoints`]. Returns [`PermitError::WrongEntrypoint`] for any
+/// other function.
+///
+/// This is not the `permit` function of CIS3, since the signature is verified
+/// with the key registered in the [`PermitRegistry`] instead of the keys of
+/// the signer account, so the entrypoint must not be named `permit`.
+///
+/// ```ignore
+/// #[receive(
+///     contract = "my_token",
+///     name = "permitWithRegisteredKey",
+///     parameter = "PermitParams",
+///     error = "ContractError",
+///     crypto_primitives,
+///     enable_logger,
+///     mutable
+/// )]
+/// fn contract_permit_with_registered_key<S: HasStateApi>(
+///     ctx: &impl HasReceiveContext,
+///     host: &mut impl HasHost<State<S>, StateApiType = S>,
+///     logger: &mut impl HasLogger,
+///     crypto_primitives: &impl HasCryptoPrimitives,
+/// ) -> Result<(), ContractError> {
+///     permit_with_registered_key(ctx, host, logger, crypto_primitives)
+/// }
+/// ```
+pub fn permit_with_registered_key<S, State, R, E>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State, StateApiType = S, ReturnValueType = R>,
+    logger: &mut impl HasLogger,
+    crypto_primitives: &impl HasCryptoPrimitives,
+) -> Result<(), Cis2Error<E>>
+where
+    S: HasStateApi,
+    State: PermitState<S> + Cis2State<State::TokenId, State::TokenAmount, S>,
+    E: From<ParseError> + From<LogError> + From<PermitError> + From<CallContractError<R>>, {
+    let params: PermitParams = ctx.parameter_cursor().get()?;
+    let event = host.state_mut().permits_mut().use_permit(&params, ctx, crypto_primitives)?;
+    logger.log(&Cis3Event::Nonce(event))?;
+    let signer = Address::Account(params.signer);
+    let message = params.message;
+    let entry_point = message.entry_point.as_entrypoint_name();
+    if entry_point == EntrypointName::new_unchecked("transfer") {
+        let TransferParams(transfers): TransferParams<State::TokenId, State::TokenAmount> =
+            from_bytes(&message.payload)?;
+        for transfer in transfers {
+            let (state, state_builder) = host.state_and_builder();
+            let result: Result<(), Cis2Error<E>> =
+                state.ledger_mut().transfer(&signer, &transfer, state_builder, logger);
+            result?;
+            transfer.notify_receiver::<State, _, E>(host)?;
+        }
+    } else if entry_point == EntrypointName::new_unchecked("updateOperator") {
+        let UpdateOperatorParams(updates) = from_bytes(&message.payload)?;
+        let (state, state_builder) = host.state_and_builder();
+        for update in updates {
+            let result: Result<(), Cis2Error<E>> =
+                state.ledger_mut().update_operator(signer, update, state_builder, logger);
+            result?;
+        }
+    } else {
+        bail!(PermitError::WrongEntrypoint.into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use concordium_std::{cell::Cell, rc::Rc, test_infrastructure::*};
+
+    #[test]
+    fn check_schema_token_ids() {
+        check_schema::<TokenIdVec>();
+        check_schema::<TokenIdFixed<4>>();
+        check_schema::<TokenIdU64>();
+        check_schema::<TokenIdU32>();
+        check_schema::<TokenIdU16>();
+        check_schema::<TokenIdU8>();
+        check_schema::<TokenIdUnit>();
+    }
+
+    #[test]
+    fn check_schema_token_amounts() {
+        check_schema::<TokenAmountU128>();
+        check_schema::<TokenAmountU64>();
+        check_schema::<TokenAmountU32>();
+        check_schema::<TokenAmountU16>();
+        check_schema::<TokenAmountU8>();
+        #[cfg(feature = "u256_amount")]
+        check_schema::<TokenAmountU256>();
+    }
+
+    #[test]
+    fn check_schema_events_and_errors() {
+        check_schema::<Cis2Event<TokenIdVec, TokenAmountU64>>();
+        check_schema::<Cis2Event<TokenIdU8, TokenAmountU128>>();
+        check_schema::<Cis2Error<u8>>();
+        check_schema::<Cis2Error<()>>();
+        check_schema::<Cis2AllowanceEvent<TokenIdVec, TokenAmountU64>>();
+        check_schema::<Cis3Event>();
+        check_schema::<PermitError>();
+    }
+
+    #[test]
+    fn check_schema_parameters() {
+        check_schema::<MetadataUrl>();
+        check_schema::<Receiver>();
+        check_schema::<AdditionalData>();
+        check_schema::<Transfer<TokenIdVec, TokenAmountU64>>();
+        check_schema::<TransferParams<TokenIdVec, TokenAmountU64>>();
+        check_schema::<OperatorUpdate>();
+        check_schema::<UpdateOperator>();
+        check_schema::<UpdateOperatorParams>();
+        check_schema::<BalanceOfQuery<TokenIdVec>>();
+        check_schema::<BalanceOfQueryParams<TokenIdVec>>();
+        check_schema::<BalanceOfQueryResponse<TokenAmountU64>>();
+        check_schema::<OperatorOfQuery>();
+        check_schema::<OperatorOfQueryParams>();
+        check_schema::<OperatorOfQueryResponse>();
+        check_schema::<TokenMetadataQueryParams<TokenIdVec>>();
+        check_schema::<TokenMetadataQueryResponse>();
+        check_schema::<StandardIdentifierOwned>();
+        check_schema::<SupportsQueryParams>();
+        check_schema::<SupportResult>();
+        check_schema::<SupportsQueryResponse>();
+        check_schema::<PermitMessage>();
+        check_schema::<PermitParams>();
+        check_schema::<Allowance<TokenAmountU64>>();
+        check_schema::<ApproveParams<TokenIdVec, TokenAmountU64>>();
+        check_schema::<DecreaseAllowanceParams<TokenIdVec, TokenAmountU64>>();
+        check_schema::<AllowanceQueryParams<TokenIdVec>>();
+        check_schema::<AllowanceQueryResponse<TokenAmountU64>>();
+        check_schema::<RoyaltyInfoQueryParams<TokenIdVec>>();
+        check_schema::<Royalty>();
+        check_schema::<RoyaltyInfoQueryResponse>();
+    }
+
+    #[test]
+    fn serial_token_amount128_127_test() {
+        let amount = TokenAmountU128::from(127);
+        let bytes = to_bytes(&amount);
+        assert_eq!(bytes, vec![127])
+    }
+
+    #[test]
+    fn serial_token_amount128_max_test() {
+        let amount = TokenAmountU128::from(u128::MAX);
+        let bytes = to_bytes(&amount);
+        assert_eq!(bytes, vec![
+            255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255,
+            255, 0b00000011
+        ])
+    }
+
+    #[test]
+    fn deserial_token_amount128_127_test() {
+        let amount: TokenAmountU128 = from_bytes(&[127]).expect("Failed to parse bytes");
+        assert_eq!(amount, TokenAmountU128::from(127))
+    }
+
+    #[test]
+    fn deserial_token_amount128_max_test() {
+        let amount: TokenAmountU128 = from_bytes(&[
+            255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255,
+            255, 0b00000011,
+        ])
+        .expect("Failed to parse bytes");
+        assert_eq!(amount, TokenAmountU128::from(u128::MAX))
+    }
+
+    #[test]
+    fn serial_token_amount64_127_test() {
+        let amount = TokenAmountU64::from(127);
+        let bytes = to_bytes(&amount);
+        assert_eq!(bytes, vec![127])
+    }
+
+    #[test]
+    fn serial_token_amount64_max_test() {
+        let amount = TokenAmountU64::from(u64::MAX);
+        let bytes = to_bytes(&amount);
+        assert_eq!(bytes, vec![255, 255, 255, 255, 255, 255, 255, 255, 255, 0b00000001])
+    }
+
+    #[test]
+    fn deserial_token_amount64_127_test() {
+        let amount: TokenAmountU64 = from_bytes(&[127]).expect("Failed to parse bytes");
+        assert_eq!(amount, TokenAmountU64::from(127))
+    }
+
+    #[test]
+    fn deserial_token_amount64_max_test() {
+        let amount: TokenAmountU64 =
+            from_bytes(&[255, 255, 255, 255, 255, 255, 255, 255, 255, 0b00000001])
+                .expect("Failed to parse bytes");
+        assert_eq!(amount, TokenAmountU64::from(u64::MAX))
+    }
+
+    #[test]
+    fn serial_token_amount32_127_test() {
+        let amount = TokenAmountU32::from(127);
+        let bytes = to_bytes(&amount);
+        assert_eq!(bytes, vec![127])
+    }
+
+    #[test]
+    fn serial_token_amount32_max_test() {
+        let amount = TokenAmountU32::from(u32::MAX);
+        let bytes = to_bytes(&amount);
+        assert_eq!(bytes, vec![255, 255, 255, 255, 0b00001111])
+    }
+
+    #[test]
+    fn deserial_token_amount32_127_test() {
+        let amount: TokenAmountU32 = from_bytes(&[127]).expect("Failed to parse bytes");
+        assert_eq!(amount, TokenAmountU32::from(127))
+    }
+
+    #[test]
+    fn deserial_token_amount32_max_test() {
+        let amount: TokenAmountU32 =
+            from_bytes(&[255, 255, 255, 255, 0b00001111]).expect("Failed to parse bytes");
+        assert_eq!(amount, TokenAmountU32::from(u32::MAX))
+    }
+
+    #[test]
+    fn serial_token_amount16_127_test() {
+        let amount = TokenAmountU16::from(127);
+        let bytes = to_bytes(&amount);
+        assert_eq!(bytes, vec![127])
+    }
+
+    #[test]
+    fn serial_token_amount16_max_test() {
+        let amount = TokenAmountU16::from(u16::MAX);
+        let bytes = to_bytes(&amount);
+        assert_eq!(bytes, vec![255, 255, 0b00000011])
+    }
+
+    #[test]
+    fn deserial_token_amount16_127_test() {
+        let amount: TokenAmountU16 = from_bytes(&[127]).expect("Failed to parse bytes");
+        assert_eq!(amount, TokenAmountU16::from(127))
+    }
+
+    #[test]
+    fn deserial_token_amount16_max_test() {
+        let amount: TokenAmountU16 =
+            from_bytes(&[255, 255, 0b00000011]).expect("Failed to parse bytes");
+        assert_eq!(amount, TokenAmountU16::from(u16::MAX))
+    }
+
+    #[test]
+    fn serial_token_amount8_127_test() {
+        let amount = TokenAmountU8::from(127);
+        let bytes = to_bytes(&amount);
+        assert_eq!(bytes, vec![127])
+    }
+
+    #[test]
+    fn serial_token_amount8_max_test() {
+        let amount = TokenAmountU8::from(u8::MAX);
+        let bytes = to_bytes(&amount);
+        assert_eq!(bytes, vec![255, 0b00000001])
+    }
+
+    #[test]
+    fn deserial_token_amount8_127_test() {
+        let amount: TokenAmountU8 = from_bytes(&[127]).expect("Failed to parse bytes");
+        assert_eq!(amount, TokenAmountU8::from(127))
+    }
+
+    #[test]
+    fn deserial_token_amount8_max_test() {
+        let amount: TokenAmountU8 = from_bytes(&[255, 0b00000001]).expect("Failed to parse bytes");
+        assert_eq!(amount, TokenAmountU8::from(u8::MAX))
+    }
+
+    #[test]
+    fn serial_token_amount_boundaries_test() {
+        assert_eq!(to_bytes(&TokenAmountU8(0)), vec![0]);
+        assert_eq!(to_bytes(&TokenAmountU8(128)), vec![0b10000000, 1]);
+        assert_eq!(to_bytes(&TokenAmountU16(16383)), vec![255, 0b01111111]);
+        assert_eq!(to_bytes(&TokenAmountU16(16384)), vec![0b10000000, 0b10000000, 1]);
+        assert_eq!(to_bytes(&TokenAmountU32(1 << 28)), vec![
+            0b10000000, 0b10000000, 0b10000000, 0b10000000, 1
+        ]);
+        assert_eq!(to_bytes(&TokenAmountU64(1 << 63)), vec![
+            0b10000000, 0b10000000, 0b10000000, 0b10000000, 0b10000000, 0b10000000, 0b10000000,
+            0b10000000, 0b10000000, 1
+        ]);
+        assert_eq!(to_bytes(&TokenAmountU128(1 << 126)).len(), 19);
+        for amount in [0, 127, 128, 16383, 16384, u64::MAX - 1, u64::MAX].iter() {
+            let amount = TokenAmountU64(*amount);
+            assert_eq!(from_bytes(&to_bytes(&amount)), Ok(amount));
+        }
+    }
+
+    #[test]
+    fn deserial_token_amount_overflow_test() {
+        // One more than the maximal amount.
+        assert!(from_bytes::<TokenAmountU8>(&[0b10000000, 0b00000010]).is_err());
+        assert!(from_bytes::<TokenAmountU16>(&[255, 255, 0b00000100]).is_err());
+        assert!(from_bytes::<TokenAmountU32>(&[255, 255, 255, 255, 0b00010000]).is_err());
+        assert!(from_bytes::<TokenAmountU64>(&[255, 255, 255, 255, 255, 255, 255, 255, 255, 2])
+            .is_err());
+        let mut bytes = vec![255; 18];
+        bytes.push(0b00000100);
+        assert!(from_bytes::<TokenAmountU128>(&bytes).is_err());
+        // Bytes beyond the width of the integer.
+        assert!(from_bytes::<TokenAmountU8>(&[0b10000000, 0b10000000, 1]).is_err());
+        // Padding with zeros is allowed.
+        assert_eq!(from_bytes(&[0b10000001, 0b10000000, 0]), Ok(TokenAmountU8(1)));
+    }
+
+    #[test]
+    fn token_amount_checked_arithmetic_test() {
+        let max = TokenAmountU8(u8::MAX);
+        assert_eq!(TokenAmountU8(1).checked_add(TokenAmountU8(2)), Some(TokenAmountU8(3)));
+        assert_eq!(max.checked_add(TokenAmountU8(1)), None);
+        assert_eq!(TokenAmountU8(1).checked_sub(TokenAmountU8(2)), None);
+        assert_eq!(TokenAmountU8(100).checked_mul(3), None);
+        assert_eq!(max.saturating_add(TokenAmountU8(1)), max);
+        assert_eq!(TokenAmountU8(1).saturating_sub(TokenAmountU8(2)), TokenAmountU8(0));
+        assert_eq!(TokenAmountU8(100).saturating_mul(3), max);
+        assert_eq!(
+            TokenAmountU8(1).sub_or_insufficient_funds::<()>(TokenAmountU8(2)),
+            Err(Cis2Error::InsufficientFunds)
+        );
+        assert_eq!(
+            TokenAmountU64::try_sum(vec![TokenAmountU64(1), TokenAmountU64(2)]),
+            Some(TokenAmountU64(3))
+        );
+        assert_eq!(TokenAmountU64::try_sum(vec![]), Some(TokenAmountU64(0)));
+        assert_eq!(
+            TokenAmountU64::try_sum(vec![TokenAmountU64(u64::MAX), TokenAmountU64(1)]),
+            None
+        );
+    }
+
+    #[test]
+    fn cis2_error_serialization_roundtrip() {
+        let errors: [Cis2Error<u8>; 4] = [
+            Cis2Error::InvalidTokenId,
+            Cis2Error::InsufficientFunds,
+            Cis2Error::Unauthorized,
+            Cis2Error::Custom(7),
+        ];
+        for error in errors.iter() {
+            let parsed: Cis2Error<u8> =
+                from_bytes(&to_bytes(error)).expect("Failed to parse bytes");
+            assert_eq!(&parsed, error)
+        }
+    }
+
+    #[test]
+    fn metadata_url_hash_test() {
+        let crypto_primitives = TestCryptoPrimitives::mocked();
+        // A hash which only depends on the length of the content.
+        crypto_primitives
+            .setup_hash_sha2_256_mock(|content| HashSha2256([content.len() as u8; 32]));
+        let url = MetadataUrl::with_hash(
+            "https://example.com/metadata.json".into(),
+            b"{}",
+            &crypto_primitives,
+        );
+        assert_eq!(url.hash, Some([2u8; 32]));
+        assert!(url.verify_hash(b"[]", &crypto_primitives));
+        assert!(!url.verify_hash(b"{ }", &crypto_primitives));
+        let url = MetadataUrl {
+            url:  "https://example.com/metadata.json".into(),
+            hash: None,
+        };
+        assert!(url.verify_hash(b"{ }", &crypto_primitives));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn token_metadata_json_test() {
+        let json = serde_json::json!({
+            "name": "Wrapped CCD",
+            "symbol": "wCCD",
+            "decimals": 6,
+            "thumbnail": {
+                "url": "https://example.com/thumbnail.png",
+                "hash": "ab".repeat(32),
+            },
+            "attributes": [{ "type": "string", "name": "color", "value": "red" }],
+            "localization": {
+                "da-DK": { "url": "https://example.com/da-DK.json" },
+            },
+        });
+        let metadata: TokenMetadata =
+            serde_json::from_value(json.clone()).expect("Failed to parse metadata");
+        assert_eq!(metadata.symbol.as_deref(), Some("wCCD"));
+        assert_eq!(metadata.decimals, Some(6));
+        assert_eq!(
+            metadata.thumbnail.as_ref().and_then(|thumbnail| thumbnail.hash),
+            Some([0xab; 32])
+        );
+        assert_eq!(metadata.attributes[0].attribute_type, "string");
+        assert_eq!(metadata.localization["da-DK"].hash, None);
+        assert_eq!(metadata.display, None);
+        // The fields which are not set are omitted.
+        assert_eq!(serde_json::to_value(&metadata).expect("Failed to serialize metadata"), json);
+
+        let invalid_hash = serde_json::json!({ "url": "https://example.com", "hash": "ab" });
+        assert!(serde_json::from_value::<MetadataUrl>(invalid_hash).is_err());
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    enum LedgerTestError {
+        Log,
+        Overflow,
+        Call,
+    }
+
+    impl From<LogError> for LedgerTestError {
+        fn from(_: LogError) -> Self { LedgerTestError::Log }
+    }
+
+    impl From<TokenAmountOverflowError> for LedgerTestError {
+        fn from(_: TokenAmountOverflowError) -> Self { LedgerTestError::Overflow }
+    }
+
+    impl<T> From<CallContractError<T>> for LedgerTestError {
+        fn from(_: CallContractError<T>) -> Self { LedgerTestError::Call }
+    }
+
+    type LedgerTestResult = Result<(), Cis2Error<LedgerTestError>>;
+
+    const ALICE: Address = Address::Account(AccountAddress([0u8; 32]));
+    const BOB: Address = Address::Account(AccountAddress([1u8; 32]));
+    const TOKEN: TokenIdU8 = TokenIdU8(1);
+
+    fn ledger_transfer(
+        amount: u64,
+        from: Address,
+        to: Address,
+    ) -> Transfer<TokenIdU8, TokenAmountU64> {
+        let to = match to {
+            Address::Account(account) => Receiver::from_account(account),
+            Address::Contract(contract) => Receiver::from_contract(
+                contract,
+                OwnedEntrypointName::new_unchecked("onReceivingCIS2".into()),
+            ),
+        };
+        Transfer {
+            token_id: TOKEN,
+            amount: TokenAmountU64(amount),
+            from,
+            to,
+            data: AdditionalData::empty(),
+        }
+    }
+
+    #[test]
+    fn ledger_mint_transfer_burn_test() {
+        let mut state_builder = TestStateBuilder::new();
+        let mut logger = TestLogger::init();
+        let mut ledger = Cis2Ledger::new(&mut state_builder);
+
+        let result: LedgerTestResult =
+            ledger.mint(TOKEN, TokenAmountU64(100), ALICE, &mut state_builder, &mut logger);
+        assert_eq!(result, Ok(()));
+        assert!(ledger.contains_token(&TOKEN));
+        assert!(!ledger.contains_token(&TokenIdU8(2)));
+
+        let result: LedgerTestResult = ledger.transfer(
+            &ALICE,
+            &ledger_transfer(40, ALICE, BOB),
+            &mut state_builder,
+            &mut logger,
+        );
+        assert_eq!(result, Ok(()));
+        let result: LedgerTestResult = ledger.burn(TOKEN, TokenAmountU64(10), BOB, &mut logger);
+        assert_eq!(result, Ok(()));
+
+        assert_eq!(ledger.balance(&TOKEN, &ALICE), TokenAmountU64(60));
+        assert_eq!(ledger.balance(&TOKEN, &BOB), TokenAmountU64(30));
+        assert_eq!(ledger.total_supply(&TOKEN), TokenAmountU64(90));
+        assert_eq!(logger.logs, vec![
+            to_bytes(&Cis2Event::Mint(MintEvent {
+                token_id: TOKEN,
+                amount:   TokenAmountU64(100),
+                owner:    ALICE,
+            })),
+            to_bytes(&Cis2Event::Transfer(TransferEvent {
+                token_id: TOKEN,
+                amount:   TokenAmountU64(40),
+                from:     ALICE,
+                to:       BOB,
+            })),
+            to_bytes(&Cis2Event::Burn(BurnEvent {
+                token_id: TOKEN,
+                amount:   TokenAmountU64(10),
+                owner:    BOB,
+            })),
+        ]);
+
+        let query = BalanceOfQueryParams {
+            queries: vec![BalanceOfQuery {
+                token_id: TOKEN,
+                address:  BOB,
+            }],
+        };
+        assert_eq!(
+            ledger.balance_of::<()>(&query).map(|response| response.0),
+            Ok(vec![TokenAmountU64(30)])
+        );
+    }
+
+    #[test]
+    fn ledger_errors_test() {
+        let mut state_builder = TestStateBuilder::new();
+        let mut logger = TestLogger::init();
+        let mut ledger: Cis2Ledger<TokenIdU8, TokenAmountU64, _> =
+            Cis2Ledger::new(&mut state_builder);
+
+        let result: LedgerTestResult = ledger.transfer(
+            &ALICE,
+            &ledger_transfer(0, ALICE, BOB),
+            &mut state_builder,
+            &mut logger,
+        );
+        assert_eq!(result, Err(Cis2Error::InvalidTokenId));
+        let result: LedgerTestResult =
+            ledger.mint(TOKEN, TokenAmountU64(u64::MAX), ALICE, &mut state_builder, &mut logger);
+        assert_eq!(result, Ok(()));
+        let result: LedgerTestResult =
+            ledger.mint(TOKEN, TokenAmountU64(1), BOB, &mut state_builder, &mut logger);
+        assert_eq!(result, Err(Cis2Error::Custom(LedgerTestError::Overflow)));
+        let result: LedgerTestResult =
+            ledger.transfer(&BOB, &ledger_transfer(1, ALICE, BOB), &mut state_builder, &mut logger);
+        assert_eq!(result, Err(Cis2Error::Unauthorized));
+        let result: LedgerTestResult =
+            ledger.transfer(&BOB, &ledger_transfer(1, BOB, ALICE), &mut state_builder, &mut logger);
+        assert_eq!(result, Err(Cis2Error::InsufficientFunds));
+        let result: LedgerTestResult =
+            ledger.burn(TokenIdU8(2), TokenAmountU64(0), ALICE, &mut logger);
+        assert_eq!(result, Err(Cis2Error::InvalidTokenId));
+        let query = BalanceOfQueryParams {
+            queries: vec![BalanceOfQuery {
+                token_id: TokenIdU8(2),
+                address:  ALICE,
+            }],
+        };
+        assert_eq!(
+            ledger.balance_of::<()>(&query).map(|response| response.0),
+            Err(Cis2Error::InvalidTokenId)
+        );
+        // Only the mint is logged.
+        assert_eq!(logger.logs.len(), 1);
+    }
+
+    #[test]
+    fn ledger_operators_test() {
+        let mut state_builder = TestStateBuilder::new();
+        let mut logger = TestLogger::init();
+        let mut ledger = Cis2Ledger::new(&mut state_builder);
+        let result: LedgerTestResult =
+            ledger.mint(TOKEN, TokenAmountU64(10), ALICE, &mut state_builder, &mut logger);
+        assert_eq!(result, Ok(()));
+
+        let add = UpdateOperator {
+            update:   OperatorUpdate::Add,
+            operator: BOB,
+        };
+        let result: LedgerTestResult =
+            ledger.update_operator(ALICE, add, &mut state_builder, &mut logger);
+        assert_eq!(result, Ok(()));
+        assert!(ledger.is_operator(&ALICE, &BOB));
+        assert!(!ledger.is_operator(&BOB, &ALICE));
+        let query = OperatorOfQueryParams {
+            queries: vec![OperatorOfQuery {
+                owner:   ALICE,
+                address: BOB,
+            }],
+        };
+        assert_eq!(ledger.operator_of(&query).0, vec![true]);
+
+        // The operator can transfer the tokens of the owner.
+        let result: LedgerTestResult =
+            ledger.transfer(&BOB, &ledger_transfer(4, ALICE, BOB), &mut state_builder, &mut logger);
+        assert_eq!(result, Ok(()));
+        assert_eq!(ledger.balance(&TOKEN, &BOB), TokenAmountU64(4));
+
+        let remove = UpdateOperator {
+            update:   OperatorUpdate::Remove,
+            operator: BOB,
+        };
+        let result: LedgerTestResult =
+            ledger.update_operator(ALICE, remove, &mut state_builder, &mut logger);
+        assert_eq!(result, Ok(()));
+        assert!(!ledger.is_operator(&ALICE, &BOB));
+        let result: LedgerTestResult =
+            ledger.transfer(&BOB, &ledger_transfer(4, ALICE, BOB), &mut state_builder, &mut logger);
+        assert_eq!(result, Err(Cis2Error::Unauthorized));
+        assert_eq!(
+            logger.logs[1],
+            to_bytes(&Cis2Event::<TokenIdU8, TokenAmountU64>::UpdateOperator(
+                UpdateOperatorEvent {
+                    update:   OperatorUpdate::Add,
+                    owner:    ALICE,
+                    operator: BOB,
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn allowances_test() {
+        let mut state_builder = TestStateBuilder::new();
+        let mut logger = TestLogger::init();
+        let mut ledger = Cis2Ledger::new(&mut state_builder);
+        let mut allowances = Allowances::new(&mut state_builder);
+        let result: LedgerTestResult =
+            ledger.mint(TOKEN, TokenAmountU64(10), ALICE, &mut state_builder, &mut logger);
+        assert_eq!(result, Ok(()));
+        let expiry = Timestamp::from_timestamp_millis(100);
+        let now = Timestamp::from_timestamp_millis(50);
+
+        let approve = Approve {
+            token_id: TOKEN,
+            spender: BOB,
+            amount: TokenAmountU64(5),
+            expiry,
+        };
+        let result: LedgerTestResult =
+            allowances.approve(&ledger, ALICE, approve, now, &mut logger);
+        assert_eq!(result, Ok(()));
+        assert_eq!(allowances.allowance(&TOKEN, &ALICE, &BOB, now), TokenAmountU64(5));
+        assert_eq!(allowances.allowance(&TOKEN, &BOB, &ALICE, now), TokenAmountU64(0));
+
+        // Bob spends from the allowance, while Alice transfers her own tokens.
+        let result: LedgerTestResult = ledger.transfer_with_allowance(
+            &BOB,
+            &ledger_transfer(3, ALICE, BOB),
+            &mut allowances,
+            now,
+            &mut state_builder,
+            &mut logger,
+        );
+        assert_eq!(result, Ok(()));
+        let result: LedgerTestResult = ledger.transfer_with_allowance(
+            &ALICE,
+            &ledger_transfer(1, ALICE, BOB),
+            &mut allowances,
+            now,
+            &mut state_builder,
+            &mut logger,
+        );
+        assert_eq!(result, Ok(()));
+        assert_eq!(ledger.balance(&TOKEN, &BOB), TokenAmountU64(4));
+        assert_eq!(allowances.allowance(&TOKEN, &ALICE, &BOB, now), TokenAmountU64(2));
+        let result: LedgerTestResult = ledger.transfer_with_allowance(
+            &BOB,
+            &ledger_transfer(3, ALICE, BOB),
+            &mut allowances,
+            now,
+            &mut state_builder,
+            &mut logger,
+        );
+        assert_eq!(result, Err(Cis2Error::Unauthorized));
+
+        let decrease = DecreaseAllowance {
+            token_id: TOKEN,
+            spender:  BOB,
+            amount:   TokenAmountU64(1),
+        };
+        let result: LedgerTestResult = allowances.decrease(ALICE, decrease, now, &mut logger);
+        assert_eq!(result, Ok(()));
+        let query = AllowanceQueryParams {
+            queries: vec![AllowanceQuery {
+                token_id: TOKEN,
+                owner:    ALICE,
+                spender:  BOB,
+            }],
+        };
+        assert_eq!(allowances.allowance_of(&query, now).0, vec![TokenAmountU64(1)]);
+        // The allowance cannot be spent after it expires.
+        let later = Timestamp::from_timestamp_millis(101);
+        assert_eq!(allowances.allowance_of(&query, later).0, vec![TokenAmountU64(0)]);
+        let result: LedgerTestResult = ledger.transfer_with_allowance(
+            &BOB,
+            &ledger_transfer(1, ALICE, BOB),
+            &mut allowances,
+            later,
+            &mut state_builder,
+            &mut logger,
+        );
+        assert_eq!(result, Err(Cis2Error::Unauthorized));
+
+        assert_eq!(
+            logger.logs[1],
+            to_bytes(&Cis2AllowanceEvent::UpdateAllowance(UpdateAllowanceEvent {
+                token_id: TOKEN,
+                owner: ALICE,
+                spender: BOB,
+                amount: TokenAmountU64(5),
+                expiry,
+            }))
+        );
+        assert_eq!(
+            logger.logs[2],
+            to_bytes(&Cis2AllowanceEvent::UpdateAllowance(UpdateAllowanceEvent {
+                token_id: TOKEN,
+                owner: ALICE,
+                spender: BOB,
+                amount: TokenAmountU64(2),
+                expiry,
+            }))
+        );
+        // Mint, approve, two transfers with the spending of the allowance, and
+        // decrease.
+        assert_eq!(logger.logs.len(), 6);
+        // The expired allowance was removed when Bob tried to spend it.
+        assert!(allowances.allowances.is_empty());
+
+        let approve = Approve {
+            token_id: TokenIdU8(2),
+            spender: BOB,
+            amount: TokenAmountU64(5),
+            expiry,
+        };
+        let result: LedgerTestResult =
+            allowances.approve(&ledger, ALICE, approve, now, &mut logger);
+        assert_eq!(result, Err(Cis2Error::InvalidTokenId));
+        // An approval that has already expired is not stored.
+        let approve = Approve {
+            token_id: TOKEN,
+            spender: BOB,
+            amount: TokenAmountU64(5),
+            expiry,
+        };
+        let result: LedgerTestResult =
+            allowances.approve(&ledger, ALICE, approve, later, &mut logger);
+        assert_eq!(result, Ok(()));
+        assert!(allowances.allowances.is_empty());
+    }
+
+    #[test]
+    fn royalties_test() {
+        let mut state_builder = TestStateBuilder::new();
+        let mut royalties = Royalties::new(&mut state_builder);
+        let creator = ALICE_ACCOUNT;
+        let artist = AccountAddress([1u8; 32]);
+        let other_token = TokenIdU8(2);
+        assert_eq!(royalties.royalty(&TOKEN), None);
+
+        let default = Royalty::new(creator, 250).unwrap();
+        royalties.set_default(Some(default));
+        let royalty = Royalty::new(artist, 1000).unwrap();
+        royalties.set_token_royalty(other_token, royalty);
+        assert_eq!(Royalty::new(artist, MAX_ROYALTY_BASIS_POINTS + 1), Err(InvalidRoyaltyError));
+        // A royalty that is too high is also rejected when parsing.
+        let mut bytes = to_bytes(&artist);
+        bytes.extend_from_slice(&(MAX_ROYALTY_BASIS_POINTS + 1).to_le_bytes());
+        assert!(from_bytes::<Royalty>(&bytes).is_err());
+        assert_eq!(from_bytes::<Royalty>(&to_bytes(&royalty)), Ok(royalty));
+
+        let query = RoyaltyInfoQueryParams {
+            queries: vec![
+                RoyaltyInfoQuery {
+                    token_id:   TOKEN,
+                    sale_price: Amount::from_micro_ccd(1_999),
+                },
+                RoyaltyInfoQuery {
+                    token_id:   other_token,
+                    sale_price: Amount::from_micro_ccd(1_999),
+                },
+            ],
+        };
+        // The royalties are rounded down.
+        assert_eq!(royalties.royalty_info_of(&query).0, vec![
+            Some(RoyaltyInfo {
+                receiver: creator,
+                amount:   Amount::from_micro_ccd(49),
+            }),
+            Some(RoyaltyInfo {
+                receiver: artist,
+                amount:   Amount::from_micro_ccd(199),
+            }),
+        ]);
+        let full = Royalty::new(artist, MAX_ROYALTY_BASIS_POINTS).unwrap();
+        assert_eq!(full.royalty_info(Amount::from_micro_ccd(u64::MAX)).amount.micro_ccd, u64::MAX);
+
+        // Without the default royalty, only the token with its own royalty has one.
+        royalties.set_default(None);
+        royalties.remove_token_royalty(&other_token);
+        assert_eq!(royalties.royalty(&other_token), None);
+        royalties.set_token_royalty(other_token, royalty);
+        assert_eq!(royalties.royalty_info_of(&query).0, vec![
+            None,
+            Some(RoyaltyInfo {
+                receiver: artist,
+                amount:   Amount::from_micro_ccd(199),
+            }),
+        ]);
+    }
+
+    #[test]
+    fn transfer_notify_receiver_test() {
+        let receiver = ContractAddress {
+            index:    1,
+            subindex: 0,
+        };
+        let mut host = TestHost::new((), TestStateBuilder::new());
+        let received = Rc::new(Cell::new(false));
+        let received_clone = received.clone();
+        host.setup_mock_entrypoint(
+            receiver,
+            OwnedEntrypointName::new_unchecked("onReceivingCIS2".into()),
+            MockFn::new_v1(move |parameter, _, _, _| {
+                let params: OnReceivingCis2Params<TokenIdU8, TokenAmountU64> =
+                    from_bytes(parameter.0).expect("Failed to parse parameter");
+                assert_eq!(params.amount, TokenAmountU64(5));
+                assert_eq!(params.from, ALICE);
+                received_clone.set(true);
+                Ok((false, ()))
+            }),
+        );
+
+        let result: LedgerTestResult = ledger_transfer(5, ALICE, BOB).notify_receiver(&mut host);
+        assert_eq!(result, Ok(()));
+        assert!(!received.get());
+        let result: LedgerTestResult =
+            ledger_transfer(5, ALICE, Address::Contract(receiver)).notify_receiver(&mut host);
+        assert_eq!(result, Ok(()));
+        assert!(received.get());
+    }
+
+    #[derive(Serial, DeserialWithState, StateClone)]
+    #[concordium(state_parameter = "S")]
+    struct EntrypointsTestState<S: HasStateApi> {
+        ledger: Cis2Ledger<TokenIdU8, TokenAmountU64, S>,
+    }
+
+    impl<S: HasStateApi> Cis2State<TokenIdU8, TokenAmountU64, S> for EntrypointsTestState<S> {
+        fn ledger(&self) -> &Cis2Ledger<TokenIdU8, TokenAmountU64, S> { &self.ledger }
+
+        fn ledger_mut(&mut self) -> &mut Cis2Ledger<TokenIdU8, TokenAmountU64, S> {
+            &mut self.ledger
+        }
+
+        fn token_metadata(&self, token_id: &TokenIdU8) -> Option<MetadataUrl> {
+            if self.ledger.contains_token(token_id) {
+                Some(MetadataUrl {
+                    url:  "https://example.com/token".into(),
+                    hash: None,
+                })
+            } else {
+                None
+            }
+        }
+    }
+
+    cis2_entrypoints!(
+        contract = "test_token",
+        state = EntrypointsTestState,
+        token_id = TokenIdU8,
+        amount = TokenAmountU64,
+    );
+
+    #[test]
+    fn cis2_entrypoints_test() {
+        let mut state_builder = TestStateBuilder::new();
+        let mut logger = TestLogger::init();
+        let mut ledger = Cis2Ledger::new(&mut state_builder);
+        let result: LedgerTestResult =
+            ledger.mint(TOKEN, TokenAmountU64(10), ALICE, &mut state_builder, &mut logger);
+        assert_eq!(result, Ok(()));
+        let mut host = TestHost::new(
+            EntrypointsTestState {
+                ledger,
+            },
+            state_builder,
+        );
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(ALICE);
+
+        let parameter = to_bytes(&TransferParams::from(vec![ledger_transfer(3, ALICE, BOB)]));
+        ctx.set_parameter(&parameter);
+        assert_eq!(cis2_transfer(&ctx, &mut host, &mut logger), Ok(()));
+
+        let parameter = to_bytes(&BalanceOfQueryParams {
+            queries: vec![BalanceOfQuery {
+                token_id: TOKEN,
+                address:  BOB,
+            }],
+        });
+        ctx.set_parameter(&parameter);
+        assert_eq!(
+            cis2_balance_of(&ctx, &host).map(|response| response.0),
+            Ok(vec![TokenAmountU64(3)])
+        );
+
+        let parameter = to_bytes(&TokenMetadataQueryParams {
+            queries: vec![TOKEN, TokenIdU8(2)],
+        });
+        ctx.set_parameter(&parameter);
+        assert_eq!(
+            cis2_token_metadata(&ctx, &host).map(|response| response.0.len()),
+            Err(Cis2Error::InvalidTokenId)
+        );
+
+        let parameter = to_bytes(&SupportsQueryParams {
+            queries: vec![
+                CIS2_STANDARD_IDENTIFIER.to_owned(),
+                StandardIdentifierOwned::new_unchecked("CIS-3".into()),
+            ],
+        });
+        ctx.set_parameter(&parameter);
+        let response = cis2_supports(&ctx, &host).expect("The query succeeds.");
+        assert!(matches!(response.results[..], [SupportResult::Support, SupportResult::NoSupport]));
+    }
+
+    #[derive(Serial, DeserialWithState, StateClone)]
+    #[concordium(state_parameter = "S")]
+    struct PermitTestState<S: HasStateApi> {
+        ledger:  Cis2Ledger<TokenIdU8, TokenAmountU64, S>,
+        permits: PermitRegistry<S>,
+    }
+
+    impl<S: HasStateApi> Cis2State<TokenIdU8, TokenAmountU64, S> for PermitTestState<S> {
+        fn ledger(&self) -> &Cis2Ledger<TokenIdU8, TokenAmountU64, S> { &self.ledger }
+
+        fn ledger_mut(&mut self) -> &mut Cis2Ledger<TokenIdU8, TokenAmountU64, S> {
+            &mut self.ledger
+        }
+
+        fn token_metadata(&self, _token_id: &TokenIdU8) -> Option<MetadataUrl> { None }
+    }
+
+    impl<S: HasStateApi> PermitState<S> for PermitTestState<S> {
+        type TokenAmount = TokenAmountU64;
+        type TokenId = TokenIdU8;
+
+        fn permits(&self) -> &PermitRegistry<S> { &self.permits }
+
+        fn permits_mut(&mut self) -> &mut PermitRegistry<S> { &mut self.permits }
+    }
+
+    type PermitTestResult = Result<(), Cis2Error<Cis2EntrypointError>>;
+
+    const ALICE_ACCOUNT: AccountAddress = AccountAddress([0u8; 32]);
+    const ALICE_PUBLIC_KEY: PublicKeyEd25519 = PublicKeyEd25519([7u8; 32]);
+    const PERMIT_CONTRACT: ContractAddress = ContractAddress {
+        index:    2,
+        subindex: 0,
+    };
+
+    fn permit_params(nonce: u64, entry_point: &str, payload: Vec<u8>) -> PermitParams {
+        PermitParams {
+            signature: SignatureEd25519([1u8; 64]),
+            signer:    ALICE_ACCOUNT,
+            message:   PermitMessage {
+                contract_address: PERMIT_CONTRACT,
+                nonce,
+                expiry: Timestamp::from_timestamp_millis(100),
+                entry_point: OwnedEntrypointName::new_unchecked(entry_point.into()),
+                payload,
+            },
+        }
+    }
+
+    fn permit_test_host() -> TestHost<PermitTestState<TestStateApi>> {
+        let mut state_builder = TestStateBuilder::new();
+        let mut logger = TestLogger::init();
+        let mut ledger = Cis2Ledger::new(&mut state_builder);
+        let result: LedgerTestResult =
+            ledger.mint(TOKEN, TokenAmountU64(10), ALICE, &mut state_builder, &mut logger);
+        assert_eq!(result, Ok(()));
+        let mut permits = PermitRegistry::new(&mut state_builder);
+        permits.register_public_key(ALICE_ACCOUNT, ALICE_PUBLIC_KEY);
+        TestHost::new(
+            PermitTestState {
+                ledger,
+                permits,
+            },
+            state_builder,
+        )
+    }
+
+    fn permit_test_crypto_primitives(valid_signature: bool) -> TestCryptoPrimitives {
+        let crypto_primitives = TestCryptoPrimitives::mocked();
+        crypto_primitives.setup_hash_sha2_256_mock(|_| HashSha2256([3u8; 32]));
+        crypto_primitives.setup_verify_ed25519_signature_mock(move |_, _, _| valid_signature);
+        crypto_primitives
+    }
+
+    #[test]
+    fn permit_test() {
+        let mut host = permit_test_host();
+        let mut logger = TestLogger::init();
+        let crypto_primitives = permit_test_crypto_primitives(true);
+        let mut ctx = TestReceiveContext::empty();
+        // The permit is submitted by Bob on behalf of Alice.
+        ctx.set_sender(BOB);
+        ctx.set_self_address(PERMIT_CONTRACT);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(50));
+
+        let transfers = to_bytes(&TransferParams::from(vec![ledger_transfer(4, ALICE, BOB)]));
+        let parameter = to_bytes(&permit_params(0, "transfer", transfers));
+        ctx.set_parameter(&parameter);
+        let result: PermitTestResult =
+            permit_with_registered_key(&ctx, &mut host, &mut logger, &crypto_primitives);
+        assert_eq!(result, Ok(()));
+        assert_eq!(host.state().ledger.balance(&TOKEN, &BOB), TokenAmountU64(4));
+        assert_eq!(host.state().permits.nonce(&ALICE_ACCOUNT), 1);
+        assert_eq!(crypto_primitives.verify_ed25519_signature_calls(), vec![(
+            ALICE_PUBLIC_KEY,
+            SignatureEd25519([1u8; 64]),
+            vec![3u8; 32]
+        )]);
+        assert_eq!(
+            logger.logs[0],
+            to_bytes(&Cis3Event::Nonce(NonceEvent {
+                nonce:   0,
+                account: ALICE_ACCOUNT,
+            }))
+        );
+        assert_eq!(logger.logs.len(), 2);
+
+        let update = UpdateOperatorParams(vec![UpdateOperator {
+            update:   OperatorUpdate::Add,
+            operator: BOB,
+        }]);
+        let parameter = to_bytes(&permit_params(1, "updateOperator", to_bytes(&update)));
+        ctx.set_parameter(&parameter);
+        let result: PermitTestResult =
+            permit_with_registered_key(&ctx, &mut host, &mut logger, &crypto_primitives);
+        assert_eq!(result, Ok(()));
+        assert!(host.state().ledger.is_operator(&ALICE, &BOB));
+        assert_eq!(host.state().permits.nonce(&ALICE_ACCOUNT), 2);
+    }
+
+    #[test]
+    fn permit_errors_test() {
+        let mut host = permit_test_host();
+        let mut logger = TestLogger::init();
+        let crypto_primitives = permit_test_crypto_primitives(true);
+        let mut ctx = TestReceiveContext::empty();
+        ctx.set_sender(BOB);
+        ctx.set_self_address(PERMIT_CONTRACT);
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(50));
+        let transfers = to_bytes(&TransferParams::from(vec![ledger_transfer(4, ALICE, BOB)]));
+        let mut use_permit = |params: &PermitParams, crypto_primitives: &TestCryptoPrimitives| {
+            host.state_mut()
+                .permits
+                .use_permit(params, &ctx, crypto_primitives)
+                .map(|event| event.nonce)
+        };
+
+        let params = permit_params(1, "transfer", transfers.clone());
+        assert_eq!(use_permit(&params, &crypto_primitives), Err(PermitError::NonceMismatch));
+        let mut params = permit_params(0, "transfer", transfers.clone());
+        params.message.contract_address = ContractAddress {
+            index:    3,
+            subindex: 0,
+        };
+        assert_eq!(use_permit(&params, &crypto_primitives), Err(PermitError::WrongContract));
+        let mut params = permit_params(0, "transfer", transfers.clone());
+        params.message.expiry = Timestamp::from_timestamp_millis(49);
+        assert_eq!(use_permit(&params, &crypto_primitives), Err(PermitError::Expired));
+        let mut params = permit_params(0, "transfer", transfers.clone());
+        params.signer = AccountAddress([1u8; 32]);
+        assert_eq!(use_permit(&params, &crypto_primitives), Err(PermitError::MissingPublicKey));
+        let params = permit_params(0, "transfer", transfers);
+        assert_eq!(
+            use_permit(&params, &permit_test_crypto_primitives(false)),
+            Err(PermitError::WrongSignature)
+        );
+        assert_eq!(use_permit(&params, &crypto_primitives), Ok(0));
+        // The permit cannot be used again.
+        assert_eq!(use_permit(&params, &crypto_primitives), Err(PermitError::NonceMismatch));
+
+        let parameter = to_bytes(&permit_params(1, "mint", Vec::new()));
+        ctx.set_parameter(&parameter);
+        let result: PermitTestResult =
+            permit_with_registered_key(&ctx, &mut host, &mut logger, &crypto_primitives);
+        assert_eq!(
+            result,
+            Err(Cis2Error::Custom(Cis2EntrypointError::Permit(PermitError::WrongEntrypoint)))
+        );
+        // The nonce cannot be incremented beyond the largest `u64`.
+        host.state_mut().permits.nonces.insert(ALICE_ACCOUNT, u64::MAX);
+        let params = permit_params(u64::MAX, "transfer", Vec::new());
+        assert_eq!(
+            host.state_mut().permits.use_permit(&params, &ctx, &crypto_primitives).map(|_| ()),
+            Err(PermitError::NonceOverflow)
+        );
+        assert_eq!(host.state().permits.nonce(&ALICE_ACCOUNT), u64::MAX);
     }
 }
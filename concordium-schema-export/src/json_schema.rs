@@ -0,0 +1,174 @@
+//! Conversion of schema types to [JSON Schema](https://json-schema.org/)
+//! (draft 2020-12) describing their JSON representation.
+use crate::{metadata_lines, named_types, upper_camel_case};
+use concordium_std::{
+    schema::{Fields, SizeLength, Type},
+    ContractSchema,
+};
+use serde_json::{json, Map, Value};
+
+/// The JSON Schema of the contract, which contains the schema of every type
+/// declared by the contract in `$defs`, named as described in
+/// [`named_types`](crate::named_types).
+///
+/// The documentation comment of the init function is the description of the
+/// contract, and the documentation comments of the receive functions are the
+/// descriptions of their parameters. The version, standards and repository of
+/// the contract are given in a comment.
+pub fn contract_json_schema(schema: &ContractSchema) -> Value {
+    let mut defs: Map<String, Value> =
+        named_types(schema).into_iter().map(|(name, ty)| (name, type_json_schema(ty))).collect();
+    for (name, entrypoint) in &schema.entrypoints {
+        let parameter = format!("{}Parameter", upper_camel_case(name));
+        if let Some(def) = defs.get_mut(&parameter) {
+            if !entrypoint.doc.is_empty() {
+                def["description"] = json!(entrypoint.doc);
+            }
+        }
+    }
+    let mut json_schema = json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": schema.name,
+        "$defs": defs,
+    });
+    if !schema.metadata.doc.is_empty() {
+        json_schema["description"] = json!(schema.metadata.doc);
+    }
+    let metadata = metadata_lines(&schema.metadata);
+    if !metadata.is_empty() {
+        json_schema["$comment"] = json!(metadata.join("\n"));
+    }
+    json_schema
+}
+
+/// The JSON Schema of the JSON representation of values of the type.
+pub fn type_json_schema(ty: &Type) -> Value {
+    match ty {
+        Type::Unit => empty_array(),
+        Type::Bool => json!({"type": "boolean"}),
+        Type::U8 => integer(u8::MIN, u8::MAX),
+        Type::U16 => integer(u16::MIN, u16::MAX),
+        Type::U32 => integer(u32::MIN, u32::MAX),
+        Type::U64 => integer(u64::MIN, u64::MAX),
+        Type::I8 => integer(i8::MIN, i8::MAX),
+        Type::I16 => integer(i16::MIN, i16::MAX),
+        Type::I32 => integer(i32::MIN, i32::MAX),
+        Type::I64 => integer(i64::MIN, i64::MAX),
+        Type::U128 | Type::Amount | Type::ULeb128(_) => pattern("^[0-9]+$"),
+        Type::I128 | Type::ILeb128(_) => pattern("^-?[0-9]+$"),
+        Type::AccountAddress => pattern("^[1-9A-HJ-NP-Za-km-z]{50}$"),
+        Type::ContractAddress => json!({
+            "type": "object",
+            "properties": {
+                "index": integer(u64::MIN, u64::MAX),
+                "subindex": integer(u64::MIN, u64::MAX),
+            },
+            "required": ["index", "subindex"],
+            "additionalProperties": false,
+        }),
+        Type::Timestamp => json!({"type": "string", "format": "date-time"}),
+        Type::Duration => pattern("^\\s*([0-9]+(d|h|m|s|ms)\\s*)+$"),
+        Type::Pair(first, second) => tuple(vec![type_json_schema(first), type_json_schema(second)]),
+        Type::List(size_length, item) => {
+            with_max_items(json!({"type": "array", "items": type_json_schema(item)}), size_length)
+        }
+        Type::Set(size_length, item) => with_max_items(
+            json!({"type": "array", "items": type_json_schema(item), "uniqueItems": true}),
+            size_length,
+        ),
+        Type::Map(size_length, key, value) => with_max_items(
+            json!({
+                "type": "array",
+                "items": tuple(vec![type_json_schema(key), type_json_schema(value)]),
+            }),
+            size_length,
+        ),
+        Type::Array(length, item) => json!({
+            "type": "array",
+            "items": type_json_schema(item),
+            "minItems": length,
+            "maxItems": length,
+        }),
+        Type::Struct(fields) => fields_json_schema(fields),
+        Type::Enum(variants) => {
+            enum_json_schema(variants.iter().map(|(name, fields)| (name, fields)))
+        }
+        Type::TaggedEnum(variants) => {
+            enum_json_schema(variants.values().map(|(name, fields)| (name, fields)))
+        }
+        Type::String(_) => json!({"type": "string"}),
+        Type::ContractName(_) => json!({
+            "type": "object",
+            "properties": {"contract": {"type": "string"}},
+            "required": ["contract"],
+            "additionalProperties": false,
+        }),
+        Type::ReceiveName(_) => json!({
+            "type": "object",
+            "properties": {"contract": {"type": "string"}, "func": {"type": "string"}},
+            "required": ["contract", "func"],
+            "additionalProperties": false,
+        }),
+        Type::ByteList(_) => pattern("^([0-9a-fA-F]{2})*$"),
+        Type::ByteArray(length) => pattern(&format!("^([0-9a-fA-F]{{2}}){{{}}}$", length)),
+    }
+}
+
+/// The JSON Schema of the fields of a struct or an enum variant.
+fn fields_json_schema(fields: &Fields) -> Value {
+    match fields {
+        Fields::Named(fields) => {
+            let properties: Map<String, Value> =
+                fields.iter().map(|(name, ty)| (name.clone(), type_json_schema(ty))).collect();
+            let required: Vec<&String> = fields.iter().map(|(name, _)| name).collect();
+            json!({
+                "type": "object",
+                "properties": properties,
+                "required": required,
+                "additionalProperties": false,
+            })
+        }
+        Fields::Unnamed(fields) => tuple(fields.iter().map(type_json_schema).collect()),
+        Fields::None => empty_array(),
+    }
+}
+
+/// The JSON Schema of an enum, where each value is an object with the name of
+/// the variant as the only key.
+fn enum_json_schema<'a>(variants: impl Iterator<Item = (&'a String, &'a Fields)>) -> Value {
+    let variants: Vec<Value> = variants
+        .map(|(name, fields)| {
+            json!({
+                "type": "object",
+                "properties": {name.as_str(): fields_json_schema(fields)},
+                "required": [name],
+                "additionalProperties": false,
+            })
+        })
+        .collect();
+    json!({ "oneOf": variants })
+}
+
+fn integer(minimum: impl Into<Value>, maximum: impl Into<Value>) -> Value {
+    json!({"type": "integer", "minimum": minimum.into(), "maximum": maximum.into()})
+}
+
+fn pattern(pattern: &str) -> Value { json!({"type": "string", "pattern": pattern}) }
+
+fn empty_array() -> Value { json!({"type": "array", "maxItems": 0}) }
+
+fn tuple(items: Vec<Value>) -> Value {
+    json!({"type": "array", "prefixItems": items, "items": false})
+}
+
+/// Restrict the number of items of an array to what fits in the size length.
+fn with_max_items(mut schema: Value, size_length: &SizeLength) -> Value {
+    let max_items = match size_length {
+        SizeLength::U8 => u64::from(u8::MAX),
+        SizeLength::U16 => u64::from(u16::MAX),
+        SizeLength::U32 => u64::from(u32::MAX),
+        SizeLength::U64 => return schema,
+    };
+    schema["maxItems"] = json!(max_items);
+    schema
+}
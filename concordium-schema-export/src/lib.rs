@@ -0,0 +1,276 @@
+//! This library exports the schema of a Concordium smart contract as [JSON
+//! Schema](https://json-schema.org/) and TypeScript, without building the
+//! contract to Wasm.
+//!
+//! The schema of a contract is collected natively from the function
+//! `contract_schema`, which
+//! [`#[contract]`](https://docs.rs/concordium-std/latest/concordium_std/attr.contract.html)
+//! generates when `concordium-std` is built with the feature `build-schema`.
+//! For every parameter, return value, error and event type of the contract,
+//! the library emits a JSON Schema definition of its JSON representation, a
+//! TypeScript type definition, and TypeScript functions for encoding the JSON
+//! representation into the binary format of the contract and decoding it
+//! again.
+//!
+//! # Example
+//!
+//! ```ignore
+//! #[test]
+//! #[cfg(feature = "build-schema")]
+//! fn export_schema() {
+//!     concordium_schema_export::export(&contract::contract_schema(), "schema")
+//!         .expect("Exporting the schema failed");
+//! }
+//! ```
+//!
+//! # JSON representation
+//!
+//! Values are represented in JSON the same way as in other Concordium tools:
+//! - Integers of at most 64 bits are JSON numbers, while 128-bit integers,
+//!   LEB128 integers and amounts in microCCD are strings.
+//! - Account addresses are Base58Check strings and contract addresses are
+//!   objects `{"index": .., "subindex": ..}`.
+//! - Timestamps are RFC 3339 strings and durations are strings such as `"1d 2h
+//!   3m 4s 5ms"`.
+//! - Pairs, lists, sets and arrays are JSON arrays, and maps are arrays of
+//!   key-value pairs.
+//! - Structs with named fields are objects, structs with unnamed fields are
+//!   arrays, and structs without fields are the empty array. Enum values are
+//!   objects with the name of the variant as the only key, and the fields of
+//!   the variant as the value.
+//! - Byte lists and byte arrays are hex strings.
+//!
+//! In the TypeScript module, 64-bit integers and the index and subindex of
+//! contract addresses are `bigint`s, since not all of them are exactly
+//! representable as a `number`. The encoders also accept them as numbers, if
+//! they are safe integers, and as decimal strings.
+use concordium_std::{schema::Type, ContractMetadata, ContractSchema};
+use std::{fs, io, path::Path};
+
+mod json_schema;
+mod typescript;
+
+pub use json_schema::{contract_json_schema, type_json_schema};
+pub use typescript::contract_typescript;
+
+/// Write the JSON Schema and the TypeScript module of the contract to the files
+/// `<contract>.schema.json` and `<contract>.ts` in the given directory, which
+/// is created if it does not exist.
+pub fn export(schema: &ContractSchema, dir: impl AsRef<Path>) -> io::Result<()> {
+    let dir = dir.as_ref();
+    fs::create_dir_all(dir)?;
+    let json_schema = serde_json::to_string_pretty(&contract_json_schema(schema))?;
+    fs::write(dir.join(format!("{}.schema.json", schema.name)), json_schema + "\n")?;
+    fs::write(dir.join(format!("{}.ts", schema.name)), contract_typescript(schema))?;
+    Ok(())
+}
+
+/// The types declared by the contract together with the names they are
+/// exported under. The types of the init function are named `InitParameter`,
+/// `InitReturnValue` and `InitError`, the types of a receive function are
+/// named after the entrypoint in upper camel case followed by `Parameter`,
+/// `ReturnValue` or `Error`, and the event type is named `Event`.
+pub fn named_types(schema: &ContractSchema) -> Vec<(String, &Type)> {
+    let mut types = Vec::new();
+    let functions = schema.functions.init.iter().map(|init| ("Init".to_string(), init)).chain(
+        schema.functions.receive.iter().map(|(name, function)| (upper_camel_case(name), function)),
+    );
+    for (prefix, function) in functions {
+        let function_types = [
+            ("Parameter", &function.parameter),
+            ("ReturnValue", &function.return_value),
+            ("Error", &function.error),
+        ];
+        for (suffix, ty) in function_types.iter() {
+            if let Some(ty) = *ty {
+                types.push((format!("{}{}", prefix, suffix), ty));
+            }
+        }
+    }
+    if let Some(event) = &schema.event {
+        types.push(("Event".to_string(), event));
+    }
+    types
+}
+
+/// The version, standards and repository of the contract as lines of the form
+/// `Version: 1.2.0`, omitting those that are not declared.
+fn metadata_lines(metadata: &ContractMetadata) -> Vec<String> {
+    let mut lines = Vec::new();
+    if let Some(version) = &metadata.version {
+        lines.push(format!("Version: {}", version));
+    }
+    if !metadata.standards.is_empty() {
+        lines.push(format!("Standards: {}", metadata.standards.join(", ")));
+    }
+    if let Some(repository) = &metadata.repository {
+        lines.push(format!("Repository: {}", repository));
+    }
+    lines
+}
+
+/// Convert the name of an entrypoint such as `balanceOf` or `set_value` to
+/// upper camel case.
+fn upper_camel_case(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+    let mut capitalize = true;
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            if capitalize {
+                result.push(c.to_ascii_uppercase());
+            } else {
+                result.push(c);
+            }
+            capitalize = false;
+        } else {
+            capitalize = true;
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use concordium_std::{
+        collections::BTreeMap,
+        schema::{ContractV2, Fields, FunctionV2, SizeLength},
+        EntrypointMetadata,
+    };
+    use serde_json::json;
+
+    fn example_schema() -> ContractSchema {
+        let mut receive = BTreeMap::new();
+        receive.insert("balanceOf".to_string(), FunctionV2 {
+            parameter:    Some(Type::List(SizeLength::U16, Box::new(Type::AccountAddress))),
+            return_value: Some(Type::List(SizeLength::U16, Box::new(Type::U64))),
+            error:        None,
+        });
+        receive.insert("set_value".to_string(), FunctionV2 {
+            parameter:    Some(Type::Struct(Fields::Named(vec![
+                ("owner".to_string(), Type::ContractAddress),
+                ("amount".to_string(), Type::Amount),
+            ]))),
+            return_value: None,
+            error:        Some(Type::Enum(vec![
+                ("ParseError".to_string(), Fields::None),
+                ("Custom".to_string(), Fields::Unnamed(vec![Type::I32])),
+            ])),
+        });
+        let mut events = BTreeMap::new();
+        events.insert(255, ("Transfer".to_string(), Fields::Unnamed(vec![Type::U128])));
+        let mut entrypoints = BTreeMap::new();
+        entrypoints.insert("balanceOf".to_string(), EntrypointMetadata {
+            doc:  "Query the balances of accounts.".to_string(),
+            view: true,
+        });
+        entrypoints.insert("set_value".to_string(), EntrypointMetadata::default());
+        ContractSchema {
+            name: "example".to_string(),
+            functions: ContractV2 {
+                init: Some(FunctionV2 {
+                    parameter:    Some(Type::Unit),
+                    return_value: None,
+                    error:        None,
+                }),
+                receive,
+            },
+            event: Some(Type::TaggedEnum(events)),
+            metadata: ContractMetadata {
+                version:    Some("1.2.0".to_string()),
+                standards:  vec!["CIS-0".to_string(), "CIS-2".to_string()],
+                repository: None,
+                doc:        "An example contract.".to_string(),
+            },
+            entrypoints,
+        }
+    }
+
+    #[test]
+    fn upper_camel_case_names() {
+        assert_eq!(upper_camel_case("balanceOf"), "BalanceOf");
+        assert_eq!(upper_camel_case("set_value"), "SetValue");
+        assert_eq!(upper_camel_case("tokenMetadata"), "TokenMetadata");
+    }
+
+    #[test]
+    fn named_contract_types() {
+        let schema = example_schema();
+        let names: Vec<_> = named_types(&schema).into_iter().map(|(name, _)| name).collect();
+        assert_eq!(names, [
+            "InitParameter",
+            "BalanceOfParameter",
+            "BalanceOfReturnValue",
+            "SetValueParameter",
+            "SetValueError",
+            "Event"
+        ]);
+    }
+
+    #[test]
+    fn json_schema_of_types() {
+        assert_eq!(
+            type_json_schema(&Type::U8),
+            json!({"type": "integer", "minimum": 0, "maximum": 255})
+        );
+        assert_eq!(
+            type_json_schema(&Type::Pair(
+                Box::new(Type::Bool),
+                Box::new(Type::String(SizeLength::U8))
+            )),
+            json!({
+                "type": "array",
+                "prefixItems": [{"type": "boolean"}, {"type": "string"}],
+                "items": false
+            })
+        );
+        assert_eq!(
+            type_json_schema(&Type::Enum(vec![("None".to_string(), Fields::None)])),
+            json!({"oneOf": [{
+                "type": "object",
+                "properties": {"None": {"type": "array", "maxItems": 0}},
+                "required": ["None"],
+                "additionalProperties": false
+            }]})
+        );
+    }
+
+    #[test]
+    fn json_schema_of_contract() {
+        let schema = contract_json_schema(&example_schema());
+        assert_eq!(schema["title"], "example");
+        assert_eq!(schema["$defs"]["BalanceOfParameter"]["type"], "array");
+        assert_eq!(schema["$defs"]["SetValueParameter"]["required"], json!(["owner", "amount"]));
+        assert!(schema["$defs"]["Event"]["oneOf"].is_array());
+        assert_eq!(schema["description"], "An example contract.");
+        assert_eq!(schema["$comment"], "Version: 1.2.0\nStandards: CIS-0, CIS-2");
+        assert_eq!(
+            schema["$defs"]["BalanceOfParameter"]["description"],
+            "Query the balances of accounts."
+        );
+        assert!(schema["$defs"]["SetValueParameter"].get("description").is_none());
+    }
+
+    #[test]
+    fn typescript_of_contract() {
+        let typescript = contract_typescript(&example_schema());
+        assert!(typescript.contains("export type BalanceOfParameter = string[];"));
+        assert!(typescript.contains("export type BalanceOfReturnValue = bigint[];"));
+        assert!(typescript.contains(
+            "export type SetValueParameter = { owner: { index: bigint; subindex: bigint }; \
+             amount: string };"
+        ));
+        assert!(typescript
+            .contains("export type SetValueError = { ParseError: [] } | { Custom: [number] };"));
+        assert!(typescript.contains("export type Event = { Transfer: [string] };"));
+        assert!(typescript.contains(
+            "export function encodeSetValueParameter(value: SetValueParameter): Uint8Array {"
+        ));
+        assert!(typescript.contains("export function decodeEvent(bytes: Uint8Array): Event {"));
+        assert!(typescript
+            .contains("// An example contract.\n// Version: 1.2.0\n// Standards: CIS-0, CIS-2\n"));
+        assert!(typescript.contains(
+            "/**\n * Query the balances of accounts.\n */\nexport type BalanceOfParameter ="
+        ));
+    }
+}
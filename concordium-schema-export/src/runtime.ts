@@ -0,0 +1,646 @@
+/* eslint-disable @typescript-eslint/no-explicit-any */
+// Runtime for encoding and decoding values in the JSON representation used by
+// Concordium tools into the binary format of the contract, using the schema of
+// each type. Requires ES2020 for BigInt.
+
+/** The number of bytes used for the length of a collection. */
+export type SizeLength = 1 | 2 | 4 | 8;
+
+/** The fields of a struct or an enum variant. */
+export type Fields =
+    | { kind: 'Named'; fields: { name: string; type: SchemaType }[] }
+    | { kind: 'Unnamed'; fields: SchemaType[] }
+    | { kind: 'None' };
+
+/** The schema of a type, describing its binary format. */
+export type SchemaType =
+    | {
+          type:
+              | 'Unit'
+              | 'Bool'
+              | 'U8'
+              | 'U16'
+              | 'U32'
+              | 'U64'
+              | 'U128'
+              | 'I8'
+              | 'I16'
+              | 'I32'
+              | 'I64'
+              | 'I128'
+              | 'Amount'
+              | 'AccountAddress'
+              | 'ContractAddress'
+              | 'Timestamp'
+              | 'Duration';
+      }
+    | { type: 'Pair'; first: SchemaType; second: SchemaType }
+    | { type: 'List' | 'Set'; sizeLength: SizeLength; item: SchemaType }
+    | { type: 'Map'; sizeLength: SizeLength; key: SchemaType; value: SchemaType }
+    | { type: 'Array'; length: number; item: SchemaType }
+    | { type: 'Struct'; fields: Fields }
+    | { type: 'Enum'; tagBytes: 1 | 2; variants: { tag: number; name: string; fields: Fields }[] }
+    | { type: 'String' | 'ContractName' | 'ReceiveName' | 'ByteList'; sizeLength: SizeLength }
+    | { type: 'ULeb128' | 'ILeb128'; maxBytes: number }
+    | { type: 'ByteArray'; length: number };
+
+/** Encode a value in the JSON representation into the binary format. */
+export function encode(schema: SchemaType, value: unknown): Uint8Array {
+    const out: number[] = [];
+    encodeValue(schema, value, out, '');
+    return Uint8Array.from(out);
+}
+
+/** Decode the binary format of a value into the JSON representation. */
+export function decode(schema: SchemaType, bytes: Uint8Array): unknown {
+    const reader = { bytes, offset: 0 };
+    const value = decodeValue(schema, reader, '');
+    if (reader.offset !== bytes.length) {
+        throw new Error(`${bytes.length - reader.offset} bytes remain after decoding the value.`);
+    }
+    return value;
+}
+
+const INTEGERS: Record<string, [number, boolean]> = {
+    U8: [1, false],
+    U16: [2, false],
+    U32: [4, false],
+    U64: [8, false],
+    U128: [16, false],
+    I8: [1, true],
+    I16: [2, true],
+    I32: [4, true],
+    I64: [8, true],
+    I128: [16, true],
+};
+
+function fail(path: string, message: string): never {
+    throw new Error(path === '' ? message : `${path.replace(/^\./, '')}: ${message}`);
+}
+
+function toBigInt(value: unknown, path: string): bigint {
+    if (typeof value === 'number' && Number.isSafeInteger(value)) {
+        return BigInt(value);
+    }
+    if (typeof value === 'string' && /^-?[0-9]+$/.test(value)) {
+        return BigInt(value);
+    }
+    if (typeof value === 'bigint') {
+        return value;
+    }
+    return fail(path, `expected an integer, found ${JSON.stringify(value)}`);
+}
+
+function writeInteger(value: bigint, bytes: number, signed: boolean, out: number[], path: string) {
+    const bits = BigInt(8 * bytes);
+    const min = signed ? -(1n << (bits - 1n)) : 0n;
+    const max = signed ? (1n << (bits - 1n)) - 1n : (1n << bits) - 1n;
+    if (value < min || value > max) {
+        fail(path, `the integer ${value} is out of range`);
+    }
+    let unsigned = value < 0n ? value + (1n << bits) : value;
+    for (let i = 0; i < bytes; i++) {
+        out.push(Number(unsigned & 0xffn));
+        unsigned >>= 8n;
+    }
+}
+
+function writeLength(length: number, sizeLength: SizeLength, out: number[], path: string) {
+    writeInteger(BigInt(length), sizeLength, false, out, path);
+}
+
+function encodeFields(fields: Fields, value: any, out: number[], path: string) {
+    switch (fields.kind) {
+        case 'Named':
+            if (typeof value !== 'object' || value === null || Array.isArray(value)) {
+                fail(path, 'expected an object');
+            }
+            for (const field of fields.fields) {
+                if (!(field.name in value)) {
+                    fail(path, `missing the field '${field.name}'`);
+                }
+                encodeValue(field.type, value[field.name], out, `${path}.${field.name}`);
+            }
+            break;
+        case 'Unnamed':
+            if (!Array.isArray(value) || value.length !== fields.fields.length) {
+                fail(path, `expected an array of ${fields.fields.length} elements`);
+            }
+            fields.fields.forEach((field, i) => encodeValue(field, value[i], out, `${path}.${i}`));
+            break;
+        case 'None':
+            if (!Array.isArray(value) || value.length !== 0) {
+                fail(path, 'expected an empty array');
+            }
+            break;
+    }
+}
+
+function encodeValue(schema: SchemaType, value: any, out: number[], path: string) {
+    switch (schema.type) {
+        case 'Unit':
+            break;
+        case 'Bool':
+            if (typeof value !== 'boolean') {
+                fail(path, 'expected a boolean');
+            }
+            out.push(value ? 1 : 0);
+            break;
+        case 'U8':
+        case 'U16':
+        case 'U32':
+        case 'U64':
+        case 'U128':
+        case 'I8':
+        case 'I16':
+        case 'I32':
+        case 'I64':
+        case 'I128': {
+            const [bytes, signed] = INTEGERS[schema.type];
+            writeInteger(toBigInt(value, path), bytes, signed, out, path);
+            break;
+        }
+        case 'Amount':
+            if (typeof value !== 'string') {
+                fail(path, 'expected the amount in microCCD as a string');
+            }
+            writeInteger(toBigInt(value, path), 8, false, out, path);
+            break;
+        case 'AccountAddress':
+            if (typeof value !== 'string') {
+                fail(path, 'expected an account address');
+            }
+            out.push(...decodeAccountAddress(value, path));
+            break;
+        case 'ContractAddress':
+            if (typeof value !== 'object' || value === null) {
+                fail(path, 'expected an object with an index and a subindex');
+            }
+            writeInteger(toBigInt(value.index, `${path}.index`), 8, false, out, `${path}.index`);
+            writeInteger(toBigInt(value.subindex, `${path}.subindex`), 8, false, out, `${path}.subindex`);
+            break;
+        case 'Timestamp': {
+            const millis = typeof value === 'string' ? Date.parse(value) : NaN;
+            if (Number.isNaN(millis)) {
+                fail(path, 'expected a timestamp in RFC 3339 format');
+            }
+            writeInteger(BigInt(millis), 8, false, out, path);
+            break;
+        }
+        case 'Duration':
+            if (typeof value !== 'string') {
+                fail(path, "expected a duration such as '1d 2h 3m 4s 5ms'");
+            }
+            writeInteger(parseDuration(value, path), 8, false, out, path);
+            break;
+        case 'Pair':
+            if (!Array.isArray(value) || value.length !== 2) {
+                fail(path, 'expected an array of two elements');
+            }
+            encodeValue(schema.first, value[0], out, `${path}.0`);
+            encodeValue(schema.second, value[1], out, `${path}.1`);
+            break;
+        case 'List':
+        case 'Set':
+            if (!Array.isArray(value)) {
+                fail(path, 'expected an array');
+            }
+            writeLength(value.length, schema.sizeLength, out, path);
+            value.forEach((item: unknown, i: number) => encodeValue(schema.item, item, out, `${path}[${i}]`));
+            break;
+        case 'Map':
+            if (!Array.isArray(value)) {
+                fail(path, 'expected an array of key-value pairs');
+            }
+            writeLength(value.length, schema.sizeLength, out, path);
+            value.forEach((entry: any, i: number) => {
+                if (!Array.isArray(entry) || entry.length !== 2) {
+                    fail(`${path}[${i}]`, 'expected a key-value pair');
+                }
+                encodeValue(schema.key, entry[0], out, `${path}[${i}].0`);
+                encodeValue(schema.value, entry[1], out, `${path}[${i}].1`);
+            });
+            break;
+        case 'Array':
+            if (!Array.isArray(value) || value.length !== schema.length) {
+                fail(path, `expected an array of ${schema.length} elements`);
+            }
+            value.forEach((item: unknown, i: number) => encodeValue(schema.item, item, out, `${path}[${i}]`));
+            break;
+        case 'Struct':
+            encodeFields(schema.fields, value, out, path);
+            break;
+        case 'Enum': {
+            if (typeof value !== 'object' || value === null || Object.keys(value).length !== 1) {
+                fail(path, 'expected an object with a single variant');
+            }
+            const name = Object.keys(value)[0];
+            const variant = schema.variants.find((v) => v.name === name);
+            if (variant === undefined) {
+                return fail(path, `unknown variant '${name}'`);
+            }
+            writeInteger(BigInt(variant.tag), schema.tagBytes, false, out, path);
+            encodeFields(variant.fields, value[name], out, `${path}.${name}`);
+            break;
+        }
+        case 'String':
+            if (typeof value !== 'string') {
+                fail(path, 'expected a string');
+            }
+            writeBytes(new TextEncoder().encode(value), schema.sizeLength, out, path);
+            break;
+        case 'ContractName':
+            if (typeof value !== 'object' || value === null || typeof value.contract !== 'string') {
+                fail(path, 'expected an object with the name of the contract');
+            }
+            writeBytes(new TextEncoder().encode(`init_${value.contract}`), schema.sizeLength, out, path);
+            break;
+        case 'ReceiveName':
+            if (
+                typeof value !== 'object' ||
+                value === null ||
+                typeof value.contract !== 'string' ||
+                typeof value.func !== 'string'
+            ) {
+                fail(path, 'expected an object with the names of the contract and the function');
+            }
+            writeBytes(new TextEncoder().encode(`${value.contract}.${value.func}`), schema.sizeLength, out, path);
+            break;
+        case 'ULeb128':
+        case 'ILeb128':
+            writeLeb128(toBigInt(value, path), schema.type === 'ILeb128', schema.maxBytes, out, path);
+            break;
+        case 'ByteList':
+            writeBytes(decodeHex(value, path), schema.sizeLength, out, path);
+            break;
+        case 'ByteArray': {
+            const bytes = decodeHex(value, path);
+            if (bytes.length !== schema.length) {
+                fail(path, `expected ${schema.length} bytes`);
+            }
+            out.push(...bytes);
+            break;
+        }
+    }
+}
+
+function writeBytes(bytes: Uint8Array, sizeLength: SizeLength, out: number[], path: string) {
+    writeLength(bytes.length, sizeLength, out, path);
+    out.push(...bytes);
+}
+
+function writeLeb128(value: bigint, signed: boolean, maxBytes: number, out: number[], path: string) {
+    if (!signed && value < 0n) {
+        fail(path, 'expected a non-negative integer');
+    }
+    const bytes: number[] = [];
+    for (;;) {
+        const byte = Number(value & 0x7fn);
+        value >>= 7n;
+        const done = signed
+            ? (value === 0n && (byte & 0x40) === 0) || (value === -1n && (byte & 0x40) !== 0)
+            : value === 0n;
+        bytes.push(done ? byte : byte | 0x80);
+        if (done) {
+            break;
+        }
+    }
+    if (bytes.length > maxBytes) {
+        fail(path, `the integer does not fit in ${maxBytes} bytes`);
+    }
+    out.push(...bytes);
+}
+
+function decodeHex(value: unknown, path: string): Uint8Array {
+    if (typeof value !== 'string' || !/^([0-9a-fA-F]{2})*$/.test(value)) {
+        return fail(path, 'expected a hex string');
+    }
+    const bytes = new Uint8Array(value.length / 2);
+    for (let i = 0; i < bytes.length; i++) {
+        bytes[i] = parseInt(value.substr(2 * i, 2), 16);
+    }
+    return bytes;
+}
+
+function encodeHex(bytes: Uint8Array): string {
+    return Array.from(bytes, (b) => b.toString(16).padStart(2, '0')).join('');
+}
+
+const DURATION_UNITS: [string, bigint][] = [
+    ['d', 86400000n],
+    ['h', 3600000n],
+    ['m', 60000n],
+    ['s', 1000n],
+    ['ms', 1n],
+];
+
+function parseDuration(value: string, path: string): bigint {
+    let millis = 0n;
+    for (const part of value.trim().split(/\s+/)) {
+        const match = /^([0-9]+)(d|h|m|s|ms)$/.exec(part);
+        if (match === null) {
+            return fail(path, `invalid duration '${value}'`);
+        }
+        const unit = DURATION_UNITS.find(([name]) => name === match[2]);
+        millis += BigInt(match[1]) * (unit as [string, bigint])[1];
+    }
+    return millis;
+}
+
+function formatDuration(millis: bigint): string {
+    const parts: string[] = [];
+    for (const [name, size] of DURATION_UNITS) {
+        if (millis >= size) {
+            parts.push(`${millis / size}${name}`);
+            millis %= size;
+        }
+    }
+    return parts.length === 0 ? '0ms' : parts.join(' ');
+}
+
+type Reader = { bytes: Uint8Array; offset: number };
+
+function readBytes(reader: Reader, length: number, path: string): Uint8Array {
+    if (reader.offset + length > reader.bytes.length) {
+        fail(path, 'unexpected end of input');
+    }
+    const bytes = reader.bytes.subarray(reader.offset, reader.offset + length);
+    reader.offset += length;
+    return bytes;
+}
+
+function readInteger(reader: Reader, bytes: number, signed: boolean, path: string): bigint {
+    const data = readBytes(reader, bytes, path);
+    let value = 0n;
+    for (let i = bytes - 1; i >= 0; i--) {
+        value = (value << 8n) | BigInt(data[i]);
+    }
+    const bits = BigInt(8 * bytes);
+    if (signed && value >= 1n << (bits - 1n)) {
+        value -= 1n << bits;
+    }
+    return value;
+}
+
+function readLength(reader: Reader, sizeLength: SizeLength, path: string): number {
+    return Number(readInteger(reader, sizeLength, false, path));
+}
+
+function decodeFields(fields: Fields, reader: Reader, path: string): unknown {
+    switch (fields.kind) {
+        case 'Named': {
+            const value: Record<string, unknown> = {};
+            for (const field of fields.fields) {
+                value[field.name] = decodeValue(field.type, reader, `${path}.${field.name}`);
+            }
+            return value;
+        }
+        case 'Unnamed':
+            return fields.fields.map((field, i) => decodeValue(field, reader, `${path}.${i}`));
+        case 'None':
+            return [];
+    }
+}
+
+function decodeString(reader: Reader, sizeLength: SizeLength, path: string): string {
+    const length = readLength(reader, sizeLength, path);
+    return new TextDecoder('utf-8', { fatal: true }).decode(readBytes(reader, length, path));
+}
+
+function decodeValue(schema: SchemaType, reader: Reader, path: string): unknown {
+    switch (schema.type) {
+        case 'Unit':
+            return [];
+        case 'Bool': {
+            const byte = readBytes(reader, 1, path)[0];
+            if (byte > 1) {
+                fail(path, `invalid boolean ${byte}`);
+            }
+            return byte === 1;
+        }
+        case 'U8':
+        case 'U16':
+        case 'U32':
+        case 'I8':
+        case 'I16':
+        case 'I32': {
+            const [bytes, signed] = INTEGERS[schema.type];
+            return Number(readInteger(reader, bytes, signed, path));
+        }
+        case 'U64':
+        case 'I64': {
+            const [bytes, signed] = INTEGERS[schema.type];
+            return readInteger(reader, bytes, signed, path);
+        }
+        case 'U128':
+        case 'I128': {
+            const [bytes, signed] = INTEGERS[schema.type];
+            return readInteger(reader, bytes, signed, path).toString();
+        }
+        case 'Amount':
+            return readInteger(reader, 8, false, path).toString();
+        case 'AccountAddress':
+            return encodeAccountAddress(readBytes(reader, 32, path));
+        case 'ContractAddress':
+            return {
+                index: readInteger(reader, 8, false, `${path}.index`),
+                subindex: readInteger(reader, 8, false, `${path}.subindex`),
+            };
+        case 'Timestamp':
+            return new Date(Number(readInteger(reader, 8, false, path))).toISOString();
+        case 'Duration':
+            return formatDuration(readInteger(reader, 8, false, path));
+        case 'Pair':
+            return [decodeValue(schema.first, reader, `${path}.0`), decodeValue(schema.second, reader, `${path}.1`)];
+        case 'List':
+        case 'Set': {
+            const length = readLength(reader, schema.sizeLength, path);
+            const items = [];
+            for (let i = 0; i < length; i++) {
+                items.push(decodeValue(schema.item, reader, `${path}[${i}]`));
+            }
+            return items;
+        }
+        case 'Map': {
+            const length = readLength(reader, schema.sizeLength, path);
+            const entries = [];
+            for (let i = 0; i < length; i++) {
+                entries.push([
+                    decodeValue(schema.key, reader, `${path}[${i}].0`),
+                    decodeValue(schema.value, reader, `${path}[${i}].1`),
+                ]);
+            }
+            return entries;
+        }
+        case 'Array': {
+            const items = [];
+            for (let i = 0; i < schema.length; i++) {
+                items.push(decodeValue(schema.item, reader, `${path}[${i}]`));
+            }
+            return items;
+        }
+        case 'Struct':
+            return decodeFields(schema.fields, reader, path);
+        case 'Enum': {
+            const tag = Number(readInteger(reader, schema.tagBytes, false, path));
+            const variant = schema.variants.find((v) => v.tag === tag);
+            if (variant === undefined) {
+                return fail(path, `unknown tag ${tag}`);
+            }
+            return { [variant.name]: decodeFields(variant.fields, reader, `${path}.${variant.name}`) };
+        }
+        case 'String':
+            return decodeString(reader, schema.sizeLength, path);
+        case 'ContractName': {
+            const name = decodeString(reader, schema.sizeLength, path);
+            if (!name.startsWith('init_')) {
+                fail(path, `invalid contract name '${name}'`);
+            }
+            return { contract: name.substring(5) };
+        }
+        case 'ReceiveName': {
+            const name = decodeString(reader, schema.sizeLength, path);
+            const dot = name.indexOf('.');
+            if (dot < 0) {
+                fail(path, `invalid receive name '${name}'`);
+            }
+            return { contract: name.substring(0, dot), func: name.substring(dot + 1) };
+        }
+        case 'ULeb128':
+        case 'ILeb128':
+            return readLeb128(reader, schema.type === 'ILeb128', schema.maxBytes, path).toString();
+        case 'ByteList': {
+            const length = readLength(reader, schema.sizeLength, path);
+            return encodeHex(readBytes(reader, length, path));
+        }
+        case 'ByteArray':
+            return encodeHex(readBytes(reader, schema.length, path));
+    }
+}
+
+function readLeb128(reader: Reader, signed: boolean, maxBytes: number, path: string): bigint {
+    let value = 0n;
+    let shift = 0n;
+    for (let i = 0; i < maxBytes; i++) {
+        const byte = readBytes(reader, 1, path)[0];
+        value |= BigInt(byte & 0x7f) << shift;
+        shift += 7n;
+        if ((byte & 0x80) === 0) {
+            if (signed && (byte & 0x40) !== 0) {
+                value -= 1n << shift;
+            }
+            return value;
+        }
+    }
+    return fail(path, `the integer does not fit in ${maxBytes} bytes`);
+}
+
+const BASE58_ALPHABET = '123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz';
+
+/** Decode an account address from its Base58Check encoding with version byte 1. */
+function decodeAccountAddress(address: string, path: string): Uint8Array {
+    let value = 0n;
+    for (const c of address) {
+        const digit = BASE58_ALPHABET.indexOf(c);
+        if (digit < 0) {
+            return fail(path, `invalid account address '${address}'`);
+        }
+        value = value * 58n + BigInt(digit);
+    }
+    const bytes = new Uint8Array(37);
+    for (let i = 36; i >= 0; i--) {
+        bytes[i] = Number(value & 0xffn);
+        value >>= 8n;
+    }
+    const checksum = sha256(sha256(bytes.subarray(0, 33))).subarray(0, 4);
+    if (value !== 0n || bytes[0] !== 1 || checksum.some((b, i) => b !== bytes[33 + i])) {
+        return fail(path, `invalid account address '${address}'`);
+    }
+    return bytes.subarray(1, 33);
+}
+
+/** Encode an account address using Base58Check with version byte 1. */
+function encodeAccountAddress(address: Uint8Array): string {
+    const payload = new Uint8Array(33);
+    payload[0] = 1;
+    payload.set(address, 1);
+    const bytes = new Uint8Array(37);
+    bytes.set(payload);
+    bytes.set(sha256(sha256(payload)).subarray(0, 4), 33);
+    let value = 0n;
+    for (const byte of bytes) {
+        value = (value << 8n) | BigInt(byte);
+    }
+    let result = '';
+    while (value > 0n) {
+        result = BASE58_ALPHABET[Number(value % 58n)] + result;
+        value /= 58n;
+    }
+    // The version byte is never zero, so there are no leading zeros to encode.
+    return result;
+}
+
+const SHA256_K = Uint32Array.from([
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5, 0xd807aa98,
+    0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174, 0xe49b69c1, 0xefbe4786,
+    0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da, 0x983e5152, 0xa831c66d, 0xb00327c8,
+    0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967, 0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13,
+    0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85, 0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819,
+    0xd6990624, 0xf40e3585, 0x106aa070, 0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a,
+    0x5b9cca4f, 0x682e6ff3, 0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7,
+    0xc67178f2,
+]);
+
+/** Compute the SHA-256 hash of the data, which is used for the checksum of account addresses. */
+export function sha256(data: Uint8Array): Uint8Array {
+    const length = Math.ceil((data.length + 9) / 64) * 64;
+    const padded = new Uint8Array(length);
+    padded.set(data);
+    padded[data.length] = 0x80;
+    new DataView(padded.buffer).setUint32(length - 4, data.length * 8);
+    const h = Uint32Array.from([
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+    ]);
+    const w = new Uint32Array(64);
+    const view = new DataView(padded.buffer);
+    const rotr = (x: number, n: number) => (x >>> n) | (x << (32 - n));
+    for (let offset = 0; offset < length; offset += 64) {
+        for (let i = 0; i < 16; i++) {
+            w[i] = view.getUint32(offset + 4 * i);
+        }
+        for (let i = 16; i < 64; i++) {
+            const s0 = rotr(w[i - 15], 7) ^ rotr(w[i - 15], 18) ^ (w[i - 15] >>> 3);
+            const s1 = rotr(w[i - 2], 17) ^ rotr(w[i - 2], 19) ^ (w[i - 2] >>> 10);
+            w[i] = w[i - 16] + s0 + w[i - 7] + s1;
+        }
+        let [a, b, c, d, e, f, g, hh] = h;
+        for (let i = 0; i < 64; i++) {
+            const s1 = rotr(e, 6) ^ rotr(e, 11) ^ rotr(e, 25);
+            const ch = (e & f) ^ (~e & g);
+            const t1 = (hh + s1 + ch + SHA256_K[i] + w[i]) >>> 0;
+            const s0 = rotr(a, 2) ^ rotr(a, 13) ^ rotr(a, 22);
+            const maj = (a & b) ^ (a & c) ^ (b & c);
+            const t2 = (s0 + maj) >>> 0;
+            hh = g;
+            g = f;
+            f = e;
+            e = (d + t1) >>> 0;
+            d = c;
+            c = b;
+            b = a;
+            a = (t1 + t2) >>> 0;
+        }
+        h[0] += a;
+        h[1] += b;
+        h[2] += c;
+        h[3] += d;
+        h[4] += e;
+        h[5] += f;
+        h[6] += g;
+        h[7] += hh;
+    }
+    const result = new Uint8Array(32);
+    const resultView = new DataView(result.buffer);
+    h.forEach((word, i) => resultView.setUint32(4 * i, word));
+    return result;
+}
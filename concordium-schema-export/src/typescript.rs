@@ -0,0 +1,280 @@
+//! Generation of a TypeScript module with type definitions, encoders and
+//! decoders for the types declared by a contract.
+use crate::{metadata_lines, named_types, upper_camel_case};
+use concordium_std::{
+    schema::{Fields, SizeLength, Type},
+    ContractSchema,
+};
+use serde_json::{json, Value};
+use std::fmt::Write;
+
+/// The runtime included in every generated module, which encodes and decodes
+/// values using a description of their schema.
+const RUNTIME: &str = include_str!("runtime.ts");
+
+/// A TypeScript module for the contract. For every type declared by the
+/// contract, named as described in [`named_types`](crate::named_types), the
+/// module exports
+/// - the type definition `X` of its JSON representation,
+/// - the constant `XSchema` describing its binary format, and
+/// - the functions `encodeX` and `decodeX`, which convert between the JSON
+///   representation and the binary format.
+///
+/// The module starts with a comment containing the metadata of the contract,
+/// and the types of the parameters of documented receive functions have their
+/// documentation comments. The module is self-contained and requires ES2020.
+pub fn contract_typescript(schema: &ContractSchema) -> String {
+    let mut out = format!(
+        "// Generated by concordium-schema-export from the schema of the contract '{}'. Do not \
+         edit.\n",
+        schema.name
+    );
+    let metadata = &schema.metadata;
+    for line in metadata.doc.lines().chain(metadata_lines(metadata).iter().map(String::as_str)) {
+        out.push_str(format!("// {}", line).trim_end());
+        out.push('\n');
+    }
+    out.push('\n');
+    out.push_str(RUNTIME);
+    let docs: Vec<(String, &str)> = schema
+        .entrypoints
+        .iter()
+        .filter(|(_, entrypoint)| !entrypoint.doc.is_empty())
+        .map(|(name, entrypoint)| {
+            (format!("{}Parameter", upper_camel_case(name)), entrypoint.doc.as_str())
+        })
+        .collect();
+    for (name, ty) in named_types(schema) {
+        if let Some((_, doc)) = docs.iter().find(|(parameter, _)| *parameter == name) {
+            out.push_str("\n/**\n");
+            for line in doc.lines() {
+                out.push_str(format!(" * {}", line).trim_end());
+                out.push('\n');
+            }
+            out.push_str(" */");
+        }
+        let descriptor =
+            serde_json::to_string_pretty(&type_descriptor(ty)).expect("Values always serialize.");
+        // Writing to a string cannot fail.
+        let _ = write!(
+            out,
+            "\nexport type {name} = {ts_type};\n\nexport const {name}Schema: SchemaType = \
+             {descriptor};\n\nexport function encode{name}(value: {name}): Uint8Array {{\n    \
+             return encode({name}Schema, value);\n}}\n\nexport function decode{name}(bytes: \
+             Uint8Array): {name} {{\n    return decode({name}Schema, bytes) as {name};\n}}\n",
+            name = name,
+            ts_type = typescript_type(ty),
+            descriptor = descriptor,
+        );
+    }
+    out
+}
+
+/// The TypeScript type of the JSON representation of values of the type.
+fn typescript_type(ty: &Type) -> String {
+    match ty {
+        Type::Unit => "[]".into(),
+        Type::Bool => "boolean".into(),
+        Type::U8 | Type::U16 | Type::U32 | Type::I8 | Type::I16 | Type::I32 => "number".into(),
+        // Not every 64-bit integer is exactly representable as a `number`.
+        Type::U64 | Type::I64 => "bigint".into(),
+        Type::U128
+        | Type::I128
+        | Type::Amount
+        | Type::AccountAddress
+        | Type::Timestamp
+        | Type::Duration
+        | Type::String(_)
+        | Type::ULeb128(_)
+        | Type::ILeb128(_)
+        | Type::ByteList(_)
+        | Type::ByteArray(_) => "string".into(),
+        Type::ContractAddress => "{ index: bigint; subindex: bigint }".into(),
+        Type::Pair(first, second) => {
+            format!("[{}, {}]", typescript_type(first), typescript_type(second))
+        }
+        Type::List(_, item) | Type::Set(_, item) | Type::Array(_, item) => {
+            format!("{}[]", parenthesize(typescript_type(item)))
+        }
+        Type::Map(_, key, value) => {
+            format!("[{}, {}][]", typescript_type(key), typescript_type(value))
+        }
+        Type::Struct(fields) => fields_typescript_type(fields),
+        Type::Enum(variants) => {
+            enum_typescript_type(variants.iter().map(|(name, fields)| (name, fields)))
+        }
+        Type::TaggedEnum(variants) => {
+            enum_typescript_type(variants.values().map(|(name, fields)| (name, fields)))
+        }
+        Type::ContractName(_) => "{ contract: string }".into(),
+        Type::ReceiveName(_) => "{ contract: string; func: string }".into(),
+    }
+}
+
+fn fields_typescript_type(fields: &Fields) -> String {
+    match fields {
+        Fields::Named(fields) => {
+            let fields: Vec<String> = fields
+                .iter()
+                .map(|(name, ty)| format!("{}: {}", property_name(name), typescript_type(ty)))
+                .collect();
+            format!("{{ {} }}", fields.join("; "))
+        }
+        Fields::Unnamed(fields) => {
+            let fields: Vec<String> = fields.iter().map(typescript_type).collect();
+            format!("[{}]", fields.join(", "))
+        }
+        Fields::None => "[]".into(),
+    }
+}
+
+fn enum_typescript_type<'a>(variants: impl Iterator<Item = (&'a String, &'a Fields)>) -> String {
+    let variants: Vec<String> = variants
+        .map(|(name, fields)| {
+            format!("{{ {}: {} }}", property_name(name), fields_typescript_type(fields))
+        })
+        .collect();
+    if variants.is_empty() {
+        "never".into()
+    } else {
+        variants.join(" | ")
+    }
+}
+
+/// Wrap union types in parentheses, so they can be used as the item type of an
+/// array.
+fn parenthesize(ty: String) -> String {
+    if ty.contains(" | ") {
+        format!("({})", ty)
+    } else {
+        ty
+    }
+}
+
+/// Quote the name of a property unless it is a valid identifier.
+fn property_name(name: &str) -> String {
+    let mut chars = name.chars();
+    let is_identifier =
+        chars.next().map_or(false, |c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if is_identifier {
+        name.into()
+    } else {
+        Value::from(name).to_string()
+    }
+}
+
+/// The description of the binary format of the type used by the runtime,
+/// which is the `SchemaType` defined in `runtime.ts`.
+fn type_descriptor(ty: &Type) -> Value {
+    let simple = |name: &str| json!({ "type": name });
+    match ty {
+        Type::Unit => simple("Unit"),
+        Type::Bool => simple("Bool"),
+        Type::U8 => simple("U8"),
+        Type::U16 => simple("U16"),
+        Type::U32 => simple("U32"),
+        Type::U64 => simple("U64"),
+        Type::U128 => simple("U128"),
+        Type::I8 => simple("I8"),
+        Type::I16 => simple("I16"),
+        Type::I32 => simple("I32"),
+        Type::I64 => simple("I64"),
+        Type::I128 => simple("I128"),
+        Type::Amount => simple("Amount"),
+        Type::AccountAddress => simple("AccountAddress"),
+        Type::ContractAddress => simple("ContractAddress"),
+        Type::Timestamp => simple("Timestamp"),
+        Type::Duration => simple("Duration"),
+        Type::Pair(first, second) => json!({
+            "type": "Pair",
+            "first": type_descriptor(first),
+            "second": type_descriptor(second),
+        }),
+        Type::List(size_length, item) => json!({
+            "type": "List",
+            "sizeLength": size_length_bytes(size_length),
+            "item": type_descriptor(item),
+        }),
+        Type::Set(size_length, item) => json!({
+            "type": "Set",
+            "sizeLength": size_length_bytes(size_length),
+            "item": type_descriptor(item),
+        }),
+        Type::Map(size_length, key, value) => json!({
+            "type": "Map",
+            "sizeLength": size_length_bytes(size_length),
+            "key": type_descriptor(key),
+            "value": type_descriptor(value),
+        }),
+        Type::Array(length, item) => json!({
+            "type": "Array",
+            "length": length,
+            "item": type_descriptor(item),
+        }),
+        Type::Struct(fields) => json!({ "type": "Struct", "fields": fields_descriptor(fields) }),
+        Type::Enum(variants) => {
+            // The tag is the index of the variant, which is a `u16` if there are more than
+            // 256 variants.
+            let tag_bytes = if variants.len() <= 256 {
+                1
+            } else {
+                2
+            };
+            let variants: Vec<Value> = variants
+                .iter()
+                .enumerate()
+                .map(|(tag, (name, fields))| variant_descriptor(tag, name, fields))
+                .collect();
+            json!({ "type": "Enum", "tagBytes": tag_bytes, "variants": variants })
+        }
+        Type::TaggedEnum(variants) => {
+            let variants: Vec<Value> = variants
+                .iter()
+                .map(|(tag, (name, fields))| variant_descriptor(usize::from(*tag), name, fields))
+                .collect();
+            json!({ "type": "Enum", "tagBytes": 1, "variants": variants })
+        }
+        Type::String(size_length) => sized("String", size_length),
+        Type::ContractName(size_length) => sized("ContractName", size_length),
+        Type::ReceiveName(size_length) => sized("ReceiveName", size_length),
+        Type::ByteList(size_length) => sized("ByteList", size_length),
+        Type::ULeb128(max_bytes) => json!({ "type": "ULeb128", "maxBytes": max_bytes }),
+        Type::ILeb128(max_bytes) => json!({ "type": "ILeb128", "maxBytes": max_bytes }),
+        Type::ByteArray(length) => json!({ "type": "ByteArray", "length": length }),
+    }
+}
+
+fn fields_descriptor(fields: &Fields) -> Value {
+    match fields {
+        Fields::Named(fields) => {
+            let fields: Vec<Value> = fields
+                .iter()
+                .map(|(name, ty)| json!({ "name": name, "type": type_descriptor(ty) }))
+                .collect();
+            json!({ "kind": "Named", "fields": fields })
+        }
+        Fields::Unnamed(fields) => {
+            let fields: Vec<Value> = fields.iter().map(type_descriptor).collect();
+            json!({ "kind": "Unnamed", "fields": fields })
+        }
+        Fields::None => json!({ "kind": "None" }),
+    }
+}
+
+fn variant_descriptor(tag: usize, name: &str, fields: &Fields) -> Value {
+    json!({ "tag": tag, "name": name, "fields": fields_descriptor(fields) })
+}
+
+fn sized(name: &str, size_length: &SizeLength) -> Value {
+    json!({ "type": name, "sizeLength": size_length_bytes(size_length) })
+}
+
+fn size_length_bytes(size_length: &SizeLength) -> u8 {
+    match size_length {
+        SizeLength::U8 => 1,
+        SizeLength::U16 => 2,
+        SizeLength::U32 => 4,
+        SizeLength::U64 => 8,
+    }
+}
@@ -0,0 +1,66 @@
+// Tests of the runtime included in the generated TypeScript modules, which are
+// run with `npx tsx --test tests/runtime.test.ts`.
+import { test } from 'node:test';
+import assert from 'node:assert/strict';
+import { decode, encode, SchemaType, sha256 } from '../src/runtime';
+
+function hex(bytes: Uint8Array): string {
+    return Buffer.from(bytes).toString('hex');
+}
+
+function roundTrip(schema: SchemaType, value: unknown, expected: string) {
+    const bytes = encode(schema, value);
+    assert.equal(hex(bytes), expected);
+    assert.deepEqual(decode(schema, bytes), value);
+}
+
+test('SHA-256 matches the test vectors', () => {
+    const vectors: [string, string][] = [
+        ['', 'e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855'],
+        ['abc', 'ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad'],
+        [
+            'abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq',
+            '248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1',
+        ],
+        // The lengths around the block size, where the padding spills into a second block.
+        ['a'.repeat(55), '9f4390f8d30c2dd92ec9f095b65e2b9ae9b0a925a5258e241c9f1e910f734318'],
+        ['a'.repeat(56), 'b35439a4ac6f0948b6d6f9e3c6af0f5f590ce20f1bde7090ef7970686ec6738a'],
+        ['a'.repeat(64), 'ffe054fe7ae0cb6dc65c3af9b61d5209f439851db43d0ba5997337df154668eb'],
+        ['a'.repeat(1000), '41edece42d63e8d9bf515a9ba6932e1c20cbc9f5a5d134645adb5db1b9737ea3'],
+    ];
+    for (const [input, digest] of vectors) {
+        assert.equal(hex(sha256(new TextEncoder().encode(input))), digest);
+    }
+});
+
+test('account addresses are encoded using Base58Check', () => {
+    const schema: SchemaType = { type: 'AccountAddress' };
+    roundTrip(
+        schema,
+        '3kBx2h5Y2veb4hZgAJWPrr8RyQESKm5TjzF3ti1QQ4VSYLwK1G',
+        '69752406cc939fc90ca6a73b57cee109963547f942006d219144924f8485fb0d',
+    );
+    roundTrip(schema, '2wkBET2rRgE8pahuaczxKbmv7ciehqsne57F9gtzf1PVdr2VP3', '00'.repeat(32));
+    roundTrip(schema, '4tVMTu4hrMTGeAQpAEzueCYqEESJQgkaH9DVJNnzK1mzCNiNFw', 'ff'.repeat(32));
+});
+
+test('account addresses with an invalid checksum are rejected', () => {
+    const schema: SchemaType = { type: 'AccountAddress' };
+    // The last character of a valid address is changed.
+    assert.throws(() => encode(schema, '3kBx2h5Y2veb4hZgAJWPrr8RyQESKm5TjzF3ti1QQ4VSYLwK1H'), /invalid account/);
+    assert.throws(() => encode(schema, '3kBx2h5Y2veb4hZgAJWPrr8RyQESKm5TjzF3ti1QQ4VSYLwK1'), /invalid account/);
+    assert.throws(() => encode(schema, '0kBx2h5Y2veb4hZgAJWPrr8RyQESKm5TjzF3ti1QQ4VSYLwK1G'), /invalid account/);
+});
+
+test('64-bit integers do not lose precision', () => {
+    roundTrip({ type: 'U64' }, 18446744073709551615n, 'ff'.repeat(8));
+    roundTrip({ type: 'I64' }, -9223372036854775808n, '00'.repeat(7) + '80');
+    roundTrip(
+        { type: 'ContractAddress' },
+        { index: 18446744073709551615n, subindex: 9007199254740993n },
+        'ff'.repeat(8) + '0100000000002000',
+    );
+    assert.equal(hex(encode({ type: 'U64' }, '18446744073709551615')), 'ff'.repeat(8));
+    assert.throws(() => encode({ type: 'U64' }, 18446744073709551615), /expected an integer/);
+    assert.throws(() => encode({ type: 'U64' }, 18446744073709551616n), /out of range/);
+});
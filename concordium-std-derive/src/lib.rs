@@ -10,11 +10,7 @@ use proc_macro2::Span;
 use quote::ToTokens;
 #[cfg(feature = "build-schema")]
 use std::collections::HashMap;
-use std::{
-    collections::{BTreeMap, BTreeSet},
-    convert::TryFrom,
-    ops::Neg,
-};
+use std::collections::{BTreeMap, BTreeSet};
 use syn::{
     parse::Parser, parse_macro_input, punctuated::*, spanned::Spanned, DataEnum, Ident, Meta, Token,
 };
@@ -67,18 +63,58 @@ struct InitAttributes {
     /// Name of the contract.
     pub(crate) contract: syn::LitStr,
     pub(crate) optional: OptionalArguments,
+    /// Metadata of the contract, which is exported alongside the schema.
+    pub(crate) metadata: MetadataAttributes,
+}
+
+/// Attributes of the initialization method that describe the contract.
+struct MetadataAttributes {
+    /// The version of the contract.
+    pub(crate) version:    Option<syn::LitStr>,
+    /// The identifiers of the standards supported by the contract.
+    pub(crate) standards:  Vec<String>,
+    /// The URL of the source repository of the contract.
+    pub(crate) repository: Option<syn::LitStr>,
+}
+
+impl MetadataAttributes {
+    /// Whether none of the attributes are set.
+    fn is_empty(&self) -> bool {
+        self.version.is_none() && self.standards.is_empty() && self.repository.is_none()
+    }
 }
 
 /// Attributes that can be attached to the receive method.
 struct ReceiveAttributes {
     /// Name of the contract the method applies to.
-    pub(crate) contract: syn::LitStr,
+    pub(crate) contract:      syn::LitStr,
     /// Name of the method.
-    pub(crate) name:     syn::LitStr,
-    pub(crate) optional: OptionalArguments,
+    pub(crate) name:          syn::LitStr,
+    /// Which type, if any, is the event type of the function.
+    /// This is used when generating schemas.
+    pub(crate) event:         Option<syn::LitStr>,
+    pub(crate) optional:      OptionalArguments,
     /// If enabled, the function has access to a mutable state, which will also
     /// be stored after the function returns.
-    pub(crate) mutable:  bool,
+    pub(crate) mutable:       bool,
+    /// Requirements on the sender that are checked before the function is
+    /// called.
+    pub(crate) access:        AccessAttributes,
+    /// Whether the method is a view, i.e., declared with `#[view]`, which only
+    /// gets read access to the host.
+    pub(crate) view:          bool,
+    /// If enabled, the method is protected by a `ReentrancyGuard`, which
+    /// rejects nested invocations of protected methods of the contract.
+    pub(crate) non_reentrant: bool,
+}
+
+/// Attributes that restrict which senders can invoke a receive method.
+struct AccessAttributes {
+    /// If set, the sender must be of this kind, i.e., either `Account` or
+    /// `Contract`.
+    pub(crate) requires_sender: Option<syn::Ident>,
+    /// If set, all policies of the sender must satisfy this predicate.
+    pub(crate) require_policy:  Option<syn::Path>,
 }
 
 #[derive(Default)]
@@ -243,6 +279,9 @@ const INIT_ATTRIBUTE_RETURN_VALUE: &str = "return_value";
 const INIT_ATTRIBUTE_ERROR: &str = "error";
 const INIT_ATTRIBUTE_EVENT: &str = "event";
 const INIT_ATTRIBUTE_CRYPTO_PRIMITIVES: &str = "crypto_primitives";
+const INIT_ATTRIBUTE_VERSION: &str = "version";
+const INIT_ATTRIBUTE_STANDARDS: &str = "standards";
+const INIT_ATTRIBUTE_REPOSITORY: &str = "repository";
 
 fn parse_init_attributes<'a, I: IntoIterator<Item = &'a Meta>>(
     attrs: I,
@@ -270,6 +309,32 @@ fn parse_init_attributes<'a, I: IntoIterator<Item = &'a Meta>>(
     let error = attributes.extract_value(INIT_ATTRIBUTE_ERROR);
     let event = attributes.extract_value(INIT_ATTRIBUTE_EVENT);
     let crypto_primitives = attributes.extract_flag(INIT_ATTRIBUTE_CRYPTO_PRIMITIVES).is_some();
+    let version = attributes.extract_value(INIT_ATTRIBUTE_VERSION);
+    if let Some(version) = &version {
+        if !is_valid_version(&version.value()) {
+            return Err(syn::Error::new(
+                version.span(),
+                "The 'version' attribute must be a semantic version, e.g., \"1.2.0\".",
+            ));
+        }
+    }
+    let standards = match attributes.extract_value(INIT_ATTRIBUTE_STANDARDS) {
+        Some(value) => {
+            let standards: Vec<String> =
+                value.value().split(',').map(|s| s.trim().to_string()).collect();
+            if standards.iter().any(|s| s.is_empty() || s.len() > 255 || !s.is_ascii()) {
+                return Err(syn::Error::new(
+                    value.span(),
+                    "The 'standards' attribute must be a comma-separated list of standard \
+                     identifiers, which are non-empty ASCII strings of at most 255 characters, \
+                     e.g., \"CIS-0, CIS-2\".",
+                ));
+            }
+            standards
+        }
+        None => Vec::new(),
+    };
+    let repository = attributes.extract_value(INIT_ATTRIBUTE_REPOSITORY);
 
     // Make sure that there are no unrecognized attributes. These would typically be
     // there due to an error. An improvement would be to find the nearest valid one
@@ -278,6 +343,11 @@ fn parse_init_attributes<'a, I: IntoIterator<Item = &'a Meta>>(
     Ok(InitAttributes {
         contract,
         event,
+        metadata: MetadataAttributes {
+            version,
+            standards,
+            repository,
+        },
         optional: OptionalArguments {
             payable,
             enable_logger,
@@ -295,6 +365,7 @@ fn parse_init_attributes<'a, I: IntoIterator<Item = &'a Meta>>(
 const RECEIVE_ATTRIBUTE_PARAMETER: &str = "parameter";
 const RECEIVE_ATTRIBUTE_RETURN_VALUE: &str = "return_value";
 const RECEIVE_ATTRIBUTE_ERROR: &str = "error";
+const RECEIVE_ATTRIBUTE_EVENT: &str = "event";
 const RECEIVE_ATTRIBUTE_CONTRACT: &str = "contract";
 const RECEIVE_ATTRIBUTE_NAME: &str = "name";
 const RECEIVE_ATTRIBUTE_FALLBACK: &str = "fallback";
@@ -303,6 +374,9 @@ const RECEIVE_ATTRIBUTE_ENABLE_LOGGER: &str = "enable_logger";
 const RECEIVE_ATTRIBUTE_LOW_LEVEL: &str = "low_level";
 const RECEIVE_ATTRIBUTE_MUTABLE: &str = "mutable";
 const RECEIVE_ATTRIBUTE_CRYPTO_PRIMITIVES: &str = "crypto_primitives";
+const RECEIVE_ATTRIBUTE_REQUIRES_SENDER: &str = "requires_sender";
+const RECEIVE_ATTRIBUTE_REQUIRE_POLICY: &str = "require_policy";
+const RECEIVE_ATTRIBUTE_NON_REENTRANT: &str = "non_reentrant";
 
 fn parse_receive_attributes<'a, I: IntoIterator<Item = &'a Meta>>(
     attrs: I,
@@ -316,11 +390,40 @@ fn parse_receive_attributes<'a, I: IntoIterator<Item = &'a Meta>>(
     let return_value: Option<syn::LitStr> =
         attributes.extract_value(RECEIVE_ATTRIBUTE_RETURN_VALUE);
     let error: Option<syn::LitStr> = attributes.extract_value(RECEIVE_ATTRIBUTE_ERROR);
+    let event: Option<syn::LitStr> = attributes.extract_value(RECEIVE_ATTRIBUTE_EVENT);
     let payable = attributes.extract_flag(RECEIVE_ATTRIBUTE_PAYABLE).is_some();
     let enable_logger = attributes.extract_flag(RECEIVE_ATTRIBUTE_ENABLE_LOGGER).is_some();
     let low_level = attributes.extract_flag(RECEIVE_ATTRIBUTE_LOW_LEVEL);
     let mutable = attributes.extract_flag(RECEIVE_ATTRIBUTE_MUTABLE);
     let crypto_primitives = attributes.extract_flag(RECEIVE_ATTRIBUTE_CRYPTO_PRIMITIVES).is_some();
+    let non_reentrant = attributes.extract_flag(RECEIVE_ATTRIBUTE_NON_REENTRANT);
+    let requires_sender = match attributes.extract_value(RECEIVE_ATTRIBUTE_REQUIRES_SENDER) {
+        Some(value) => match value.value().as_str() {
+            "account" => Some(syn::Ident::new("Account", value.span())),
+            "contract" => Some(syn::Ident::new("Contract", value.span())),
+            _ => {
+                return Err(syn::Error::new(
+                    value.span(),
+                    "The value of 'requires_sender' must be either \"account\" or \"contract\".",
+                ))
+            }
+        },
+        None => None,
+    };
+    let require_policy = match attributes.extract_value(RECEIVE_ATTRIBUTE_REQUIRE_POLICY) {
+        Some(value) => Some(value.parse::<syn::Path>().map_err(|_| {
+            syn::Error::new(
+                value.span(),
+                "The value of 'require_policy' must be a path to a function, e.g., \
+                 \"my_predicate\".",
+            )
+        })?),
+        None => None,
+    };
+    let access = AccessAttributes {
+        requires_sender,
+        require_policy,
+    };
 
     if let (Some(mutable), Some(low_level)) = (&mutable, &low_level) {
         let mut error = syn::Error::new(
@@ -336,6 +439,16 @@ fn parse_receive_attributes<'a, I: IntoIterator<Item = &'a Meta>>(
         return Err(error);
     }
 
+    if let Some(non_reentrant) = &non_reentrant {
+        if mutable.is_none() && low_level.is_none() {
+            return Err(syn::Error::new(
+                non_reentrant.span(),
+                "The attribute 'non_reentrant' requires the attribute 'mutable', since the \
+                 reentrancy guard is recorded in the state of the contract.",
+            ));
+        }
+    }
+
     if let (Some((name, _)), Some(fallback)) = (&name, &fallback) {
         let mut error = syn::Error::new(
             name.span(),
@@ -357,6 +470,7 @@ fn parse_receive_attributes<'a, I: IntoIterator<Item = &'a Meta>>(
         (Some(contract), Some((_, name))) => Ok(ReceiveAttributes {
             contract,
             name,
+            event,
             optional: OptionalArguments {
                 payable,
                 enable_logger,
@@ -369,12 +483,16 @@ fn parse_receive_attributes<'a, I: IntoIterator<Item = &'a Meta>>(
             mutable: mutable.is_some(), /* This is also optional, but does not belong in
                                          * OptionalArguments, as
                                          * it doesn't apply to init methods. */
+            access,
+            view: false,
+            non_reentrant: non_reentrant.is_some(),
         }),
         (Some(contract), None) => {
             if let Some(ident) = fallback {
                 Ok(ReceiveAttributes {
                     contract,
                     name: syn::LitStr::new("", ident.span()),
+                    event,
                     optional: OptionalArguments {
                         payable,
                         enable_logger,
@@ -388,6 +506,9 @@ fn parse_receive_attributes<'a, I: IntoIterator<Item = &'a Meta>>(
                                                  * belong in
                                                  * OptionalArguments, as
                                                  * it doesn't apply to init methods. */
+                    access,
+                    view: false,
+                    non_reentrant: non_reentrant.is_some(),
                 })
             } else {
                 Err(syn::Error::new(
@@ -417,6 +538,181 @@ fn contains_attribute<'a, I: IntoIterator<Item = &'a Meta>>(iter: I, name: &str)
     iter.into_iter().any(|attr| attr.path().is_ident(name))
 }
 
+/// Check that a version is a semantic version, i.e., of the form
+/// `major.minor.patch` optionally followed by a pre-release part starting with
+/// `-` and build metadata starting with `+`.
+fn is_valid_version(version: &str) -> bool {
+    let core_end = version.find(&['-', '+'][..]).unwrap_or(version.len());
+    let (core, suffix) = version.split_at(core_end);
+    let parts: Vec<&str> = core.split('.').collect();
+    let is_number = |part: &&str| {
+        !part.is_empty()
+            && part.bytes().all(|b| b.is_ascii_digit())
+            && (part.len() == 1 || !part.starts_with('0'))
+    };
+    let is_identifiers = |s: &str| {
+        s.split('.')
+            .all(|id| !id.is_empty() && id.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-'))
+    };
+    let (pre_release, build) = match suffix.find('+') {
+        Some(i) => (&suffix[..i], Some(&suffix[i + 1..])),
+        None => (suffix, None),
+    };
+    parts.len() == 3
+        && parts.iter().all(is_number)
+        && (pre_release.is_empty() || is_identifiers(&pre_release[1..]))
+        && build.map_or(true, is_identifiers)
+}
+
+/// The documentation comment of an item, which is given by its `doc`
+/// attributes. The leading space that `///` comments have on every line is
+/// removed.
+fn doc_comment(attributes: &[syn::Attribute]) -> String {
+    let lines: Vec<String> = attributes
+        .iter()
+        .filter(|attr| attr.path.is_ident("doc"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(Meta::NameValue(syn::MetaNameValue {
+                lit: syn::Lit::Str(line),
+                ..
+            })) => {
+                let line = line.value();
+                Some(line.strip_prefix(' ').unwrap_or(&line).trim_end().to_string())
+            }
+            _ => None,
+        })
+        .collect();
+    lines.join("\n").trim().to_string()
+}
+
+/// Determine whether a receive function takes its parameter as an argument,
+/// which is the case if the function has one more argument than required by
+/// its attributes, and return the type of the parameter if so. Arguments that
+/// require an attribute which is not set, such as an `Amount` without
+/// `payable`, are reported instead of being taken for the parameter.
+///
+/// The schema of the parameter is then given by the type of the argument, and
+/// unless the `return_value` attribute is set, the schema of the return value
+/// is given by the return type `Result<R, E>` of the function, which are both
+/// set in the attributes.
+fn receive_typed_parameter(
+    sig: &syn::Signature,
+    attributes: &mut ReceiveAttributes,
+) -> syn::Result<Option<syn::Type>> {
+    let optional = &mut attributes.optional;
+    check_optional_argument_attributes(sig, optional)?;
+    let required_arg_count =
+        2 + [optional.payable, optional.enable_logger, optional.crypto_primitives]
+            .iter()
+            .filter(|enabled| **enabled)
+            .count();
+    if sig.inputs.len() != required_arg_count + 1 {
+        return Ok(None);
+    }
+    // Other arguments are reported as an incorrect number of arguments.
+    let parameter_type = match sig.inputs.last() {
+        Some(syn::FnArg::Typed(arg)) => (*arg.ty).clone(),
+        _ => return Ok(None),
+    };
+    if let syn::Type::Reference(_) | syn::Type::ImplTrait(_) = parameter_type {
+        return Err(syn::Error::new_spanned(
+            &parameter_type,
+            "The parameter of the function must be an owned type implementing `Deserial`, e.g., \
+             `params: MyParameter`. If this argument is not the parameter, an attribute such as \
+             'payable', 'enable_logger' or 'crypto_primitives' is missing.",
+        ));
+    }
+    if let Some(parameter) = &optional.parameter {
+        return Err(syn::Error::new(
+            parameter.span(),
+            "The 'parameter' attribute cannot be used when the parameter is an argument of the \
+             function, since the schema is given by the type of the argument.",
+        ));
+    }
+    optional.parameter = Some(type_literal(&parameter_type));
+    if optional.return_value.is_none() {
+        optional.return_value = result_ok_type(&sig.output).map(|ty| type_literal(&ty));
+    }
+    Ok(Some(parameter_type))
+}
+
+/// Report the arguments of a receive function that are only supported with an
+/// attribute that is not set, i.e., the logger without `enable_logger`, the
+/// crypto primitives without `crypto_primitives`, and the amount without
+/// `payable`. An `Amount` as the last argument is taken to be the parameter.
+fn check_optional_argument_attributes(
+    sig: &syn::Signature,
+    optional: &OptionalArguments,
+) -> syn::Result<()> {
+    let arg_count = sig.inputs.len();
+    for (i, arg) in sig.inputs.iter().enumerate().skip(2) {
+        let ty = match arg {
+            syn::FnArg::Typed(arg) => &arg.ty,
+            syn::FnArg::Receiver(_) => continue,
+        };
+        let missing_attribute = if !optional.enable_logger && type_mentions(ty, "HasLogger") {
+            RECEIVE_ATTRIBUTE_ENABLE_LOGGER
+        } else if !optional.crypto_primitives && type_mentions(ty, "HasCryptoPrimitives") {
+            RECEIVE_ATTRIBUTE_CRYPTO_PRIMITIVES
+        } else if !optional.payable && i + 1 < arg_count && type_mentions(ty, "Amount") {
+            RECEIVE_ATTRIBUTE_PAYABLE
+        } else {
+            continue;
+        };
+        return Err(syn::Error::new_spanned(
+            ty,
+            format!(
+                "The function takes this argument only if the attribute '{}' is set. Add the \
+                 attribute, or remove the argument.",
+                missing_attribute
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Whether the type mentions the identifier, e.g., `HasLogger` in
+/// `&mut impl HasLogger`.
+fn type_mentions(ty: &syn::Type, ident: &str) -> bool {
+    fn tokens_mention(tokens: proc_macro2::TokenStream, ident: &str) -> bool {
+        tokens.into_iter().any(|token| match token {
+            proc_macro2::TokenTree::Ident(i) => i == ident,
+            proc_macro2::TokenTree::Group(group) => tokens_mention(group.stream(), ident),
+            _ => false,
+        })
+    }
+    tokens_mention(ty.to_token_stream(), ident)
+}
+
+/// The type `R` of a return type of the form `Result<R, E>`, where the name of
+/// the result type can be an alias such as `ReceiveResult<R>`, or `None` if the
+/// return type does not have this form or `R` is `()`.
+fn result_ok_type(output: &syn::ReturnType) -> Option<syn::Type> {
+    let ty = match output {
+        syn::ReturnType::Type(_, ty) => ty,
+        syn::ReturnType::Default => return None,
+    };
+    let segment = match &**ty {
+        syn::Type::Path(path) => path.path.segments.last()?,
+        _ => return None,
+    };
+    let arguments = match &segment.arguments {
+        syn::PathArguments::AngleBracketed(arguments) => arguments,
+        _ => return None,
+    };
+    match arguments.args.first()? {
+        syn::GenericArgument::Type(syn::Type::Tuple(tuple)) if tuple.elems.is_empty() => None,
+        syn::GenericArgument::Type(ty) => Some(ty.clone()),
+        _ => None,
+    }
+}
+
+/// A string literal containing a type, in the form used by the attributes that
+/// declare the types of the schema.
+fn type_literal(ty: &syn::Type) -> syn::LitStr {
+    syn::LitStr::new(&ty.to_token_stream().to_string(), ty.span())
+}
+
 /// Derive the appropriate export for an annotated init function.
 ///
 /// This macro requires the following items to be present
@@ -528,6 +824,32 @@ fn contains_attribute<'a, I: IntoIterator<Item = &'a Meta>>(iter: I, name: &str)
 ///     crypto_primitives: &impl HasCryptoPrimitives,
 /// ) -> InitResult<MyState> {...}
 /// ```
+///
+/// ## `version`, `standards` and `repository`: Describe the contract
+/// The attributes `version`, `standards` and `repository` declare metadata of
+/// the contract, which is the semantic version of the contract, a
+/// comma-separated list of the identifiers of the standards it supports, and
+/// the URL of its source repository. Together with the documentation comment
+/// of the init function, which should describe the contract, the metadata is
+/// exported as `ContractMetadata` alongside the schema of the init function
+/// when building with the `build-schema` feature, so that tools such as
+/// blockchain explorers can display it. The documentation comments of receive
+/// functions are exported in the same way.
+///
+/// ### Example
+/// ```ignore
+/// /// A token contract following the CIS-2 standard.
+/// #[init(
+///     contract = "my_contract",
+///     version = "1.2.0",
+///     standards = "CIS-0, CIS-2",
+///     repository = "https://github.com/me/my_contract"
+/// )]
+/// fn some_init<S: HasStateApi>(
+///     ctx: &impl HasInitContext,
+///     state_builder: &mut StateBuilder<S>,
+/// ) -> InitResult<MyState> {...}
+/// ```
 #[proc_macro_attribute]
 pub fn init(attr: TokenStream, item: TokenStream) -> TokenStream {
     unwrap_or_report(init_worker(attr, item))
@@ -645,6 +967,17 @@ fn init_worker(attr: TokenStream, item: TokenStream) -> syn::Result<TokenStream>
         wasm_export_fn_name,
     )?);
 
+    // Adding the contract metadata, if there is any.
+    let doc = doc_comment(&ast.attrs);
+    if !init_attributes.metadata.is_empty() || !doc.is_empty() {
+        out.extend(contract_metadata_schema(
+            &init_attributes.metadata,
+            &doc,
+            rust_name_cloned.clone(),
+            wasm_name_cloned.clone(),
+        ));
+    }
+
     // Adding the event schema
     out.extend(contract_function_event_schema(event_option, rust_name_cloned, wasm_name_cloned)?);
 
@@ -681,6 +1014,33 @@ fn init_worker(attr: TokenStream, item: TokenStream) -> syn::Result<TokenStream>
 /// are from `concordium-std` and `MyState` and `MyReturnValue` are user-defined
 /// types.
 ///
+/// # Typed parameter
+/// The function can take the parameter as its last argument, after the
+/// arguments required by the attributes. The generated function then parses
+/// the parameter and rejects with a `ParseError` if it fails. The type of the
+/// parameter must implement `Deserial`.
+///
+/// When building with the `build-schema` feature, the schema of the parameter
+/// is the schema of the type of the argument, so the `parameter` attribute
+/// cannot be used together with a typed parameter. Unless the `return_value`
+/// attribute is set, the schema of the return value is the schema of `R` if
+/// the return type of the function is `Result<R, E>` or an alias such as
+/// `ReceiveResult<R>`, and `R` is not `()`. The types must then implement
+/// `SchemaType`.
+///
+/// ### Example
+/// ```ignore
+/// #[derive(Serialize, SchemaType)]
+/// struct TransferParameter { ... }
+///
+/// #[receive(contract = "my_contract", name = "transfer", mutable)]
+/// fn transfer<S: HasStateApi>(
+///     ctx: &impl HasReceiveContext,
+///     host: &mut impl HasHost<MyState, StateApiType = S>,
+///     params: TransferParameter,
+/// ) -> ReceiveResult<MyReturnValue> {...}
+/// ```
+///
 /// # Optional attributes
 ///
 /// ## `payable`: Make function accept an amount of CCD
@@ -812,6 +1172,37 @@ fn init_worker(attr: TokenStream, item: TokenStream) -> syn::Result<TokenStream>
 /// ) -> Result<A, MyError> {...}
 /// ```
 ///
+/// ## `event="<Event>"`: Generate schema for the events
+/// To make schema generation include the type of the events logged by this
+/// function, add the attribute `event` and set it equal to a string literal
+/// containing the type used for the events. The event type must implement the
+/// SchemaType trait, which for most cases can be derived automatically.
+///
+/// This attribute is only supported within a [`#[contract]`](macro@contract)
+/// module, where the event types of all the entrypoints, including the init
+/// function, are merged into the event type of the contract. This requires that
+/// the event types are the same, or that they are enums whose tags do not
+/// conflict. Note that this is checked when building the schema, which panics
+/// if they conflict, and not when compiling the contract. Outside of a
+/// `#[contract]` module, the event type of the contract is given by the `event`
+/// attribute of the init function.
+///
+/// ### Example
+/// ```ignore
+/// #[derive(Serialize, SchemaType)]
+/// enum MyEvent { ... }
+///
+/// #[contract(name = "my_contract")]
+/// mod my_contract {
+///     #[receive(name = "some_receive", event = "MyEvent", enable_logger)]
+///     fn contract_receive<S: HasStateApi>(
+///         ctx: &impl HasReceiveContext,
+///         host: &HasHost<MyState, StateApiType = S>,
+///         logger: &mut impl HasLogger,
+///     ) -> ReceiveResult<()> {...}
+/// }
+/// ```
+///
 /// ## `fallback`: Create a fallback entrypoint.
 /// A contract can have a *single* fallback entrypoint defined.
 /// If defined, invocations on missing entrypoint will be redirected to the
@@ -848,18 +1239,214 @@ fn init_worker(attr: TokenStream, item: TokenStream) -> syn::Result<TokenStream>
 ///     crypto_primitives: &impl HasCryptoPrimitives,
 /// ) -> ReceiveResult<MyReturnValue> {...}
 /// ```
+///
+/// ## `requires_sender = "account"`: Restrict the kind of sender
+/// Setting the `requires_sender` attribute to either `"account"` or
+/// `"contract"` makes the function reject with
+/// `AccessDeniedError::WrongSenderKind` if the sender is of the other kind.
+/// The check is inserted at the start of the body of the function, so it also
+/// happens when the function is called in tests, and the signature of the
+/// function is unchanged. The error type of the function must implement
+/// `From<AccessDeniedError>`, which [`Reject`] does.
+///
+/// ### Example
+/// ```ignore
+/// #[receive(contract = "my_contract", name = "some_receive", requires_sender = "account")]
+/// fn some_receive<S: HasStateApi>(
+///     ctx: &impl HasReceiveContext,
+///     host: &impl HasHost<MyState, StateApiType = S>,
+/// ) -> ReceiveResult<MyReturnValue> {...}
+/// ```
+///
+/// ## `require_policy = "<predicate>"`: Restrict the identity of the sender
+/// Setting the `require_policy` attribute to the path of a predicate makes the
+/// function reject with `AccessDeniedError::PolicyNotSatisfied` unless all
+/// policies of the sender satisfy the predicate. The predicate is called with
+/// each policy and the slot time of the block, and must have the signature
+/// `fn(&impl HasPolicy, Timestamp) -> bool`. As for `requires_sender`, the
+/// check is inserted at the start of the body of the function, so in tests the
+/// slot time and the policies of the `TestReceiveContext` must be set.
+///
+/// Note that if the sender is a contract, its policies are those of the
+/// account that created it. Combine with `requires_sender = "account"` to only
+/// allow accounts.
+///
+/// ### Example
+/// ```ignore
+/// fn adult_in_denmark(policy: &impl HasPolicy, now: Timestamp) -> bool {
+///     policy.country_of_residence() == Some(*b"DK")
+///         && policy.age_at(now).map_or(false, |age| age >= 18)
+/// }
+///
+/// #[receive(
+///     contract = "my_contract",
+///     name = "some_receive",
+///     requires_sender = "account",
+///     require_policy = "adult_in_denmark"
+/// )]
+/// fn some_receive<S: HasStateApi>(
+///     ctx: &impl HasReceiveContext,
+///     host: &impl HasHost<MyState, StateApiType = S>,
+/// ) -> ReceiveResult<MyReturnValue> {...}
+/// ```
+///
+/// When building with the `build-schema` feature, the requirements are
+/// exported as `EntrypointAccess` alongside the schema of the function.
+///
+/// ## `non_reentrant`: Reject nested invocations
+/// Setting the `non_reentrant` attribute protects the function by a
+/// `ReentrancyGuard`. The body of the function is wrapped such that it records
+/// that an invocation is in progress before the body is evaluated, and rejects
+/// with `ReentrancyError` if another function of the contract with the
+/// attribute is already in progress, e.g., if a contract invoked by the
+/// function calls back into the contract. The guard is exited again when the
+/// body returns, whether it succeeds or rejects.
+///
+/// The attribute requires `mutable`, or `low_level`, since the guard is
+/// recorded in the state of the contract, and the error type of the function
+/// must implement `From<ReentrancyError>`, which [`Reject`] does. Since the
+/// guard is part of the function, it is also used when calling the function
+/// with a `TestHost` in tests, where reentrancy can be tested by using a
+/// `ReentrancyGuard` in the mocked entrypoints.
+///
+/// ### Example
+/// ```ignore
+/// #[receive(contract = "my_contract", name = "withdraw", mutable, non_reentrant)]
+/// fn withdraw<S: HasStateApi>(
+///     ctx: &impl HasReceiveContext,
+///     host: &mut impl HasHost<MyState, StateApiType = S>,
+/// ) -> ReceiveResult<()> {...}
+/// ```
+///
+/// # Documentation
+/// When building with the `build-schema` feature, the documentation comment of
+/// the function is exported as `EntrypointMetadata` alongside the schema of
+/// the function, so that tools such as blockchain explorers can display it.
 #[proc_macro_attribute]
 pub fn receive(attr: TokenStream, item: TokenStream) -> TokenStream {
-    unwrap_or_report(receive_worker(attr, item))
+    unwrap_or_report(receive_worker(attr, item, false))
 }
 
-fn receive_worker(attr: TokenStream, item: TokenStream) -> syn::Result<TokenStream> {
-    let ast: syn::ItemFn =
-        attach_error(syn::parse(item), "#[receive] can only be applied to functions.")?;
+/// Attributes of `#[receive]` that are not supported by `#[view]`, since views
+/// can neither modify the state, receive CCD nor log events.
+const VIEW_UNSUPPORTED_ATTRIBUTES: [&str; 7] = [
+    RECEIVE_ATTRIBUTE_MUTABLE,
+    RECEIVE_ATTRIBUTE_PAYABLE,
+    RECEIVE_ATTRIBUTE_LOW_LEVEL,
+    RECEIVE_ATTRIBUTE_FALLBACK,
+    RECEIVE_ATTRIBUTE_ENABLE_LOGGER,
+    RECEIVE_ATTRIBUTE_EVENT,
+    RECEIVE_ATTRIBUTE_NON_REENTRANT,
+];
+
+/// Derive the appropriate export for an annotated read-only receive function.
+///
+/// The macro takes the same attributes as [`#[receive]`](macro@receive),
+/// except `mutable`, `payable`, `low_level`, `fallback`, `enable_logger`,
+/// `event` and `non_reentrant`, which are rejected. The generated entrypoint
+/// never writes the state back, and the function is given a host that only
+/// implements `HasViewHost`, i.e., it can read the state and the balance of the
+/// contract, but it has no methods for modifying the state, transferring CCD or
+/// invoking other contracts. Using any of these is a compile error.
+///
+/// The required signature is therefore
+///
+/// ```ignore
+/// #[view(contract = "my_contract", name = "some_view")]
+/// fn some_view<S: HasStateApi>(
+///     ctx: &impl HasReceiveContext,
+///     host: &impl HasViewHost<MyState<S>>,
+/// ) -> ReceiveResult<MyReturnValue> {...}
+/// ```
+///
+/// optionally followed by `crypto_primitives: &impl HasCryptoPrimitives` if
+/// the `crypto_primitives` attribute is set, and a typed parameter, as for
+/// `#[receive]`.
+///
+/// Since every `HasHost` is also a `HasViewHost`, the function can be tested
+/// with a `TestHost` as usual.
+///
+/// # Schema
+/// When building with the `build-schema` feature, the entrypoint is marked as a
+/// view in its `EntrypointMetadata`, so that tools such as wallets know that
+/// it can be queried without sending a transaction.
+///
+/// Within a [`#[contract]`](macro@contract) module, the generated client
+/// invokes views using `ContractInterface::invoke_read_only`.
+///
+/// ### Example
+/// ```ignore
+/// #[view(contract = "my_contract", name = "balance", return_value = "Amount")]
+/// fn balance<S: HasStateApi>(
+///     _ctx: &impl HasReceiveContext,
+///     host: &impl HasViewHost<MyState<S>>,
+/// ) -> ReceiveResult<Amount> {
+///     Ok(host.self_balance())
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn view(attr: TokenStream, item: TokenStream) -> TokenStream {
+    unwrap_or_report(receive_worker(attr, item, true))
+}
+
+/// Report the attributes of a `#[view]` that are only supported by
+/// `#[receive]`.
+fn check_view_attributes<'a, I: IntoIterator<Item = &'a Meta>>(attrs: I) -> syn::Result<()> {
+    let mut errors: Option<syn::Error> = None;
+    for attr in attrs {
+        if let Some(name) =
+            VIEW_UNSUPPORTED_ATTRIBUTES.iter().find(|name| attr.path().is_ident(name))
+        {
+            let error = syn::Error::new(
+                attr.span(),
+                format!(
+                    "The attribute '{}' is not supported by #[view], since views cannot modify \
+                     the state, receive CCD or log events. Use #[receive] instead.",
+                    name
+                ),
+            );
+            match errors.as_mut() {
+                Some(errors) => errors.combine(error),
+                None => errors = Some(error),
+            }
+        }
+    }
+    match errors {
+        Some(errors) => Err(errors),
+        None => Ok(()),
+    }
+}
+
+fn receive_worker(attr: TokenStream, item: TokenStream, view: bool) -> syn::Result<TokenStream> {
+    let macro_name = if view {
+        "view"
+    } else {
+        "receive"
+    };
+    let mut ast: syn::ItemFn = attach_error(
+        syn::parse(item),
+        &format!("#[{}] can only be applied to functions.", macro_name),
+    )?;
 
     let attrs = Punctuated::<Meta, Token![,]>::parse_terminated.parse(attr)?;
 
-    let receive_attributes = parse_receive_attributes(&attrs)?;
+    if view {
+        check_view_attributes(&attrs)?;
+    }
+    let mut receive_attributes = parse_receive_attributes(&attrs)?;
+    receive_attributes.view = view;
+    // A `#[contract]` module removes the event types of its entrypoints, since it
+    // merges them into the event type of the contract.
+    if let Some(event) = receive_attributes.event.as_ref() {
+        return Err(syn::Error::new(
+            event.span(),
+            "The attribute 'event' of a receive function is only supported in a #[contract] \
+             module, which merges the event types of the entrypoints into the event type of the \
+             contract. Otherwise, use the attribute 'event' of the init function.",
+        ));
+    }
+
+    let typed_parameter = receive_typed_parameter(&ast.sig, &mut receive_attributes)?;
 
     let contract_name = receive_attributes.contract;
 
@@ -894,18 +1481,39 @@ fn receive_worker(attr: TokenStream, item: TokenStream) -> syn::Result<TokenStre
     // different number of arguments, than elements in this vector, then the
     // strings are displayed as the expected arguments.
     let mut required_args = vec!["ctx: &impl HasReceiveContext"];
-    if receive_attributes.mutable {
+    if receive_attributes.view {
+        required_args.push("host: &impl HasViewHost");
+    } else if receive_attributes.mutable {
         required_args.push("host: &mut impl HasHost");
     } else {
         required_args.push("host: &impl HasHost");
     }
 
-    let (setup_fn_optional_args, fn_optional_args) = contract_function_optional_args_tokens(
+    let (setup_fn_optional_args, mut fn_optional_args) = contract_function_optional_args_tokens(
         &receive_attributes.optional,
         &amount_ident,
         &mut required_args,
     );
 
+    // Parse the parameter if it is an argument of the function.
+    let parse_parameter = match &typed_parameter {
+        Some(parameter_type) => {
+            required_args.push("parameter: impl Deserial");
+            fn_optional_args.push(quote!(parameter));
+            quote! {
+                let parameter = match <#parameter_type as concordium_std::Deserial>::deserial(
+                    &mut concordium_std::HasCommonData::parameter_cursor(&ctx),
+                ) {
+                    Ok(parameter) => parameter,
+                    Err(_) => {
+                        return concordium_std::Reject::from(concordium_std::ParseError::default()).error_code.get();
+                    }
+                };
+            }
+        }
+        None => proc_macro2::TokenStream::new(),
+    };
+
     let mut out = if receive_attributes.optional.low_level {
         quote! {
             #[export_name = #wasm_export_fn_name]
@@ -913,6 +1521,7 @@ fn receive_worker(attr: TokenStream, item: TokenStream) -> syn::Result<TokenStre
                 use concordium_std::{SeekFrom, Logger, ExternReceiveContext, ExternContext, ExternLowLevelHost};
                 #setup_fn_optional_args
                 let ctx = ExternContext::<ExternReceiveContext>::open(());
+                #parse_parameter
                 let mut host = ExternLowLevelHost::default();
                 match #fn_name(&ctx, &mut host, #(#fn_optional_args, )*) {
                     Ok(rv) => {
@@ -947,6 +1556,8 @@ fn receive_worker(attr: TokenStream, item: TokenStream) -> syn::Result<TokenStre
                 let new_state_size = root_entry_end.size().unwrap_abort();
                 root_entry_end.truncate(new_state_size).unwrap_abort();
             })
+        } else if receive_attributes.view {
+            (quote!(&concordium_std::ViewHost::new(&host)), quote!())
         } else {
             (quote!(&host), quote!())
         };
@@ -957,6 +1568,7 @@ fn receive_worker(attr: TokenStream, item: TokenStream) -> syn::Result<TokenStre
                 use concordium_std::{SeekFrom, StateBuilder, Logger, ExternHost, trap};
                 #setup_fn_optional_args
                 let ctx = ExternContext::<ExternReceiveContext>::open(());
+                #parse_parameter
                 let state_api = ExternStateApi::open();
                 if let Ok(state) = DeserialWithState::deserial_with_state(&state_api, &mut state_api.lookup_entry(&[]).unwrap_abort()) {
                     let mut state_builder = StateBuilder::open(state_api);
@@ -996,7 +1608,8 @@ fn receive_worker(attr: TokenStream, item: TokenStream) -> syn::Result<TokenStre
         return Err(syn::Error::new(
             ast.sig.inputs.span(),
             format!(
-                "Incorrect number of function arguments, the expected arguments are ({}) ",
+                "Incorrect number of function arguments, the expected arguments are ({}), \
+                 optionally followed by the parameter, e.g., `parameter: MyParameter` ",
                 required_args.join(", ")
             ),
         ));
@@ -1007,6 +1620,8 @@ fn receive_worker(attr: TokenStream, item: TokenStream) -> syn::Result<TokenStre
     let parameter_option = receive_attributes.optional.parameter;
     let return_value_option = receive_attributes.optional.return_value;
     let error_option = receive_attributes.optional.error;
+    let wasm_name_cloned = wasm_export_fn_name.clone();
+    let rust_name_cloned = rust_export_fn_name.clone();
     out.extend(contract_function_schema_tokens(
         parameter_option,
         return_value_option,
@@ -1014,64 +1629,846 @@ fn receive_worker(attr: TokenStream, item: TokenStream) -> syn::Result<TokenStre
         rust_export_fn_name,
         wasm_export_fn_name,
     )?);
-    // add the original function to the output as well.
+    // Adding the access requirements.
+    out.extend(contract_function_access_schema(
+        &receive_attributes.access,
+        rust_name_cloned.clone(),
+        wasm_name_cloned.clone(),
+    ));
+    // Adding the documentation comment.
+    out.extend(entrypoint_metadata_schema(
+        &doc_comment(&ast.attrs),
+        receive_attributes.view,
+        rust_name_cloned,
+        wasm_name_cloned,
+    ));
+    // add the original function to the output as well, protected by the
+    // reentrancy guard if requested and preceded by the access checks, which
+    // thus happen before the guard is entered.
+    if receive_attributes.non_reentrant {
+        with_reentrancy_guard(&mut ast)?;
+    }
+    with_access_check(&mut ast, &receive_attributes.access)?;
     ast.to_tokens(&mut out);
     Ok(out.into())
 }
 
-/// Generate tokens for some of the optional arguments, based on the attributes.
-/// Returns a pair, where the first entry is tokens for setting up the arguments
-/// and the second entry is a Vec of the argument names as tokens.
-///
-/// It also mutates a vector of required arguments with the expected type
-/// signature of each.
-fn contract_function_optional_args_tokens(
-    optional: &OptionalArguments,
-    amount_ident: &syn::Ident,
-    required_args: &mut Vec<&str>,
-) -> (proc_macro2::TokenStream, Vec<proc_macro2::TokenStream>) {
-    let mut setup_fn_args = proc_macro2::TokenStream::new();
-    let mut fn_args = vec![];
-    if optional.payable {
-        required_args.push("amount: Amount");
-        fn_args.push(quote!(#amount_ident));
-    } else {
-        setup_fn_args.extend(quote! {
-            if #amount_ident.micro_ccd != 0 {
-                return concordium_std::Reject::from(concordium_std::NotPayableError).error_code.get();
+/// Wrap the body of a receive function in a `ReentrancyGuard`, which is entered
+/// before the body is evaluated and exited afterwards, whether the function
+/// succeeds or rejects. The guard is thus also used when the function is called
+/// with a `TestHost`, where rejecting does not roll back the state.
+fn with_reentrancy_guard(ast: &mut syn::ItemFn) -> syn::Result<()> {
+    let host = match ast.sig.inputs.iter().nth(1) {
+        Some(syn::FnArg::Typed(syn::PatType {
+            pat,
+            ..
+        })) => match &**pat {
+            syn::Pat::Ident(pat) => pat.ident.clone(),
+            pat => {
+                return Err(syn::Error::new(
+                    pat.span(),
+                    "The host of a function with the attribute 'non_reentrant' must be bound to a \
+                     name, e.g., `host`.",
+                ))
             }
-        });
+        },
+        // Missing arguments are reported as an incorrect number of arguments.
+        _ => return Ok(()),
     };
+    let output = &ast.sig.output;
+    let block = &ast.block;
+    *ast.block = syn::parse_quote! {{
+        let mut reentrancy_guard = concordium_std::ReentrancyGuard::new(
+            concordium_std::HasHost::state_builder(&mut *#host),
+        );
+        reentrancy_guard.enter()?;
+        #[allow(clippy::redundant_closure_call)]
+        let result = (move || #output #block)();
+        reentrancy_guard.exit();
+        result
+    }};
+    Ok(())
+}
 
-    if optional.enable_logger {
-        required_args.push("logger: &mut impl HasLogger");
-        let logger_ident = format_ident!("logger");
-        setup_fn_args.extend(quote!(let mut #logger_ident = concordium_std::Logger::init();));
-        fn_args.push(quote!(&mut #logger_ident));
-    }
+// Supported attributes for the contract macro.
 
-    if optional.crypto_primitives {
-        required_args.push("crypto_primitives: &impl HasCryptoPrimitives");
-        let crypto_primitives_ident = format_ident!("crypto_primitives");
-        setup_fn_args
-            .extend(quote!(let #crypto_primitives_ident = concordium_std::ExternCryptoPrimitives;));
-        fn_args.push(quote!(&#crypto_primitives_ident));
-    }
+const CONTRACT_ATTRIBUTE_NAME: &str = "name";
 
-    (setup_fn_args, fn_args)
+/// Group the init and receive functions of a smart contract in a module, and
+/// generate a typed client for invoking the contract from other contracts.
+///
+/// This macro requires the following items to be present
+/// - `name="<name>"` where *\<name\>* is the name of the smart contract.
+///
+/// The macro must be applied to a module with a body. All functions in the
+/// module that are annotated with `#[init]`, `#[receive]` or `#[view]` are
+/// considered part of the contract, and their `contract` attribute can be
+/// omitted. If it is given, it must match the name of the contract. The macro
+/// checks that the contract has at most one init function and that the names of
+/// the receive functions are unique.
+///
+/// Note that the attributes must be written as `#[init(..)]`, `#[receive(..)]`
+/// and `#[view(..)]` for the macro to recognize them, i.e., they must not be
+/// qualified by a path, and they must be in scope in the module, e.g., by
+/// `use concordium_std::*`.
+///
+/// # Generated client
+/// The macro adds a struct to the module named after the contract in upper
+/// camel case with the suffix `Client`, e.g., `MyContractClient` for the
+/// contract `my_contract`. It holds the address of a contract instance and
+/// has a method for each receive function except a fallback, named after the
+/// function. Since the client is constructed with `new`, a receive function
+/// cannot be named `new`. The client implements `ContractInterface`, and each
+/// method invokes
+/// the entrypoint using `ContractInterface::invoke`, or
+/// `ContractInterface::invoke_read_only` for views, which then only need an
+/// immutable reference to the host.
+///
+/// The types of the method are taken from the attributes of the receive
+/// function:
+/// - The parameter has the type given by `parameter`, or by the typed parameter
+///   of the function. If neither is given, the method takes a parameter of any
+///   type implementing `Serial`, i.e., `&impl Serial`, which is not checked
+///   against the parameter the entrypoint expects.
+/// - The return value has the type given by `return_value`, and otherwise `()`.
+/// - The return value of a rejection has the type given by `error`. If it is
+///   not given, the type defaults to `()`, which discards the return value of a
+///   rejection.
+/// - If the receive function is `payable` the method takes an additional
+///   argument of type `Amount`. Otherwise, no CCD is transferred.
+///
+/// The documentation of each method states which of these defaults it uses.
+/// Errors in the attributes of the init and receive functions are reported by
+/// this macro.
+///
+/// # Event schema
+/// When building with the `build-schema` feature, the event types declared with
+/// the `event` attribute on the init and receive functions are merged into the
+/// event type of the contract using `merge_event_types`. If the event types
+/// differ, they must be enums whose tags do not conflict, otherwise building
+/// the schema panics. This is not checked when compiling the contract.
+///
+/// # Contract schema
+/// When building with the `build-schema` feature, the macro also adds the
+/// function `contract_schema` to the module, which returns the schema of the
+/// contract as a `ContractSchema`. This allows collecting the schema natively,
+/// e.g., in a test, and exporting it with the `concordium-schema-export`
+/// library.
+///
+/// ### Example
+/// ```ignore
+/// #[contract(name = "counter")]
+/// mod counter {
+///     use concordium_std::*;
+///
+///     #[init]
+///     fn init<S: HasStateApi>(
+///         _ctx: &impl HasInitContext,
+///         _state_builder: &mut StateBuilder<S>,
+///     ) -> InitResult<u64> {
+///         Ok(0)
+///     }
+///
+///     #[receive(name = "add", parameter = "u64", return_value = "u64", mutable)]
+///     fn add<S: HasStateApi>(
+///         ctx: &impl HasReceiveContext,
+///         host: &mut impl HasHost<u64, StateApiType = S>,
+///     ) -> ReceiveResult<u64> {
+///         let n: u64 = ctx.parameter_cursor().get()?;
+///         *host.state_mut() += n;
+///         Ok(*host.state())
+///     }
+/// }
+///
+/// // In another contract:
+/// let client = counter::CounterClient::new(counter_address);
+/// let total: u64 = client.add(host, &5)?;
+/// ```
+#[proc_macro_attribute]
+pub fn contract(attr: TokenStream, item: TokenStream) -> TokenStream {
+    unwrap_or_report(contract_worker(attr, item))
 }
 
-#[cfg(feature = "build-schema")]
-fn contract_function_event_schema(
-    event_option: Option<syn::LitStr>,
-    rust_name: syn::Ident,
-    wasm_name: String,
-) -> syn::Result<proc_macro2::TokenStream> {
-    let event_embed = match event_option {
-        Some(event_ty) => {
-            let event_ty = event_ty.parse::<syn::Type>()?;
-            Some(quote! {
-            let event = <#event_ty as schema::SchemaType>::get_type();
+fn contract_worker(attr: TokenStream, item: TokenStream) -> syn::Result<TokenStream> {
+    let mut ast: syn::ItemMod =
+        attach_error(syn::parse(item), "#[contract] can only be applied to modules.")?;
+
+    let attrs = Punctuated::<Meta, Token![,]>::parse_terminated.parse(attr)?;
+
+    let mut attributes = parse_attributes(&attrs)?;
+    let contract_name = attributes.extract_value(CONTRACT_ATTRIBUTE_NAME).ok_or_else(|| {
+        syn::Error::new(
+            Span::call_site(),
+            "A name for the contract must be provided, using the 'name' attribute.\n\nFor \
+             example, #[contract(name = \"my-contract\")]",
+        )
+    })?;
+    attributes.report_all_attributes()?;
+
+    if let Err(e) = ContractName::is_valid_contract_name(&format!("init_{}", contract_name.value()))
+    {
+        return Err(syn::Error::new(contract_name.span(), e));
+    }
+
+    let mod_span = ast.span();
+    let items = match ast.content.as_mut() {
+        Some((_, items)) => items,
+        None => {
+            return Err(syn::Error::new(
+                mod_span,
+                "#[contract] can only be applied to modules with a body, e.g., `mod my_contract { \
+                 ... }`.",
+            ))
+        }
+    };
+
+    let mut init_span: Option<Span> = None;
+    let mut entrypoints: BTreeMap<String, Span> = BTreeMap::new();
+    let mut client_methods = Vec::new();
+    let mut event_types = Vec::new();
+    let mut init_function = None;
+    let mut receive_functions = Vec::new();
+    for item in items.iter_mut() {
+        let item_fn = match item {
+            syn::Item::Fn(item_fn) => item_fn,
+            _ => continue,
+        };
+        let fn_name = item_fn.sig.ident.clone();
+        let doc = doc_comment(&item_fn.attrs);
+        for attr in item_fn.attrs.iter_mut() {
+            let is_init = attr.path.is_ident("init");
+            let is_view = attr.path.is_ident("view");
+            if !is_init && !is_view && !attr.path.is_ident("receive") {
+                continue;
+            }
+            let mut metas = if attr.tokens.is_empty() {
+                Punctuated::new()
+            } else {
+                attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?
+            };
+            contract_set_contract_name(&mut metas, &contract_name)?;
+            if !is_view {
+                // The event types of the init and receive functions are exported as part of
+                // the merged event type of the contract instead.
+                metas = metas
+                    .into_iter()
+                    .filter(|meta| match meta {
+                        Meta::NameValue(syn::MetaNameValue {
+                            path,
+                            lit: syn::Lit::Str(event),
+                            ..
+                        }) if path.is_ident(INIT_ATTRIBUTE_EVENT) => {
+                            event_types.push(event.clone());
+                            false
+                        }
+                        _ => true,
+                    })
+                    .collect();
+            }
+            attr.tokens = quote!((#metas));
+
+            if is_init {
+                if let Some(existing) = init_span {
+                    let mut error = syn::Error::new(
+                        existing,
+                        "A contract can only have one init function, but the first one appears \
+                         here.",
+                    );
+                    error.combine(syn::Error::new(attr.span(), "And another one appears here."));
+                    return Err(error);
+                }
+                init_span = Some(attr.span());
+                let init_attributes = parse_init_attributes(&metas)?;
+                init_function =
+                    Some((init_attributes.optional, init_attributes.metadata, doc.clone()));
+                continue;
+            }
+            if is_view {
+                check_view_attributes(&metas)?;
+            }
+            let mut receive_attributes = parse_receive_attributes(&metas)?;
+            receive_attributes.view = is_view;
+            receive_typed_parameter(&item_fn.sig, &mut receive_attributes)?;
+            let name = receive_attributes.name.value();
+            if let Some(existing) = entrypoints.get(&name) {
+                let message = if name.is_empty() {
+                    "Duplicate fallback entrypoint.".to_string()
+                } else {
+                    format!("Duplicate entrypoint '{}'.", name)
+                };
+                let mut error = syn::Error::new(*existing, message);
+                error.combine(syn::Error::new(
+                    receive_attributes.name.span(),
+                    "The entrypoint also appears here.",
+                ));
+                return Err(error);
+            }
+            entrypoints.insert(name.clone(), receive_attributes.name.span());
+            if !name.is_empty() {
+                client_methods.push(contract_client_method(&fn_name, &receive_attributes)?);
+            }
+            receive_functions.push((
+                name,
+                receive_attributes.optional,
+                doc.clone(),
+                receive_attributes.view,
+            ));
+        }
+    }
+
+    let client_ident = contract_client_ident(&contract_name);
+    let client_doc = format!(
+        "A typed client for invoking the entrypoints of the `{}` contract.",
+        contract_name.value()
+    );
+    items.push(syn::Item::Verbatim(quote! {
+        #[doc = #client_doc]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        pub struct #client_ident {
+            /// The address of the contract instance.
+            pub address: concordium_std::ContractAddress,
+        }
+
+        impl #client_ident {
+            /// Construct a client for the contract instance at the given address.
+            pub fn new(address: concordium_std::ContractAddress) -> Self {
+                Self {
+                    address,
+                }
+            }
+
+            #(#client_methods)*
+        }
+
+        impl concordium_std::ContractInterface for #client_ident {
+            fn address(&self) -> concordium_std::ContractAddress { self.address }
+        }
+    }));
+    items.push(syn::Item::Verbatim(contract_event_schema(&contract_name, &event_types)?));
+    items.push(syn::Item::Verbatim(contract_schema(
+        &contract_name,
+        init_function.as_ref(),
+        &receive_functions,
+        &event_types,
+    )?));
+    Ok(ast.into_token_stream().into())
+}
+
+/// Generate the function `contract_schema` in a `#[contract]` module, which
+/// returns the schema of the contract, so that tools can collect it natively.
+#[cfg(feature = "build-schema")]
+fn contract_schema(
+    contract_name: &syn::LitStr,
+    init_function: Option<&(OptionalArguments, MetadataAttributes, String)>,
+    receive_functions: &[(String, OptionalArguments, String, bool)],
+    event_types: &[syn::LitStr],
+) -> syn::Result<proc_macro2::TokenStream> {
+    let (init, metadata) = match init_function {
+        Some((init_function, metadata, doc)) => {
+            let function = contract_function_schema_value(init_function)?;
+            (quote!(Some(#function)), contract_metadata_value(metadata, doc))
+        }
+        None => (quote!(None), quote!(concordium_std::ContractMetadata::default())),
+    };
+    let receive_names: Vec<_> = receive_functions.iter().map(|(name, ..)| name).collect();
+    let receive_docs = receive_functions.iter().map(|(_, _, doc, _)| doc);
+    let receive_views = receive_functions.iter().map(|(.., view)| view);
+    let receive_functions = receive_functions
+        .iter()
+        .map(|(_, function, ..)| contract_function_schema_value(function))
+        .collect::<syn::Result<Vec<_>>>()?;
+    let event = if event_types.is_empty() {
+        quote!(None)
+    } else {
+        let event_types = event_types
+            .iter()
+            .map(|ty| ty.parse::<syn::Type>())
+            .collect::<syn::Result<Vec<_>>>()?;
+        let event = merged_event_type(&event_types);
+        quote!(Some(#event))
+    };
+    Ok(quote! {
+        /// The schema of the contract, which allows tools to collect it natively
+        /// instead of from a Wasm module.
+        pub fn contract_schema() -> concordium_std::ContractSchema {
+            let mut receive = concordium_std::collections::BTreeMap::new();
+            #(receive.insert(concordium_std::String::from(#receive_names), #receive_functions);)*
+            let mut entrypoints = concordium_std::collections::BTreeMap::new();
+            #(entrypoints.insert(concordium_std::String::from(#receive_names), concordium_std::EntrypointMetadata {
+                doc:  concordium_std::String::from(#receive_docs),
+                view: #receive_views,
+            });)*
+            concordium_std::ContractSchema {
+                name:      concordium_std::String::from(#contract_name),
+                functions: concordium_std::schema::ContractV2 {
+                    init: #init,
+                    receive,
+                },
+                event:     #event,
+                metadata:  #metadata,
+                entrypoints,
+            }
+        }
+    })
+}
+
+#[cfg(not(feature = "build-schema"))]
+fn contract_schema(
+    _contract_name: &syn::LitStr,
+    _init_function: Option<&(OptionalArguments, MetadataAttributes, String)>,
+    _receive_functions: &[(String, OptionalArguments, String, bool)],
+    _event_types: &[syn::LitStr],
+) -> syn::Result<proc_macro2::TokenStream> {
+    Ok(proc_macro2::TokenStream::new())
+}
+
+/// Generate an expression constructing the `FunctionV2` schema of a contract
+/// function from the types given in its attributes.
+#[cfg(feature = "build-schema")]
+fn contract_function_schema_value(
+    function: &OptionalArguments,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let schema_type = |ty: &Option<syn::LitStr>| -> syn::Result<proc_macro2::TokenStream> {
+        match ty {
+            Some(ty) => {
+                let ty = ty.parse::<syn::Type>()?;
+                Ok(quote!(Some(<#ty as concordium_std::schema::SchemaType>::get_type())))
+            }
+            None => Ok(quote!(None)),
+        }
+    };
+    let parameter = schema_type(&function.parameter)?;
+    let return_value = schema_type(&function.return_value)?;
+    let error = schema_type(&function.error)?;
+    Ok(quote! {
+        concordium_std::schema::FunctionV2 {
+            parameter:    #parameter,
+            return_value: #return_value,
+            error:        #error,
+        }
+    })
+}
+
+/// Generate an expression merging the given event types into the event type of
+/// a contract, which panics if they are incompatible.
+#[cfg(feature = "build-schema")]
+fn merged_event_type(event_types: &[syn::Type]) -> proc_macro2::TokenStream {
+    quote! {
+        match concordium_std::merge_event_types(concordium_std::Vec::from([
+            #(<#event_types as concordium_std::schema::SchemaType>::get_type()),*
+        ])) {
+            Ok(Some(event)) => event,
+            Ok(None) => unreachable!(),
+            Err(err) => panic!("The event types of the contract are incompatible: {:?}", err),
+        }
+    }
+}
+
+/// Generate the export of the event type of a `#[contract]`, which is the
+/// merge of the event types of its entrypoints. This replaces the export of the
+/// event type of the init function.
+#[cfg(feature = "build-schema")]
+fn contract_event_schema(
+    contract_name: &syn::LitStr,
+    event_types: &[syn::LitStr],
+) -> syn::Result<proc_macro2::TokenStream> {
+    // Only produce the schema function if an event was set.
+    if event_types.is_empty() {
+        return Ok(proc_macro2::TokenStream::new());
+    }
+    let event_types =
+        event_types.iter().map(|ty| ty.parse::<syn::Type>()).collect::<syn::Result<Vec<_>>>()?;
+    let schema_name = format!("concordium_event_schema_init_{}", contract_name.value());
+    let event = merged_event_type(&event_types);
+    Ok(quote! {
+        #[export_name = #schema_name]
+        pub extern "C" fn concordium_event_schema_contract() -> *mut u8 {
+            let event = #event;
+            let schema_bytes = concordium_std::to_bytes(&event);
+            concordium_std::put_in_memory(&schema_bytes)
+        }
+    })
+}
+
+#[cfg(not(feature = "build-schema"))]
+fn contract_event_schema(
+    _contract_name: &syn::LitStr,
+    _event_types: &[syn::LitStr],
+) -> syn::Result<proc_macro2::TokenStream> {
+    Ok(proc_macro2::TokenStream::new())
+}
+
+/// Add the `contract` attribute to the attributes of an init or receive
+/// function in a `#[contract]` module, or check that it matches the name of the
+/// contract if it is already present.
+fn contract_set_contract_name(
+    metas: &mut Punctuated<Meta, Token![,]>,
+    contract_name: &syn::LitStr,
+) -> syn::Result<()> {
+    let existing = metas.iter().find(|meta| meta.path().is_ident(INIT_ATTRIBUTE_CONTRACT));
+    match existing {
+        Some(Meta::NameValue(syn::MetaNameValue {
+            lit: syn::Lit::Str(existing),
+            ..
+        })) => {
+            if existing.value() != contract_name.value() {
+                let mut error = syn::Error::new(
+                    existing.span(),
+                    format!(
+                        "The contract '{}' does not match the enclosing #[contract], which is \
+                         named '{}'.",
+                        existing.value(),
+                        contract_name.value()
+                    ),
+                );
+                error.combine(syn::Error::new(
+                    contract_name.span(),
+                    "The name of the enclosing contract is given here.",
+                ));
+                return Err(error);
+            }
+        }
+        // Malformed attributes are reported when the #[init] or #[receive] macro is expanded.
+        Some(_) => (),
+        None => metas.push(syn::parse_quote!(contract = #contract_name)),
+    }
+    Ok(())
+}
+
+/// The name of the client generated by `#[contract]`, which is the name of the
+/// contract in upper camel case with the suffix `Client`.
+fn contract_client_ident(contract_name: &syn::LitStr) -> syn::Ident {
+    let mut name = String::new();
+    for part in
+        contract_name.value().split(|c: char| !c.is_ascii_alphanumeric()).filter(|p| !p.is_empty())
+    {
+        let mut chars = part.chars();
+        if let Some(first) = chars.next() {
+            name.push(first.to_ascii_uppercase());
+            name.extend(chars);
+        }
+    }
+    // Identifiers cannot start with a digit.
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert_str(0, "Contract");
+    }
+    syn::Ident::new(&format!("{}Client", name), contract_name.span())
+}
+
+/// Generate the method of the client generated by `#[contract]` for invoking
+/// the given receive function.
+fn contract_client_method(
+    fn_name: &syn::Ident,
+    receive_attributes: &ReceiveAttributes,
+) -> syn::Result<proc_macro2::TokenStream> {
+    if fn_name == "new" {
+        return Err(syn::Error::new(
+            fn_name.span(),
+            "The receive function cannot be named 'new' in a #[contract] module, since the \
+             generated client is constructed with `new`. Rename the function, the name of the \
+             entrypoint is given by the 'name' attribute.",
+        ));
+    }
+    let optional = &receive_attributes.optional;
+    let parameter_type = match &optional.parameter {
+        Some(ty) => {
+            let ty: syn::Type = ty.parse()?;
+            quote!(&#ty)
+        }
+        None => quote!(&impl concordium_std::Serial),
+    };
+    let return_value_type = match &optional.return_value {
+        Some(ty) => ty.parse::<syn::Type>()?.into_token_stream(),
+        None => quote!(()),
+    };
+    let error_type = match &optional.error {
+        Some(ty) => ty.parse::<syn::Type>()?.into_token_stream(),
+        None => quote!(()),
+    };
+    let (amount_arg, amount) = if optional.payable {
+        (quote!(amount: concordium_std::Amount,), quote!(amount))
+    } else {
+        (quote!(), quote!(concordium_std::Amount::zero()))
+    };
+    // Views do not modify the state of the contract, so they can be invoked
+    // without a mutable reference to the host.
+    let (host_type, invoke) = if receive_attributes.view {
+        (quote!(&impl concordium_std::HasHost<State>), quote!(invoke_read_only))
+    } else {
+        (quote!(&mut impl concordium_std::HasHost<State>), quote!(invoke))
+    };
+    let entrypoint = &receive_attributes.name;
+    let mut doc = vec![format!("Invoke the `{}` entrypoint of the contract.", entrypoint.value())];
+    if optional.parameter.is_none() {
+        doc.push(
+            "The parameter type is not declared by the receive function, so any parameter \
+             implementing `Serial` is accepted."
+                .to_string(),
+        );
+    }
+    if optional.error.is_none() {
+        doc.push(
+            "The error type is not declared by the receive function, so the return value of a \
+             rejection is `()`."
+                .to_string(),
+        );
+    }
+    let doc = doc.join("\n\n");
+    Ok(quote! {
+        #[doc = #doc]
+        pub fn #fn_name<State>(
+            &self,
+            host: #host_type,
+            parameter: #parameter_type,
+            #amount_arg
+        ) -> Result<#return_value_type, concordium_std::CallContractError<#error_type>> {
+            concordium_std::ContractInterface::#invoke(
+                self,
+                host,
+                concordium_std::EntrypointName::new_unchecked(#entrypoint),
+                parameter,
+                #amount,
+            )
+        }
+    })
+}
+
+/// Generate tokens for some of the optional arguments, based on the attributes.
+/// Returns a pair, where the first entry is tokens for setting up the arguments
+/// and the second entry is a Vec of the argument names as tokens.
+///
+/// It also mutates a vector of required arguments with the expected type
+/// signature of each.
+fn contract_function_optional_args_tokens(
+    optional: &OptionalArguments,
+    amount_ident: &syn::Ident,
+    required_args: &mut Vec<&str>,
+) -> (proc_macro2::TokenStream, Vec<proc_macro2::TokenStream>) {
+    let mut setup_fn_args = proc_macro2::TokenStream::new();
+    let mut fn_args = vec![];
+    if optional.payable {
+        required_args.push("amount: Amount");
+        fn_args.push(quote!(#amount_ident));
+    } else {
+        setup_fn_args.extend(quote! {
+            if #amount_ident.micro_ccd != 0 {
+                return concordium_std::Reject::from(concordium_std::NotPayableError).error_code.get();
+            }
+        });
+    };
+
+    if optional.enable_logger {
+        required_args.push("logger: &mut impl HasLogger");
+        let logger_ident = format_ident!("logger");
+        setup_fn_args.extend(quote!(let mut #logger_ident = concordium_std::Logger::init();));
+        fn_args.push(quote!(&mut #logger_ident));
+    }
+
+    if optional.crypto_primitives {
+        required_args.push("crypto_primitives: &impl HasCryptoPrimitives");
+        let crypto_primitives_ident = format_ident!("crypto_primitives");
+        setup_fn_args
+            .extend(quote!(let #crypto_primitives_ident = concordium_std::ExternCryptoPrimitives;));
+        fn_args.push(quote!(&#crypto_primitives_ident));
+    }
+
+    (setup_fn_args, fn_args)
+}
+
+/// Check the access requirements of a receive function at the start of its
+/// body, rejecting with the corresponding [`AccessDeniedError`] if a
+/// requirement is not met. The requirements are thus also checked when the
+/// function is called with a `TestReceiveContext` in tests.
+fn with_access_check(ast: &mut syn::ItemFn, access: &AccessAttributes) -> syn::Result<()> {
+    if access.requires_sender.is_none() && access.require_policy.is_none() {
+        return Ok(());
+    }
+    let ctx = match ast.sig.inputs.first() {
+        Some(syn::FnArg::Typed(syn::PatType {
+            pat,
+            ..
+        })) => match &**pat {
+            syn::Pat::Ident(pat) => pat.ident.clone(),
+            pat => {
+                return Err(syn::Error::new(
+                    pat.span(),
+                    "The context of a function with the attribute 'requires_sender' or \
+                     'require_policy' must be bound to a name, e.g., `ctx`.",
+                ))
+            }
+        },
+        // Missing arguments are reported as an incorrect number of arguments.
+        _ => return Ok(()),
+    };
+    let mut checks = proc_macro2::TokenStream::new();
+    if let Some(kind) = &access.requires_sender {
+        checks.extend(quote! {
+            if !concordium_std::SenderKind::#kind.matches(&concordium_std::HasReceiveContext::sender(#ctx)) {
+                return Err(concordium_std::AccessDeniedError::WrongSenderKind.into());
+            }
+        });
+    }
+    if let Some(predicate) = &access.require_policy {
+        checks.extend(quote! {
+            let slot_time = concordium_std::HasChainMetadata::slot_time(concordium_std::HasCommonData::metadata(#ctx));
+            if !concordium_std::HasCommonData::policies(#ctx).all(|policy| #predicate(&policy, slot_time)) {
+                return Err(concordium_std::AccessDeniedError::PolicyNotSatisfied.into());
+            }
+        });
+    }
+    let block = &ast.block;
+    *ast.block = syn::parse_quote! {{
+        {
+            #checks
+        }
+        #block
+    }};
+    Ok(())
+}
+
+/// Generate an expression constructing the `ContractMetadata` declared on an
+/// init function.
+#[cfg(feature = "build-schema")]
+fn contract_metadata_value(metadata: &MetadataAttributes, doc: &str) -> proc_macro2::TokenStream {
+    let optional_string = |value: &Option<syn::LitStr>| match value {
+        Some(value) => quote!(Some(concordium_std::String::from(#value))),
+        None => quote!(None),
+    };
+    let version = optional_string(&metadata.version);
+    let standards = &metadata.standards;
+    let repository = optional_string(&metadata.repository);
+    quote! {
+        concordium_std::ContractMetadata {
+            version:    #version,
+            standards:  concordium_std::Vec::from([#(concordium_std::String::from(#standards)),*]),
+            repository: #repository,
+            doc:        concordium_std::String::from(#doc),
+        }
+    }
+}
+
+#[cfg(feature = "build-schema")]
+fn contract_metadata_schema(
+    metadata: &MetadataAttributes,
+    doc: &str,
+    rust_name: syn::Ident,
+    wasm_name: String,
+) -> proc_macro2::TokenStream {
+    let metadata = contract_metadata_value(metadata, doc);
+    let schema_name = format!("concordium_metadata_{}", wasm_name);
+    let schema_ident = format_ident!("concordium_metadata_{}", rust_name);
+    quote! {
+        #[export_name = #schema_name]
+        pub extern "C" fn #schema_ident() -> *mut u8 {
+            let metadata = #metadata;
+            let schema_bytes = concordium_std::to_bytes(&metadata);
+            concordium_std::put_in_memory(&schema_bytes)
+        }
+    }
+}
+
+#[cfg(not(feature = "build-schema"))]
+fn contract_metadata_schema(
+    _metadata: &MetadataAttributes,
+    _doc: &str,
+    _rust_name: syn::Ident,
+    _wasm_name: String,
+) -> proc_macro2::TokenStream {
+    proc_macro2::TokenStream::new()
+}
+
+#[cfg(feature = "build-schema")]
+fn entrypoint_metadata_schema(
+    doc: &str,
+    view: bool,
+    rust_name: syn::Ident,
+    wasm_name: String,
+) -> proc_macro2::TokenStream {
+    // Only produce the function if the receive function is documented or a view.
+    if doc.is_empty() && !view {
+        return proc_macro2::TokenStream::new();
+    }
+    let schema_name = format!("concordium_metadata_{}", wasm_name);
+    let schema_ident = format_ident!("concordium_metadata_{}", rust_name);
+    quote! {
+        #[export_name = #schema_name]
+        pub extern "C" fn #schema_ident() -> *mut u8 {
+            let metadata = concordium_std::EntrypointMetadata {
+                doc:  concordium_std::String::from(#doc),
+                view: #view,
+            };
+            let schema_bytes = concordium_std::to_bytes(&metadata);
+            concordium_std::put_in_memory(&schema_bytes)
+        }
+    }
+}
+
+#[cfg(not(feature = "build-schema"))]
+fn entrypoint_metadata_schema(
+    _doc: &str,
+    _view: bool,
+    _rust_name: syn::Ident,
+    _wasm_name: String,
+) -> proc_macro2::TokenStream {
+    proc_macro2::TokenStream::new()
+}
+
+#[cfg(feature = "build-schema")]
+fn contract_function_access_schema(
+    access: &AccessAttributes,
+    rust_name: syn::Ident,
+    wasm_name: String,
+) -> proc_macro2::TokenStream {
+    // Only produce the function if there are any access requirements.
+    if access.requires_sender.is_none() && access.require_policy.is_none() {
+        return proc_macro2::TokenStream::new();
+    }
+    let sender = match &access.requires_sender {
+        Some(kind) => quote!(Some(concordium_std::SenderKind::#kind)),
+        None => quote!(None),
+    };
+    let policy = match &access.require_policy {
+        Some(predicate) => {
+            let path = predicate.to_token_stream().to_string().replace(' ', "");
+            quote!(Some(concordium_std::String::from(#path)))
+        }
+        None => quote!(None),
+    };
+    let schema_name = format!("concordium_access_schema_{}", wasm_name);
+    let schema_ident = format_ident!("concordium_access_schema_{}", rust_name);
+    quote! {
+        #[export_name = #schema_name]
+        pub extern "C" fn #schema_ident() -> *mut u8 {
+            let access = concordium_std::EntrypointAccess {
+                sender: #sender,
+                policy: #policy,
+            };
+            let schema_bytes = concordium_std::to_bytes(&access);
+            concordium_std::put_in_memory(&schema_bytes)
+        }
+    }
+}
+
+#[cfg(not(feature = "build-schema"))]
+fn contract_function_access_schema(
+    _access: &AccessAttributes,
+    _rust_name: syn::Ident,
+    _wasm_name: String,
+) -> proc_macro2::TokenStream {
+    proc_macro2::TokenStream::new()
+}
+
+#[cfg(feature = "build-schema")]
+fn contract_function_event_schema(
+    event_option: Option<syn::LitStr>,
+    rust_name: syn::Ident,
+    wasm_name: String,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let event_embed = match event_option {
+        Some(event_ty) => {
+            let event_ty = event_ty.parse::<syn::Type>()?;
+            Some(quote! {
+            let event = <#event_ty as schema::SchemaType>::get_type();
             let schema_bytes = concordium_std::to_bytes(&event);})
         }
         _ => None,
@@ -1182,25 +2579,61 @@ const CONCORDIUM_ATTRIBUTE: &str = "concordium";
 /// A list of valid concordium field attributes
 const VALID_CONCORDIUM_FIELD_ATTRIBUTES: [&str; 3] = ["size_length", "ensure_ordered", "rename"];
 
+/// A list of valid concordium attributes of enum variants. The attributes
+/// other than `rename` are used by `derive(Reject)`, which is derived alongside
+/// `derive(SchemaType)` for errors with a schema.
+const VALID_CONCORDIUM_VARIANT_ATTRIBUTES: [&str; 4] =
+    ["rename", "error_code", "transparent", "message"];
+
+/// A list of valid concordium attributes of the variants of an enum deriving
+/// `Event`, which also derives the schema of the enum.
+const VALID_CONCORDIUM_EVENT_VARIANT_ATTRIBUTES: [&str; 3] = ["rename", "tag", "flatten"];
+
 /// A list of valid concordium attributes
-const VALID_CONCORDIUM_ATTRIBUTES: [&str; 1] = ["state_parameter"];
+const VALID_CONCORDIUM_ATTRIBUTES: [&str; 4] =
+    ["state_parameter", "error_code_base", "with_messages", "check_schema"];
 
 /// Finds concordium field attributes.
 fn get_concordium_field_attributes(attributes: &[syn::Attribute]) -> syn::Result<Vec<syn::Meta>> {
     get_concordium_attributes(attributes, true)
 }
 
+/// Finds concordium attributes of an enum variant, which must be among the
+/// given valid attributes.
+fn get_concordium_variant_attributes(
+    attributes: &[syn::Attribute],
+    valid_attributes: &[&str],
+) -> syn::Result<Vec<syn::Meta>> {
+    filter_concordium_attributes(attributes, valid_attributes, "concordium variant attribute")
+}
+
 /// Finds concordium attributes, either field or general attributes.
 fn get_concordium_attributes(
     attributes: &[syn::Attribute],
     for_field: bool,
 ) -> syn::Result<Vec<syn::Meta>> {
-    let (valid_attributes, attribute_type) = if for_field {
-        (&VALID_CONCORDIUM_FIELD_ATTRIBUTES[..], "concordium field attribute")
+    if for_field {
+        filter_concordium_attributes(
+            attributes,
+            &VALID_CONCORDIUM_FIELD_ATTRIBUTES,
+            "concordium field attribute",
+        )
     } else {
-        (&VALID_CONCORDIUM_ATTRIBUTES[..], "concordium attribute")
-    };
+        filter_concordium_attributes(
+            attributes,
+            &VALID_CONCORDIUM_ATTRIBUTES,
+            "concordium attribute",
+        )
+    }
+}
 
+/// Finds concordium attributes, and ensures that they are among the given
+/// valid attributes.
+fn filter_concordium_attributes(
+    attributes: &[syn::Attribute],
+    valid_attributes: &[&str],
+    attribute_type: &str,
+) -> syn::Result<Vec<syn::Meta>> {
     attributes
         .iter()
         // Keep only concordium attributes
@@ -1235,13 +2668,33 @@ fn find_field_attribute_value(
     find_attribute_value(attributes, true, target_attr)
 }
 
+fn find_variant_attribute_value(
+    attributes: &[syn::Attribute],
+    valid_attributes: &[&str],
+    target_attr: &str,
+) -> syn::Result<Option<syn::Lit>> {
+    find_unique_attribute_value(
+        get_concordium_variant_attributes(attributes, valid_attributes)?,
+        target_attr,
+    )
+}
+
 fn find_attribute_value(
     attributes: &[syn::Attribute],
     for_field: bool,
     target_attr: &str,
+) -> syn::Result<Option<syn::Lit>> {
+    find_unique_attribute_value(get_concordium_attributes(attributes, for_field)?, target_attr)
+}
+
+/// Find the value of an attribute among the given concordium attributes, and
+/// check that it is only specified once.
+fn find_unique_attribute_value(
+    attributes: Vec<syn::Meta>,
+    target_attr: &str,
 ) -> syn::Result<Option<syn::Lit>> {
     let target_attr = format_ident!("{}", target_attr);
-    let attr_values: Vec<_> = get_concordium_attributes(attributes, for_field)?
+    let attr_values: Vec<_> = attributes
         .into_iter()
         .filter_map(|nested_meta| match nested_meta {
             syn::Meta::NameValue(value) if value.path.is_ident(&target_attr) => Some(value.lit),
@@ -1322,8 +2775,24 @@ fn find_state_parameter_attribute(
 /// Checks that the attribute is only defined once and that the value is a
 /// string.
 #[cfg(feature = "build-schema")]
-fn find_rename_attribute(attributes: &[syn::Attribute]) -> syn::Result<Option<(String, Span)>> {
-    let value = match find_field_attribute_value(attributes, "rename")? {
+fn find_rename_attribute(attributes: &[syn::Attribute]) -> syn::Result<Option<(String, Span)>> {
+    parse_rename_attribute(find_field_attribute_value(attributes, "rename")?)
+}
+
+/// Find a 'rename' attribute of an enum variant, whose attributes must be among
+/// the given valid attributes, and return its value and span.
+#[cfg(feature = "build-schema")]
+fn find_variant_rename_attribute(
+    attributes: &[syn::Attribute],
+    valid_attributes: &[&str],
+) -> syn::Result<Option<(String, Span)>> {
+    parse_rename_attribute(find_variant_attribute_value(attributes, valid_attributes, "rename")?)
+}
+
+/// Check that the value of a 'rename' attribute is a string.
+#[cfg(feature = "build-schema")]
+fn parse_rename_attribute(value: Option<syn::Lit>) -> syn::Result<Option<(String, Span)>> {
+    let value = match value {
         Some(v) => v,
         None => return Ok(None),
     };
@@ -1445,7 +2914,7 @@ fn impl_deserial_with_state(ast: &syn::DeriveInput) -> syn::Result<TokenStream>
                             &field_ident,
                             &source_ident,
                             &state_parameter,
-                        ));
+                        )?);
                         names.extend(quote!(#field_ident,))
                     }
                     quote!(Ok(#data_name{#names}))
@@ -1459,7 +2928,7 @@ fn impl_deserial_with_state(ast: &syn::DeriveInput) -> syn::Result<TokenStream>
                             &field_ident,
                             &source_ident,
                             &state_parameter,
-                        ));
+                        )?);
                         names.extend(quote!(#field_ident,))
                     }
                     quote!(Ok(#data_name(#names)))
@@ -1552,7 +3021,26 @@ fn impl_deserial_with_state(ast: &syn::DeriveInput) -> syn::Result<TokenStream>
 /// Derive the `SchemaType` trait for a type.
 /// If the feature `build-schema` is not enabled this is a no-op, i.e., it does
 /// not produce any code.
-#[proc_macro_derive(SchemaType, attributes(size_length))]
+///
+/// With `#[concordium(check_schema)]` on the type, a unit test is generated
+/// which calls `concordium_std::test_infrastructure::check_schema` to check
+/// that the schema agrees with the `Serial` and `Deserial` implementations of
+/// the type. The test is only generated if both the `build-schema` and the
+/// `schema-json` features are enabled, and the type must not be generic.
+///
+/// If the feature `error-messages` is enabled, the schema of an error enum with
+/// `#[concordium(with_messages)]`, see [`Reject`], also includes the
+/// `ErrorEnvelope` as the variant with tag 255.
+///
+/// ```ignore
+/// #[derive(Serial, Deserial, SchemaType)]
+/// #[concordium(check_schema)]
+/// struct MyStruct {
+///     #[concordium(size_length = 1)]
+///     names: Vec<String>,
+/// }
+/// ```
+#[proc_macro_derive(SchemaType, attributes(size_length, concordium))]
 pub fn schema_type_derive(input: TokenStream) -> TokenStream {
     unwrap_or_report(schema_type_derive_worker(input))
 }
@@ -1579,8 +3067,10 @@ fn schema_type_derive_worker(input: TokenStream) -> syn::Result<TokenStream> {
                 .iter()
                 .map(|variant| {
                     // Handle the 'rename' attribute.
-                    let (variant_name, variant_span) = match find_rename_attribute(&variant.attrs)?
-                    {
+                    let (variant_name, variant_span) = match find_variant_rename_attribute(
+                        &variant.attrs,
+                        &VALID_CONCORDIUM_VARIANT_ATTRIBUTES,
+                    )? {
                         Some(name_and_span) => name_and_span,
                         None => (variant.ident.to_string(), variant.ident.span()),
                     };
@@ -1596,13 +3086,43 @@ fn schema_type_derive_worker(input: TokenStream) -> syn::Result<TokenStream> {
                     })
                 })
                 .collect::<syn::Result<_>>()?;
-            quote! {
+            let enum_type = quote! {
                 concordium_std::schema::Type::Enum(concordium_std::Vec::from([ #(#variant_tokens),* ]))
+            };
+            // With the feature `error-messages`, the errors deriving `Reject` with messages
+            // are returned in an `ErrorEnvelope`, which the schema must be able to decode
+            // as well.
+            let with_messages =
+                contains_attribute(&get_concordium_attributes(&ast.attrs, false)?, "with_messages");
+            if with_messages && cfg!(feature = "error-messages") {
+                let error_msg = format!(
+                    "The variants of {} conflict with the error envelope: {{:?}}",
+                    data_name
+                );
+                quote! {
+                    match concordium_std::merge_event_types(concordium_std::Vec::from([
+                        #enum_type,
+                        <concordium_std::ErrorEnvelope as concordium_std::schema::SchemaType>::get_type(),
+                    ])) {
+                        Ok(Some(error)) => error,
+                        Ok(None) => unreachable!(),
+                        Err(err) => panic!(#error_msg, err),
+                    }
+                }
+            } else {
+                enum_type
             }
         }
         _ => syn::Error::new(ast.span(), "Union is not supported").to_compile_error(),
     };
 
+    let check_schema_tokens =
+        if contains_attribute(&get_concordium_attributes(&ast.attrs, false)?, "check_schema") {
+            check_schema_test(&ast)?
+        } else {
+            proc_macro2::TokenStream::new()
+        };
+
     let out = quote! {
         #[automatically_derived]
         impl #impl_generics concordium_std::schema::SchemaType for #data_name #ty_generics #where_clauses {
@@ -1610,6 +3130,8 @@ fn schema_type_derive_worker(input: TokenStream) -> syn::Result<TokenStream> {
                 #body
             }
         }
+
+        #check_schema_tokens
     };
     Ok(out.into())
 }
@@ -1619,6 +3141,35 @@ fn schema_type_derive_worker(_input: TokenStream) -> syn::Result<TokenStream> {
     Ok(TokenStream::new())
 }
 
+/// Generate the unit test for the `check_schema` attribute, which checks that
+/// the schema of the type agrees with its serialization.
+#[cfg(all(feature = "build-schema", feature = "schema-json"))]
+fn check_schema_test(ast: &syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    if !ast.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            ast.generics.span(),
+            "The `check_schema` attribute is not supported for generic types. Call \
+             `concordium_std::test_infrastructure::check_schema` in a test for each instantiation \
+             instead.",
+        ));
+    }
+    let data_name = &ast.ident;
+    let test_name = format_ident!("concordium_check_schema_{}", data_name);
+    Ok(quote! {
+        #[cfg(test)]
+        #[test]
+        #[allow(non_snake_case)]
+        fn #test_name() {
+            concordium_std::test_infrastructure::check_schema::<#data_name>();
+        }
+    })
+}
+
+#[cfg(all(feature = "build-schema", not(feature = "schema-json")))]
+fn check_schema_test(_ast: &syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    Ok(proc_macro2::TokenStream::new())
+}
+
 #[cfg(feature = "build-schema")]
 fn schema_type_field_type(field: &syn::Field) -> syn::Result<proc_macro2::TokenStream> {
     let field_type = &field.ty;
@@ -1668,6 +3219,361 @@ fn schema_type_fields(fields: &syn::Fields) -> syn::Result<proc_macro2::TokenStr
     }
 }
 
+/// The event tags reserved for the events of the CIS-2 standard and its
+/// extensions in `concordium-cis2`, i.e., the tags of `Cis2Event`, `Cis3Event`
+/// and `Cis2AllowanceEvent`. These are only used by flattening those types
+/// into an event type.
+const RESERVED_EVENT_TAGS: std::ops::RangeInclusive<u8> = (u8::MAX - 6)..=u8::MAX;
+
+/// Derive `Serial`, `Deserial` and `TaggedEvent`, and `SchemaType` if the
+/// feature `build-schema` is enabled, for an enum of events with explicit tags.
+///
+/// Every variant must have one of the following attributes.
+/// - `#[concordium(tag = N)]`: The event is serialized as the tag `N`, which
+///   must be unique in the enum, followed by the fields of the variant. Tags
+///   249 to 255 are reserved for the events of the CIS-2 standard and its
+///   extensions.
+/// - `#[concordium(flatten)]`: The variant must have exactly one unnamed field
+///   whose type implements `TaggedEvent`, such as `Cis2Event`, and the event is
+///   serialized as that type. This way the events of a standard can be logged
+///   with their own tags along with the events of the contract.
+///
+/// Events from another event type can also be nested by using that type as a
+/// field of a tagged variant, in which case they are serialized after the tag
+/// of the variant.
+///
+/// Fields support the `size_length` and `ensure_ordered` attributes as in
+/// [`derive(Deserial)`](./derive.Deserial.html). In the schema, the variants
+/// of flattened event types are merged into the variants of the enum, and
+/// variants can be renamed using `#[concordium(rename = "...")]`.
+///
+/// Duplicate tags are rejected by the macro. Overlaps between the tags of the
+/// enum and the tags of flattened event types are rejected at compile time
+/// with an error that the index is out of bounds. For a generic enum this
+/// happens when the events are serialized or parsed, since the tags of the
+/// flattened types can only be checked for concrete type arguments.
+///
+/// ### Example
+/// ```ignore
+/// #[derive(Event)]
+/// enum MyEvent<T: IsTokenId, A: IsTokenAmount> {
+///     #[concordium(flatten)]
+///     Cis2(Cis2Event<T, A>),
+///     #[concordium(tag = 1)]
+///     Paused {
+///         paused: bool,
+///     },
+///     #[concordium(tag = 0)]
+///     Message(#[concordium(size_length = 1)] String),
+/// }
+/// ```
+#[proc_macro_derive(Event, attributes(concordium))]
+pub fn event_derive(input: TokenStream) -> TokenStream {
+    unwrap_or_report(event_derive_worker(input))
+}
+
+/// How a variant of an enum deriving `Event` is serialized.
+enum EventVariantKind<'a> {
+    /// The variant is serialized with the given tag, followed by its fields.
+    Tagged(u8),
+    /// The variant is serialized as its only field, which is of the given
+    /// tagged event type.
+    Flattened(&'a syn::Type),
+}
+
+/// Get the kind of a variant from its `tag` or `flatten` attribute.
+fn event_variant_kind(variant: &syn::Variant) -> syn::Result<EventVariantKind> {
+    let tag = find_variant_attribute_value(
+        &variant.attrs,
+        &VALID_CONCORDIUM_EVENT_VARIANT_ATTRIBUTES,
+        "tag",
+    )?;
+    let flatten = contains_attribute(
+        &get_concordium_variant_attributes(
+            &variant.attrs,
+            &VALID_CONCORDIUM_EVENT_VARIANT_ATTRIBUTES,
+        )?,
+        "flatten",
+    );
+    match (tag, flatten) {
+        (Some(tag), false) => {
+            let tag_span = tag.span();
+            let tag = match tag {
+                syn::Lit::Int(int) => int.base10_parse::<u8>().ok(),
+                _ => None,
+            }
+            .ok_or_else(|| {
+                syn::Error::new(
+                    tag_span,
+                    format!(
+                        "The tag of an event must be an integer from 0 to {}.",
+                        RESERVED_EVENT_TAGS.start() - 1
+                    ),
+                )
+            })?;
+            if RESERVED_EVENT_TAGS.contains(&tag) {
+                return Err(syn::Error::new(
+                    tag_span,
+                    format!(
+                        "The tags {} to {} are reserved for the events of the CIS-2 standard and \
+                         its extensions. Use a variant with #[concordium(flatten)] to include \
+                         `Cis2Event`, `Cis3Event` or `Cis2AllowanceEvent`.",
+                        RESERVED_EVENT_TAGS.start(),
+                        RESERVED_EVENT_TAGS.end()
+                    ),
+                ));
+            }
+            Ok(EventVariantKind::Tagged(tag))
+        }
+        (None, true) => match &variant.fields {
+            syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                Ok(EventVariantKind::Flattened(&fields.unnamed[0].ty))
+            }
+            _ => Err(syn::Error::new(
+                variant.span(),
+                "A flattened event must have exactly one unnamed field, e.g., \
+                 `Cis2(Cis2Event<ContractTokenId, ContractTokenAmount>)`.",
+            )),
+        },
+        (Some(_), true) => Err(syn::Error::new(
+            variant.span(),
+            "An event cannot have both a tag and the `flatten` attribute.",
+        )),
+        (None, false) => Err(syn::Error::new(
+            variant.span(),
+            "An event must have either a tag, e.g., #[concordium(tag = 0)], or the `flatten` \
+             attribute.",
+        )),
+    }
+}
+
+fn event_derive_worker(input: TokenStream) -> syn::Result<TokenStream> {
+    let ast: syn::DeriveInput = syn::parse(input)?;
+    let data = match &ast.data {
+        syn::Data::Enum(data) if !data.variants.is_empty() => data,
+        _ => {
+            return Err(syn::Error::new(
+                ast.span(),
+                "Event can only be derived for enums with at least one variant.",
+            ))
+        }
+    };
+    let data_name = &ast.ident;
+    let (impl_generics, ty_generics, where_clauses) = ast.generics.split_for_impl();
+
+    let out_ident = Ident::new("________________out", Span::call_site());
+    let source_ident = Ident::new("________________source", Span::call_site());
+    let tag_ident = Ident::new("________________tag", Span::call_site());
+
+    let mut used_tags: BTreeMap<u8, Span> = BTreeMap::new();
+    let mut tags = Vec::new();
+    let mut flattened_types = Vec::new();
+    let mut serial_cases = proc_macro2::TokenStream::new();
+    let mut deserial_cases = proc_macro2::TokenStream::new();
+    for variant in data.variants.iter() {
+        let variant_ident = &variant.ident;
+        match event_variant_kind(variant)? {
+            EventVariantKind::Tagged(tag) => {
+                if let Some(used_span) = used_tags.insert(tag, variant.ident.span()) {
+                    let error_msg = format!("the tag `{}` is used by multiple events", tag);
+                    let mut error = syn::Error::new(used_span, &error_msg);
+                    error.combine(syn::Error::new(variant.ident.span(), &error_msg));
+                    return Err(error);
+                }
+                tags.push(tag);
+
+                let field_names: Vec<_> = match variant.fields {
+                    syn::Fields::Named(_) => {
+                        variant.fields.iter().map(|field| field.ident.clone().unwrap()).collect()
+                    }
+                    _ => (0..variant.fields.len()).map(|i| format_ident!("x_{}", i)).collect(),
+                };
+                let pattern = match variant.fields {
+                    syn::Fields::Named(_) => quote! { {#(#field_names),*} },
+                    syn::Fields::Unnamed(_) => quote! { ( #(#field_names),* ) },
+                    syn::Fields::Unit => proc_macro2::TokenStream::new(),
+                };
+                let mut field_serials = proc_macro2::TokenStream::new();
+                let mut field_deserials = proc_macro2::TokenStream::new();
+                for (name, field) in field_names.iter().zip(variant.fields.iter()) {
+                    let concordium_attributes = get_concordium_field_attributes(&field.attrs)?;
+                    let ensure_ordered =
+                        contains_attribute(&concordium_attributes, "ensure_ordered");
+                    let size_length = find_length_attribute(&field.attrs)?;
+                    let ty = &field.ty;
+                    if ensure_ordered || size_length.is_some() {
+                        // Default size length is u32, i.e. 4 bytes.
+                        let l = format_ident!("U{}", 8 * size_length.unwrap_or(4));
+                        field_serials.extend(quote! {
+                            concordium_std::SerialCtx::serial_ctx(#name, concordium_std::schema::SizeLength::#l, #out_ident)?;
+                        });
+                        field_deserials.extend(quote! {
+                            let #name = <#ty as concordium_std::DeserialCtx>::deserial_ctx(concordium_std::schema::SizeLength::#l, #ensure_ordered, #source_ident)?;
+                        });
+                    } else {
+                        field_serials.extend(quote! {
+                            concordium_std::Serial::serial(#name, #out_ident)?;
+                        });
+                        field_deserials.extend(quote! {
+                            let #name = <#ty as concordium_std::Deserial>::deserial(#source_ident)?;
+                        });
+                    }
+                }
+                serial_cases.extend(quote! {
+                    #data_name::#variant_ident #pattern => {
+                        concordium_std::Write::write_u8(#out_ident, #tag)?;
+                        #field_serials
+                        Ok(())
+                    }
+                });
+                deserial_cases.extend(quote! {
+                    #tag => {
+                        #field_deserials
+                        Ok(#data_name::#variant_ident #pattern)
+                    }
+                });
+            }
+            EventVariantKind::Flattened(ty) => {
+                serial_cases.extend(quote! {
+                    #data_name::#variant_ident(event) => concordium_std::Serial::serial(event, #out_ident),
+                });
+                deserial_cases.extend(quote! {
+                    #tag_ident if <#ty as concordium_std::TaggedEvent>::TAGS.contains(#tag_ident) => {
+                        <#ty as concordium_std::TaggedEvent>::deserial_tagged(#tag_ident, #source_ident).map(#data_name::#variant_ident)
+                    }
+                });
+                flattened_types.push(ty);
+            }
+        }
+    }
+
+    let schema_type = event_schema_type(&ast, data)?;
+
+    // The constant of a generic enum can only be evaluated for concrete type
+    // arguments, so for those it is evaluated when the events are serialized or
+    // parsed.
+    let assert_unique = if ast.generics.params.is_empty() {
+        quote! {
+            const _: () = #data_name::CONCORDIUM_EVENT_TAGS_ARE_UNIQUE;
+        }
+    } else {
+        proc_macro2::TokenStream::new()
+    };
+
+    let gen = quote! {
+        impl #impl_generics #data_name #ty_generics #where_clauses {
+            /// Fails to evaluate if the enum and the event types flattened into it
+            /// use the same tag. Indexing out of bounds is used to fail, since
+            /// panicking in constants is not supported by the minimum supported
+            /// Rust version.
+            #[doc(hidden)]
+            const CONCORDIUM_EVENT_TAGS_ARE_UNIQUE: () =
+                [()][<Self as concordium_std::TaggedEvent>::TAGS.has_duplicates() as usize];
+        }
+
+        #assert_unique
+
+        #[automatically_derived]
+        impl #impl_generics concordium_std::TaggedEvent for #data_name #ty_generics #where_clauses {
+            const TAGS: concordium_std::EventTags = concordium_std::EventTags {
+                tags:      &[#(#tags),*],
+                flattened: &[#(&<#flattened_types as concordium_std::TaggedEvent>::TAGS),*],
+            };
+
+            fn deserial_tagged<__R: concordium_std::Read>(#tag_ident: u8, #source_ident: &mut __R) -> concordium_std::ParseResult<Self> {
+                #[allow(clippy::let_unit_value)]
+                let () = Self::CONCORDIUM_EVENT_TAGS_ARE_UNIQUE;
+                match #tag_ident {
+                    #deserial_cases
+                    _ => Err(Default::default()),
+                }
+            }
+        }
+
+        #[automatically_derived]
+        impl #impl_generics concordium_std::Serial for #data_name #ty_generics #where_clauses {
+            fn serial<__W: concordium_std::Write>(&self, #out_ident: &mut __W) -> Result<(), __W::Err> {
+                #[allow(clippy::let_unit_value)]
+                let () = Self::CONCORDIUM_EVENT_TAGS_ARE_UNIQUE;
+                match self {
+                    #serial_cases
+                }
+            }
+        }
+
+        #[automatically_derived]
+        impl #impl_generics concordium_std::Deserial for #data_name #ty_generics #where_clauses {
+            fn deserial<__R: concordium_std::Read>(#source_ident: &mut __R) -> concordium_std::ParseResult<Self> {
+                let #tag_ident = concordium_std::Read::read_u8(#source_ident)?;
+                <Self as concordium_std::TaggedEvent>::deserial_tagged(#tag_ident, #source_ident)
+            }
+        }
+
+        #schema_type
+    };
+    Ok(gen.into())
+}
+
+/// Generate the `SchemaType` implementation for an enum deriving `Event`, which
+/// is a `TaggedEnum` with the variants of the enum and of the event types
+/// flattened into it.
+#[cfg(feature = "build-schema")]
+fn event_schema_type(
+    ast: &syn::DeriveInput,
+    data: &DataEnum,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let data_name = &ast.ident;
+    let (impl_generics, ty_generics, where_clauses) = ast.generics.split_for_impl();
+    let mut used_variant_names = HashMap::new();
+    let mut variant_tokens = Vec::new();
+    let mut flattened_types = Vec::new();
+    for variant in data.variants.iter() {
+        match event_variant_kind(variant)? {
+            EventVariantKind::Tagged(tag) => {
+                let (variant_name, variant_span) = match find_variant_rename_attribute(
+                    &variant.attrs,
+                    &VALID_CONCORDIUM_EVENT_VARIANT_ATTRIBUTES,
+                )? {
+                    Some(name_and_span) => name_and_span,
+                    None => (variant.ident.to_string(), variant.ident.span()),
+                };
+                check_for_name_collisions(&mut used_variant_names, &variant_name, variant_span)?;
+                let fields_tokens = schema_type_fields(&variant.fields)?;
+                variant_tokens.push(quote! {
+                    variants.insert(#tag, (concordium_std::String::from(#variant_name), #fields_tokens));
+                });
+            }
+            EventVariantKind::Flattened(ty) => flattened_types.push(ty),
+        }
+    }
+    let error_msg = format!("The events flattened into {} are incompatible: {{:?}}", data_name);
+    Ok(quote! {
+        #[automatically_derived]
+        impl #impl_generics concordium_std::schema::SchemaType for #data_name #ty_generics #where_clauses {
+            fn get_type() -> concordium_std::schema::Type {
+                let mut variants = concordium_std::collections::BTreeMap::new();
+                #(#variant_tokens)*
+                match concordium_std::merge_event_types(concordium_std::Vec::from([
+                    concordium_std::schema::Type::TaggedEnum(variants),
+                    #(<#flattened_types as concordium_std::schema::SchemaType>::get_type()),*
+                ])) {
+                    Ok(Some(event)) => event,
+                    Ok(None) => unreachable!(),
+                    Err(err) => panic!(#error_msg, err),
+                }
+            }
+        }
+    })
+}
+
+#[cfg(not(feature = "build-schema"))]
+fn event_schema_type(
+    _ast: &syn::DeriveInput,
+    _data: &DataEnum,
+) -> syn::Result<proc_macro2::TokenStream> {
+    Ok(proc_macro2::TokenStream::new())
+}
+
 /// We reserve a number of error codes for custom errors, such as ParseError,
 /// that are provided by concordium-std. These reserved error codes can have
 /// indices i32::MIN, i32::MIN + 1, ..., RESERVED_ERROR_CODES
@@ -1684,7 +3590,44 @@ const RESERVED_ERROR_CODES: i32 = i32::MIN + 100;
 /// is to be derived.
 ///
 /// The conversion will map the first variant to error code -1, second to -2,
-/// etc.
+/// etc. Since the error codes are observed on chain, they can be fixed so that
+/// reordering or inserting variants does not change them:
+/// - `#[concordium(error_code = -17)]` on a variant sets its error code.
+///   Variants without an explicit error code get the error code of the previous
+///   variant minus one.
+/// - `#[concordium(error_code_base = -100)]` on the enum sets the error code of
+///   the first variant, if it has no explicit error code. It defaults to -1.
+///
+/// The error codes must be unique and in the range from -1 to
+/// `i32::MIN + 101`, since the error codes below are reserved for the errors
+/// of concordium-std. The error codes are available in the generated constant
+/// `ERROR_CODES`, which maps each error code to the name of its variant.
+///
+/// Variants can be converted from other error types with the `from` attribute:
+/// - `#[from(ParseError, ...)]` converts the listed error types into the
+///   variant, which must have no fields.
+/// - `#[from]` on a variant with a single unnamed field converts the type of
+///   the field into the variant.
+///
+/// A variant with a single unnamed field can be marked as
+/// `#[concordium(transparent)]`, if the type of the field can be converted into
+/// [`Reject`] as well. It is then rejected with the error code of the wrapped
+/// error, e.g., a nested error enum keeps its own error codes, and the
+/// serialized enum as the return value, like the other variants. Transparent
+/// variants have no error code, and it is up to the user to ensure that the
+/// error codes of the wrapped error do not overlap with the other error codes.
+///
+/// With `#[concordium(with_messages)]` on the enum, every variant, except the
+/// transparent ones, must have a message given by
+/// `#[concordium(message = "...")]`. If the feature `error-messages` is
+/// enabled, the return value is an `ErrorEnvelope` with the error code, the
+/// name of the variant, the message and the serialized enum, which can be
+/// displayed without the schema of the contract. Otherwise, the messages are
+/// not included in the contract to keep it small, and the return value is only
+/// the serialized enum. Since the serialized envelope starts with the tag 255,
+/// such an enum can have at most 254 variants, and with the feature enabled,
+/// [`SchemaType`] includes the envelope in the schema of the enum, which thus
+/// decodes the return value in both cases.
 ///
 /// ### Example
 /// ```ignore
@@ -1701,7 +3644,29 @@ const RESERVED_ERROR_CODES: i32 = i32::MIN + 100;
 /// fn receive<A: HasActions>(ctx: &impl HasReceiveContext, state: &mut MyState)
 /// -> Result<A, MyError> {...}
 /// ```
-#[proc_macro_derive(Reject, attributes(from))]
+///
+/// ### Example with explicit error codes
+/// ```ignore
+/// #[derive(Reject, Serial)]
+/// #[concordium(error_code_base = -10)]
+/// enum MyError {
+///     #[from(ParseError)]
+///     ParseParams, // receives error code -10
+///     #[concordium(error_code = -20)]
+///     InsufficientFunds, // receives error code -20
+///     Unauthorized, // receives error code -21
+///     #[from]
+///     #[concordium(transparent)]
+///     Roles(RolesError), // keeps the error code of `RolesError`
+/// }
+///
+/// assert_eq!(MyError::ERROR_CODES, &[
+///     (-10, "ParseParams"),
+///     (-20, "InsufficientFunds"),
+///     (-21, "Unauthorized"),
+/// ]);
+/// ```
+#[proc_macro_derive(Reject, attributes(from, concordium))]
 pub fn reject_derive(input: TokenStream) -> TokenStream {
     unwrap_or_report(reject_derive_worker(input))
 }
@@ -1714,26 +3679,66 @@ fn reject_derive_worker(input: TokenStream) -> syn::Result<TokenStream> {
     }?;
     let enum_ident = &ast.ident;
 
-    // Ensure that the number of enum variants fits into the number of error codes
-    // we can generate.
-    let too_many_variants = format!(
-        "Error enum {} cannot have more than {} variants.",
+    let error_codes = reject_error_codes(&ast, enum_data)?;
+    let messages = reject_messages(&ast, enum_data, &error_codes)?;
+    let variant_error_conversions = generate_variant_error_conversions(enum_data, enum_ident)?;
+    let buf_var_ident = format_ident!("{}", "buf");
+    let variant_matches = generate_variant_matches(
+        enum_data,
+        &error_codes,
+        messages.as_deref(),
         enum_ident,
-        RESERVED_ERROR_CODES.neg()
+        &buf_var_ident,
     );
-    match i32::try_from(enum_data.variants.len()) {
-        Ok(n) if n <= RESERVED_ERROR_CODES.neg() => (),
-        _ => {
-            return Err(syn::Error::new(ast.span(), &too_many_variants));
+
+    let error_code_entries =
+        enum_data.variants.iter().zip(error_codes.iter()).filter_map(|(variant, code)| {
+            let variant_name = variant.ident.to_string();
+            code.map(|code| quote!((#code, #variant_name)))
+        });
+
+    let transparent_variants: Vec<_> = enum_data
+        .variants
+        .iter()
+        .zip(error_codes.iter())
+        .filter(|(_, code)| code.is_none())
+        .map(|(variant, _)| &variant.ident)
+        .collect();
+    let conversion = if transparent_variants.is_empty() {
+        quote! {
+            match &e {
+               #variant_matches
+            }
+        }
+    } else {
+        // The return value is the serialized enum, but the error code is the one of
+        // the wrapped error.
+        quote! {
+            match e {
+                #(#enum_ident::#transparent_variants(inner) => Reject {
+                    error_code: Reject::from(inner).error_code,
+                    return_value: Some(#buf_var_ident),
+                },)*
+                e => match &e {
+                   #variant_matches
+                   _ => concordium_std::trap(), // Transparent variants are handled above.
+                },
+            }
         }
     };
 
-    let variant_error_conversions = generate_variant_error_conversions(enum_data, enum_ident)?;
-    let buf_var_ident = format_ident!("{}", "buf");
-    let variant_matches = generate_variant_matches(enum_data, enum_ident, &buf_var_ident);
-
     let gen = quote! {
-        /// The from implementation maps the first variant to -1, second to -2, etc.
+        #[automatically_derived]
+        impl #enum_ident {
+            /// The error codes of the variants, which are used when converting
+            /// the error into a `Reject`, along with the names of the variants.
+            /// Transparent variants are not included, since they use the error
+            /// codes of the wrapped error.
+            pub const ERROR_CODES: &'static [(i32, &'static str)] = &[#(#error_code_entries),*];
+        }
+
+        /// The from implementation maps each variant to its error code, which
+        /// by default is -1 for the first variant, -2 for the second, etc.
         /// NB: This differs from the cast `variant as i32` since we cannot easily modify
         /// the variant tags in the derive macro itself.
         #[automatically_derived]
@@ -1742,9 +3747,7 @@ fn reject_derive_worker(input: TokenStream) -> syn::Result<TokenStream> {
             fn from(e: #enum_ident) -> Self {
                 let mut #buf_var_ident = Vec::new();
                 concordium_std::Serial::serial(&e, &mut #buf_var_ident).unwrap_abort();
-                match &e {
-                   #variant_matches
-                }
+                #conversion
             }
         }
 
@@ -1753,44 +3756,215 @@ fn reject_derive_worker(input: TokenStream) -> syn::Result<TokenStream> {
     Ok(gen.into())
 }
 
-/// Generate the cases for matching on the enum.
-/// The error codes for variants start at -1 and go downwards.
+/// Parse an error code given in the `error_code` or `error_code_base`
+/// attribute, and check that it is not a reserved error code.
+fn parse_error_code(value: &syn::Lit) -> syn::Result<i32> {
+    let code = match value {
+        syn::Lit::Int(int) => int.base10_parse::<i64>()?,
+        _ => return Err(syn::Error::new(value.span(), "Error codes must be integers.")),
+    };
+    check_error_code(code, value.span())
+}
+
+/// Check that an error code is negative and not reserved.
+fn check_error_code(code: i64, span: Span) -> syn::Result<i32> {
+    if code <= i64::from(RESERVED_ERROR_CODES) || code >= 0 {
+        return Err(syn::Error::new(
+            span,
+            format!(
+                "Error codes must be between -1 and {}, since the error codes from {} to {} are \
+                 reserved for concordium-std.",
+                RESERVED_ERROR_CODES + 1,
+                i32::MIN,
+                RESERVED_ERROR_CODES
+            ),
+        ));
+    }
+    Ok(code as i32)
+}
+
+/// Compute the error codes of the variants of an enum deriving Reject.
+/// Variants with an `error_code` attribute get that error code, and other
+/// variants get the error code of the previous variant minus one, starting
+/// from the `error_code_base` of the enum or -1. Transparent variants have no
+/// error code.
+fn reject_error_codes(
+    ast: &syn::DeriveInput,
+    enum_data: &DataEnum,
+) -> syn::Result<Vec<Option<i32>>> {
+    let mut next_code = match find_attribute_value(&ast.attrs, false, "error_code_base")? {
+        Some(base) => i64::from(parse_error_code(&base)?),
+        None => -1,
+    };
+    let mut used_codes: BTreeMap<i32, Span> = BTreeMap::new();
+    let mut codes = Vec::new();
+    for variant in enum_data.variants.iter() {
+        // in the future we might incorporate explicit discriminants,
+        // but the general case of this requires evaluating constant expressions,
+        // which is not easily supported at the moment.
+        if let Some((_, discriminant)) = variant.discriminant.as_ref() {
+            return Err(syn::Error::new(
+                discriminant.span(),
+                "Explicit discriminants are not yet supported. Use #[concordium(error_code = \
+                 ...)] to set the error code of a variant.",
+            ));
+        }
+        let explicit_code = find_variant_attribute_value(
+            &variant.attrs,
+            &VALID_CONCORDIUM_VARIANT_ATTRIBUTES,
+            "error_code",
+        )?;
+        let concordium_attributes = get_concordium_variant_attributes(
+            &variant.attrs,
+            &VALID_CONCORDIUM_VARIANT_ATTRIBUTES,
+        )?;
+        if contains_attribute(&concordium_attributes, "transparent") {
+            if let Some(code) = explicit_code {
+                return Err(syn::Error::new(
+                    code.span(),
+                    "A transparent variant uses the error codes of the wrapped error, and cannot \
+                     have an error code.",
+                ));
+            }
+            match &variant.fields {
+                syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => (),
+                _ => {
+                    return Err(syn::Error::new(
+                        variant.span(),
+                        "A transparent variant must have exactly one unnamed field, whose type \
+                         can be converted into `Reject`.",
+                    ))
+                }
+            }
+            codes.push(None);
+            continue;
+        }
+        let (code, span) = match explicit_code {
+            Some(code) => (parse_error_code(&code)?, code.span()),
+            None => (check_error_code(next_code, variant.span())?, variant.span()),
+        };
+        if let Some(used_span) = used_codes.insert(code, span) {
+            let error_msg = format!("the error code `{}` is used by multiple variants", code);
+            let mut error = syn::Error::new(used_span, &error_msg);
+            error.combine(syn::Error::new(span, &error_msg));
+            return Err(error);
+        }
+        next_code = i64::from(code) - 1;
+        codes.push(Some(code));
+    }
+    Ok(codes)
+}
+
+/// Get the messages of the variants of an enum deriving Reject with the
+/// `with_messages` attribute, or `None` if the enum does not have it.
+/// Every variant except the transparent ones must have a message.
+fn reject_messages(
+    ast: &syn::DeriveInput,
+    enum_data: &DataEnum,
+    error_codes: &[Option<i32>],
+) -> syn::Result<Option<Vec<String>>> {
+    let with_messages =
+        contains_attribute(&get_concordium_attributes(&ast.attrs, false)?, "with_messages");
+    // The tag 255 is used by the `ErrorEnvelope` in the schema of the enum.
+    if with_messages && enum_data.variants.len() >= 255 {
+        return Err(syn::Error::new(
+            ast.ident.span(),
+            "An enum with #[concordium(with_messages)] can have at most 254 variants, since the \
+             tag 255 is used by the error envelope.",
+        ));
+    }
+    let mut messages = Vec::new();
+    for (variant, code) in enum_data.variants.iter().zip(error_codes.iter()) {
+        let message = match find_variant_attribute_value(
+            &variant.attrs,
+            &VALID_CONCORDIUM_VARIANT_ATTRIBUTES,
+            "message",
+        )? {
+            Some(syn::Lit::Str(message)) => Some(message),
+            Some(other) => {
+                return Err(syn::Error::new(other.span(), "The message must be a string."))
+            }
+            None => None,
+        };
+        match (message, with_messages, code) {
+            (Some(message), false, _) => {
+                return Err(syn::Error::new(
+                    message.span(),
+                    "The `message` attribute requires #[concordium(with_messages)] on the enum.",
+                ))
+            }
+            (Some(message), true, None) => {
+                return Err(syn::Error::new(
+                    message.span(),
+                    "A transparent variant uses the message of the wrapped error, and cannot have \
+                     a message.",
+                ))
+            }
+            (Some(message), true, Some(_)) => messages.push(message.value()),
+            (None, true, Some(_)) => {
+                return Err(syn::Error::new(
+                    variant.span(),
+                    "Every variant must have a message, e.g., #[concordium(message = \"...\")], \
+                     when the enum has #[concordium(with_messages)].",
+                ))
+            }
+            (None, _, _) => messages.push(String::new()),
+        }
+    }
+    Ok(if with_messages {
+        Some(messages)
+    } else {
+        None
+    })
+}
+
+/// Generate the cases for matching on the enum, using the given error codes.
+/// Transparent variants, which have no error code, are skipped.
 /// The whole enum is serialized and included in the return_value field, which,
-/// thus, is always `Some`.
+/// thus, is always `Some`. If the feature `error-messages` is enabled and the
+/// variants have messages, the serialized enum is wrapped in an
+/// `ErrorEnvelope` along with the error code, the variant name and the
+/// message.
 fn generate_variant_matches(
     enum_data: &DataEnum,
+    error_codes: &[Option<i32>],
+    messages: Option<&[String]>,
     enum_name: &syn::Ident,
     buf_var_ident: &syn::Ident,
 ) -> proc_macro2::TokenStream {
     let mut match_cases = proc_macro2::TokenStream::new();
-    for (index, variant) in enum_data.variants.iter().enumerate() {
-        let variant_ident = &variant.ident;
-        match variant.fields {
-            syn::Fields::Named(_) => {
-                match_cases.extend(quote! {
-                    #enum_name::#variant_ident{..} => Reject {
-                        error_code: unsafe { num::NonZeroI32::new_unchecked(-(#index as i32) - 1) },
-                        return_value: Some(#buf_var_ident),
-                    },
-                });
-            }
-            syn::Fields::Unnamed(_) => {
-                match_cases.extend(quote! {
-                    #enum_name::#variant_ident(..) => Reject {
-                        error_code: unsafe { num::NonZeroI32::new_unchecked(-(#index as i32) - 1) },
-                        return_value: Some(#buf_var_ident),
-                    },
-                });
-            }
-            syn::Fields::Unit => {
-                match_cases.extend(quote! {
-                    #enum_name::#variant_ident => Reject {
-                        error_code: unsafe { num::NonZeroI32::new_unchecked(-(#index as i32) - 1) },
-                        return_value: Some(#buf_var_ident),
-                    },
-                });
+    for (i, (variant, code)) in enum_data.variants.iter().zip(error_codes.iter()).enumerate() {
+        let code = match code {
+            Some(code) => code,
+            None => continue,
+        };
+        let return_value = match messages {
+            Some(messages) if cfg!(feature = "error-messages") => {
+                let variant_name = variant.ident.to_string();
+                let message = &messages[i];
+                quote! {
+                    concordium_std::to_bytes(&concordium_std::ErrorEnvelope {
+                        error_code: #code,
+                        variant:    concordium_std::String::from(#variant_name),
+                        message:    concordium_std::String::from(#message),
+                        error:      #buf_var_ident,
+                    })
+                }
             }
+            _ => quote!(#buf_var_ident),
+        };
+        let variant_ident = &variant.ident;
+        let pattern = match variant.fields {
+            syn::Fields::Named(_) => quote!({ .. }),
+            syn::Fields::Unnamed(_) => quote!((..)),
+            syn::Fields::Unit => proc_macro2::TokenStream::new(),
         };
+        match_cases.extend(quote! {
+            #enum_name::#variant_ident #pattern => Reject {
+                error_code: unsafe { num::NonZeroI32::new_unchecked(#code) },
+                return_value: Some(#return_value),
+            },
+        });
     }
     match_cases
 }
@@ -1813,20 +3987,9 @@ fn generate_variant_error_conversions(
         .variants
         .iter()
         .map(|variant| {
-            // in the future we might incorporate explicit discriminants,
-            // but the general case of this requires evaluating constant expressions,
-            // which is not easily supported at the moment.
-            if let Some((_, discriminant)) = variant.discriminant.as_ref() {
-                return Err(syn::Error::new(
-                    discriminant.span(),
-                    "Explicit discriminants are not yet supported.",
-                ));
-            }
             let variant_attributes = variant.attrs.iter();
             variant_attributes
-                .map(move |attr| {
-                    parse_attr_and_gen_error_conversions(attr, enum_name, &variant.ident)
-                })
+                .map(move |attr| parse_attr_and_gen_error_conversions(attr, enum_name, variant))
                 .collect::<syn::Result<Vec<_>>>()
         })
         .collect::<syn::Result<Vec<_>>>()?
@@ -1840,7 +4003,7 @@ fn generate_variant_error_conversions(
 fn parse_attr_and_gen_error_conversions(
     attr: &syn::Attribute,
     enum_name: &syn::Ident,
-    variant_name: &syn::Ident,
+    variant: &syn::Variant,
 ) -> syn::Result<Vec<proc_macro2::TokenStream>> {
     let wrong_from_usage = |x: &dyn Spanned| {
         syn::Error::new(
@@ -1848,6 +4011,7 @@ fn parse_attr_and_gen_error_conversions(
             "The `from` attribute expects a list of error types, e.g.: #[from(ParseError)].",
         )
     };
+    let variant_name = &variant.ident;
     match attr.parse_meta() {
         Ok(syn::Meta::List(list)) if list.path.is_ident("from") => {
             let mut from_error_names = vec![];
@@ -1865,6 +4029,23 @@ fn parse_attr_and_gen_error_conversions(
             }
             Ok(from_error_token_stream(&from_error_names, enum_name, variant_name).collect())
         }
+        Ok(syn::Meta::Path(path)) if path.is_ident("from") => match &variant.fields {
+            syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                let field_type = &fields.unnamed[0].ty;
+                Ok(vec![quote! {
+                impl From<#field_type> for #enum_name {
+                   #[inline]
+                   fn from(fe: #field_type) -> Self {
+                     #enum_name::#variant_name(fe)
+                   }
+                }}])
+            }
+            _ => Err(syn::Error::new(
+                path.span(),
+                "The `from` attribute without a list of error types can only be used on variants \
+                 with exactly one unnamed field.",
+            )),
+        },
         Ok(syn::Meta::NameValue(mnv)) if mnv.path.is_ident("from") => Err(wrong_from_usage(&mnv)),
         _ => Ok(vec![]),
     }
@@ -1,5 +1,6 @@
 use crate::{
     cell::UnsafeCell,
+    collections::{btree_map, BTreeMap},
     convert::{self, TryInto},
     fmt,
     marker::PhantomData,
@@ -92,6 +93,49 @@ impl From<NotPayableError> for Reject {
     }
 }
 
+/// WrongSenderKind is i32::MIN + 22,
+/// PolicyNotSatisfied is i32::MIN + 23.
+impl From<AccessDeniedError> for Reject {
+    #[inline(always)]
+    fn from(ade: AccessDeniedError) -> Self {
+        match ade {
+            AccessDeniedError::WrongSenderKind => unsafe {
+                crate::num::NonZeroI32::new_unchecked(i32::MIN + 22).into()
+            },
+            AccessDeniedError::PolicyNotSatisfied => unsafe {
+                crate::num::NonZeroI32::new_unchecked(i32::MIN + 23).into()
+            },
+        }
+    }
+}
+
+/// Unauthorized is i32::MIN + 24,
+/// Log errors are mapped as [`LogError`].
+impl From<RolesError> for Reject {
+    #[inline(always)]
+    fn from(re: RolesError) -> Self {
+        match re {
+            RolesError::Unauthorized => unsafe {
+                crate::num::NonZeroI32::new_unchecked(i32::MIN + 24).into()
+            },
+            RolesError::Log(le) => le.into(),
+        }
+    }
+}
+
+/// The error code is i32::MIN + 25.
+impl From<ReentrancyError> for Reject {
+    #[inline(always)]
+    fn from(_: ReentrancyError) -> Self {
+        unsafe { crate::num::NonZeroI32::new_unchecked(i32::MIN + 25) }.into()
+    }
+}
+
+impl From<LogError> for RolesError {
+    #[inline(always)]
+    fn from(le: LogError) -> Self { RolesError::Log(le) }
+}
+
 /// AmountTooLarge is i32::MIN + 13,
 /// MissingAccount is i32::MIN + 14.
 impl From<TransferError> for Reject {
@@ -114,7 +158,8 @@ impl From<TransferError> for Reject {
 /// MissingEntrypoint is i32::MIN + 18,
 /// MessageFailed is i32::MIN + 19,
 /// LogicReject is i32::MIN + 20,
-/// Trap is i32::MIN + 21.
+/// Trap is i32::MIN + 21,
+/// InvalidReturnValue is i32::MIN + 26.
 impl<T> From<CallContractError<T>> for Reject {
     #[inline(always)]
     fn from(cce: CallContractError<T>) -> Self {
@@ -140,6 +185,9 @@ impl<T> From<CallContractError<T>> for Reject {
             CallContractError::Trap => unsafe {
                 crate::num::NonZeroI32::new_unchecked(i32::MIN + 21).into()
             },
+            CallContractError::InvalidReturnValue {
+                ..
+            } => unsafe { crate::num::NonZeroI32::new_unchecked(i32::MIN + 26).into() },
         }
     }
 }
@@ -1194,6 +1242,13 @@ impl<K, V, S> Serial for StateMap<K, V, S> {
     fn serial<W: Write>(&self, out: &mut W) -> Result<(), W::Err> { out.write_all(&self.prefix) }
 }
 
+impl<S> Serial for Roles<S> {
+    fn serial<W: Write>(&self, out: &mut W) -> Result<(), W::Err> {
+        self.members.serial(out)?;
+        self.admins.serial(out)
+    }
+}
+
 impl<T, S> StateSet<T, S>
 where
     T: Serialize,
@@ -1680,6 +1735,144 @@ impl ExactSizeIterator for PolicyAttributesIter {
     fn len(&self) -> usize { self.cursor.remaining_items as usize }
 }
 
+/// Number of milliseconds in a day.
+const MILLIS_PER_DAY: u64 = 24 * 60 * 60 * 1000;
+
+impl Date {
+    /// Construct a new date. Returns `None` if the year has more than 4
+    /// digits, or if the month or the day is out of range.
+    pub fn new(year: u16, month: u8, day: u8) -> Option<Self> {
+        if year > 9999 || month == 0 || month > 12 || day == 0 || day > days_in_month(year, month) {
+            return None;
+        }
+        Some(Self {
+            year,
+            month,
+            day,
+        })
+    }
+
+    /// The year of the date.
+    #[inline(always)]
+    pub fn year(&self) -> u16 { self.year }
+
+    /// The month of the date, from 1 to 12.
+    #[inline(always)]
+    pub fn month(&self) -> u8 { self.month }
+
+    /// The day of the month, starting from 1.
+    #[inline(always)]
+    pub fn day(&self) -> u8 { self.day }
+
+    /// The date (in UTC) that the timestamp falls on. Returns `None` if the
+    /// year of the timestamp has more than 4 digits.
+    pub fn from_timestamp(timestamp: Timestamp) -> Option<Self> {
+        // Convert days since the unix epoch to a civil date, see
+        // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+        let z = timestamp.timestamp_millis() / MILLIS_PER_DAY + 719468;
+        let era = z / 146097;
+        let day_of_era = z - era * 146097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 {
+            mp + 3
+        } else {
+            mp - 9
+        };
+        let year = year_of_era + era * 400 + u64::from(month <= 2);
+        if year > 9999 {
+            return None;
+        }
+        Some(Self {
+            year:  year as u16,
+            month: month as u8,
+            day:   day as u8,
+        })
+    }
+
+    /// The timestamp of the start of the date in UTC. Returns `None` if the
+    /// date is before the unix epoch.
+    pub fn to_timestamp(&self) -> Option<Timestamp> {
+        if self.year < 1970 {
+            return None;
+        }
+        // Convert a civil date to days since the unix epoch, see
+        // http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+        let year = u64::from(self.year) - u64::from(self.month <= 2);
+        let era = year / 400;
+        let year_of_era = year - era * 400;
+        let month = u64::from(self.month);
+        let mp = if month > 2 {
+            month - 3
+        } else {
+            month + 9
+        };
+        let day_of_year = (153 * mp + 2) / 5 + u64::from(self.day) - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        let days = era * 146097 + day_of_era - 719468;
+        Some(Timestamp::from_timestamp_millis(days * MILLIS_PER_DAY))
+    }
+
+    /// Parse a date from its representation as an attribute value, i.e., the
+    /// ASCII digits `YYYYMMDD`. Returns `None` if the bytes are not a valid
+    /// date in this format.
+    pub fn from_attribute_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != 8 {
+            return None;
+        }
+        let mut digits: u32 = 0;
+        for byte in bytes {
+            if !byte.is_ascii_digit() {
+                return None;
+            }
+            digits = digits * 10 + u32::from(byte - b'0');
+        }
+        Self::new((digits / 10000) as u16, (digits / 100 % 100) as u8, (digits % 100) as u8)
+    }
+
+    /// The number of whole years from this date until the other date, e.g.,
+    /// the age at `other` of a person born on this date. Returns `None` if
+    /// `other` is before this date.
+    pub fn years_until(&self, other: Date) -> Option<u16> {
+        let years = other.year.checked_sub(self.year)?;
+        if (other.month, other.day) < (self.month, self.day) {
+            years.checked_sub(1)
+        } else {
+            Some(years)
+        }
+    }
+
+    /// The representation of the date as an attribute value, i.e., the ASCII
+    /// digits `YYYYMMDD`.
+    pub fn to_attribute_bytes(&self) -> [u8; 8] {
+        let mut out = [b'0'; 8];
+        let mut rest =
+            u32::from(self.year) * 10000 + u32::from(self.month) * 100 + u32::from(self.day);
+        for byte in out.iter_mut().rev() {
+            *byte = b'0' + (rest % 10) as u8;
+            rest /= 10;
+        }
+        out
+    }
+}
+
+/// Whether the year is a leap year in the Gregorian calendar.
+fn is_leap_year(year: u16) -> bool { year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) }
+
+/// The number of days in the month of the given year. The month must be
+/// between 1 and 12.
+fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
 /// An iterator over policies using host functions to supply the data.
 /// The main interface to using this type is via the methods of the [Iterator](https://doc.rust-lang.org/std/iter/trait.Iterator.html)
 /// and [ExactSizeIterator](https://doc.rust-lang.org/std/iter/trait.ExactSizeIterator.html) traits.
@@ -2030,6 +2223,23 @@ where
     }
 }
 
+impl<'a, State, H: HasViewHost<State>> HasViewHost<State> for ViewHost<'a, H> {
+    #[inline(always)]
+    fn state(&self) -> &State { self.host.state() }
+
+    #[inline(always)]
+    fn self_balance(&self) -> Amount { self.host.self_balance() }
+}
+
+impl<S> HasViewHost<S> for ExternHost<S> {
+    fn state(&self) -> &S { &self.state }
+
+    #[inline(always)]
+    fn self_balance(&self) -> Amount {
+        Amount::from_micro_ccd(unsafe { prims::get_receive_self_balance() })
+    }
+}
+
 impl<S> HasHost<S> for ExternHost<S>
 where
     S: Serial + DeserialWithState<ExternStateApi>,
@@ -2089,8 +2299,6 @@ where
         }
     }
 
-    fn state(&self) -> &S { &self.state }
-
     fn state_mut(&mut self) -> &mut S { &mut self.state }
 
     fn commit_state(&mut self) {
@@ -2100,11 +2308,6 @@ where
         root_entry.truncate(new_state_size).unwrap_abort();
     }
 
-    #[inline(always)]
-    fn self_balance(&self) -> Amount {
-        Amount::from_micro_ccd(unsafe { prims::get_receive_self_balance() })
-    }
-
     #[inline(always)]
     fn state_builder(&mut self) -> &mut StateBuilder<Self::StateApiType> { &mut self.state_builder }
 
@@ -2114,6 +2317,16 @@ where
     }
 }
 
+impl HasViewHost<ExternStateApi> for ExternLowLevelHost {
+    #[inline(always)]
+    fn state(&self) -> &ExternStateApi { &self.state_api }
+
+    #[inline(always)]
+    fn self_balance(&self) -> Amount {
+        Amount::from_micro_ccd(unsafe { prims::get_receive_self_balance() })
+    }
+}
+
 impl HasHost<ExternStateApi> for ExternLowLevelHost {
     type ReturnValueType = ExternCallResponse;
     type StateApiType = ExternStateApi;
@@ -2136,9 +2349,6 @@ impl HasHost<ExternStateApi> for ExternLowLevelHost {
         parse_call_response_code(response)
     }
 
-    #[inline(always)]
-    fn state(&self) -> &ExternStateApi { &self.state_api }
-
     #[inline(always)]
     fn state_mut(&mut self) -> &mut ExternStateApi { &mut self.state_api }
 
@@ -2147,11 +2357,6 @@ impl HasHost<ExternStateApi> for ExternLowLevelHost {
         // do nothing since the low level host does not maintain any state
     }
 
-    #[inline(always)]
-    fn self_balance(&self) -> Amount {
-        Amount::from_micro_ccd(unsafe { prims::get_receive_self_balance() })
-    }
-
     #[inline(always)]
     fn state_builder(&mut self) -> &mut StateBuilder<Self::StateApiType> { &mut self.state_builder }
 
@@ -2373,6 +2578,190 @@ pub fn put_in_memory(input: &[u8]) -> *mut u8 {
     ptr
 }
 
+/// Parse the result of [invoking](HasHost::invoke_contract) a contract
+/// entrypoint, using the return value type `R` and the error type `E` declared
+/// by the entrypoint. The return value of a successful call is parsed as `R`,
+/// and the return value of a [`LogicReject`](CallContractError::LogicReject)
+/// is parsed as `E`. If the invoked contract did not produce a return value,
+/// i.e., if it is a V0 contract, then `R` is parsed from an empty input.
+///
+/// This is used by [`ContractInterface`].
+///
+/// If the return value cannot be parsed, i.e., if the invoked contract does not
+/// adhere to the declared types, the result is
+/// [`InvalidReturnValue`](CallContractError::InvalidReturnValue) with the
+/// reason of the rejection, if any.
+pub fn parse_call_response<A: HasCallResponse, R: Deserial, E: Deserial>(
+    result: CallContractResult<A>,
+) -> Result<R, CallContractError<E>> {
+    match result {
+        Ok((_, return_value)) => parse_return_value(return_value),
+        Err(err) => Err(parse_call_error(err)),
+    }
+}
+
+/// Like [`parse_call_response`], but for the result of
+/// [invoking](HasHost::invoke_contract_read_only) a contract entrypoint in
+/// read-only mode.
+pub fn parse_read_only_call_response<A: HasCallResponse, R: Deserial, E: Deserial>(
+    result: ReadOnlyCallContractResult<A>,
+) -> Result<R, CallContractError<E>> {
+    match result {
+        Ok(return_value) => parse_return_value(return_value),
+        Err(err) => Err(parse_call_error(err)),
+    }
+}
+
+/// Merge the event types of several entrypoints of a contract into the event
+/// type of the contract, which is used in the schema of the contract. Returns
+/// `None` if there are no event types.
+///
+/// Event types that are equal are merged into one. Otherwise, all event types
+/// must be enums with at most 256 variants, i.e., either
+/// [`Enum`](schema::Type::Enum), where the tag of a variant is its index, or
+/// [`TaggedEnum`](schema::Type::TaggedEnum), and the result is a `TaggedEnum`
+/// with the variants of all of them. Two event types may only use the same tag
+/// for the same variant, since events cannot be told apart otherwise.
+///
+/// This is used when building the schema of contracts declared with
+/// [`#[contract]`](attr.contract.html).
+pub fn merge_event_types(
+    types: impl IntoIterator<Item = schema::Type>,
+) -> Result<Option<schema::Type>, MergeEventTypesError> {
+    let mut types = types.into_iter();
+    let mut merged = match types.next() {
+        Some(ty) => ty,
+        None => return Ok(None),
+    };
+    for ty in types {
+        if ty == merged {
+            continue;
+        }
+        let mut variants = event_type_variants(&merged).ok_or(MergeEventTypesError::NotTagged)?;
+        for (tag, variant) in event_type_variants(&ty).ok_or(MergeEventTypesError::NotTagged)? {
+            match variants.entry(tag) {
+                btree_map::Entry::Vacant(vacant) => {
+                    vacant.insert(variant);
+                }
+                btree_map::Entry::Occupied(occupied) => {
+                    if *occupied.get() != variant {
+                        return Err(MergeEventTypesError::ConflictingTag(tag));
+                    }
+                }
+            }
+        }
+        merged = schema::Type::TaggedEnum(variants);
+    }
+    Ok(Some(merged))
+}
+
+/// The variants of an event type by their tag, if the events are tagged.
+fn event_type_variants(ty: &schema::Type) -> Option<BTreeMap<u8, (String, schema::Fields)>> {
+    match ty {
+        schema::Type::TaggedEnum(variants) => Some(variants.clone()),
+        schema::Type::Enum(variants) if variants.len() <= 256 => {
+            Some(variants.iter().cloned().enumerate().map(|(tag, v)| (tag as u8, v)).collect())
+        }
+        _ => None,
+    }
+}
+
+impl EventTags {
+    /// Whether the tag is used by the event type or by one of the event types
+    /// flattened into it.
+    pub const fn contains(&self, tag: u8) -> bool {
+        let mut i = 0;
+        while i < self.tags.len() {
+            if self.tags[i] == tag {
+                return true;
+            }
+            i += 1;
+        }
+        let mut j = 0;
+        while j < self.flattened.len() {
+            if self.flattened[j].contains(tag) {
+                return true;
+            }
+            j += 1;
+        }
+        false
+    }
+
+    /// Whether some tag is used more than once, either by the event type itself
+    /// or by the event types flattened into it. This is used by
+    /// [`derive(Event)`](derive.Event.html) to reject overlapping tags at
+    /// compile time.
+    pub const fn has_duplicates(&self) -> bool { self.mark_tags([false; 256]).is_none() }
+
+    /// Mark the tags of the event type as seen, returning `None` if a tag was
+    /// already seen.
+    const fn mark_tags(&self, mut seen: [bool; 256]) -> Option<[bool; 256]> {
+        let mut i = 0;
+        while i < self.tags.len() {
+            let tag = self.tags[i] as usize;
+            if seen[tag] {
+                return None;
+            }
+            seen[tag] = true;
+            i += 1;
+        }
+        let mut j = 0;
+        while j < self.flattened.len() {
+            seen = match self.flattened[j].mark_tags(seen) {
+                Some(seen) => seen,
+                None => return None,
+            };
+            j += 1;
+        }
+        Some(seen)
+    }
+}
+
+/// Parse the return value of a successful contract invocation, or an empty
+/// input if there is none.
+fn parse_return_value<A: HasCallResponse, R: Deserial, E>(
+    return_value: Option<A>,
+) -> Result<R, CallContractError<E>> {
+    let parsed = match return_value {
+        Some(mut return_value) => R::deserial(&mut return_value),
+        None => R::deserial(&mut Cursor::new(&[][..])),
+    };
+    parsed.map_err(|_| CallContractError::InvalidReturnValue {
+        reason: None,
+    })
+}
+
+/// Parse the return value of a rejected contract invocation.
+fn parse_call_error<A: HasCallResponse, E: Deserial>(
+    err: CallContractError<A>,
+) -> CallContractError<E> {
+    match err {
+        CallContractError::AmountTooLarge => CallContractError::AmountTooLarge,
+        CallContractError::MissingAccount => CallContractError::MissingAccount,
+        CallContractError::MissingContract => CallContractError::MissingContract,
+        CallContractError::MissingEntrypoint => CallContractError::MissingEntrypoint,
+        CallContractError::MessageFailed => CallContractError::MessageFailed,
+        CallContractError::LogicReject {
+            reason,
+            mut return_value,
+        } => match E::deserial(&mut return_value) {
+            Ok(return_value) => CallContractError::LogicReject {
+                reason,
+                return_value,
+            },
+            Err(_) => CallContractError::InvalidReturnValue {
+                reason: Some(reason),
+            },
+        },
+        CallContractError::Trap => CallContractError::Trap,
+        CallContractError::InvalidReturnValue {
+            reason,
+        } => CallContractError::InvalidReturnValue {
+            reason,
+        },
+    }
+}
+
 impl<A, E> UnwrapAbort for Result<A, E> {
     type Unwrap = A;
 
@@ -2491,6 +2880,15 @@ where
     }
 }
 
+impl<S: HasStateApi> DeserialWithState<S> for Roles<S> {
+    fn deserial_with_state<R: Read>(state: &S, source: &mut R) -> ParseResult<Self> {
+        Ok(Roles {
+            members: StateMap::deserial_with_state(state, source)?,
+            admins:  StateMap::deserial_with_state(state, source)?,
+        })
+    }
+}
+
 impl<T: Serialize> Deletable for T {
     #[inline(always)]
     fn delete(self) {} // Types that are Serialize have nothing to delete!
@@ -2556,6 +2954,439 @@ where
     fn delete(mut self) { self.clear(); }
 }
 
+impl<S: HasStateApi> Deletable for Roles<S> {
+    fn delete(self) {
+        self.members.delete();
+        self.admins.delete();
+    }
+}
+
+impl SenderKind {
+    /// Whether the address is of this kind.
+    pub fn matches(self, address: &Address) -> bool {
+        matches!(
+            (self, address),
+            (SenderKind::Account, Address::Account(_))
+                | (SenderKind::Contract, Address::Contract(_))
+        )
+    }
+}
+
+impl Role {
+    /// The default admin role of every role, including itself.
+    pub const ADMIN: Role = Role(0);
+}
+
+/// The key of the entry in which [`ReentrancyGuard`] records that an
+/// invocation is in progress. The state builder only allocates items at keys
+/// of at least 8 bytes, and the contract state is stored at the empty key, so
+/// the entry is never used otherwise.
+const REENTRANCY_GUARD_KEY: [u8; 4] = [255, b'R', b'E', b'N'];
+
+impl<S: HasStateApi> ReentrancyGuard<S> {
+    /// Construct a guard for the state of the contract instance that the state
+    /// builder belongs to, e.g., `ReentrancyGuard::new(host.state_builder())`.
+    pub fn new(state_builder: &StateBuilder<S>) -> Self {
+        Self {
+            state_api: state_builder.state_api.clone(),
+        }
+    }
+
+    /// Whether an invocation protected by the guard is in progress.
+    pub fn is_entered(&self) -> bool {
+        self.state_api.lookup_entry(&REENTRANCY_GUARD_KEY).is_some()
+    }
+
+    /// Record that a protected invocation is in progress, or return
+    /// [`ReentrancyError`] if one already is.
+    pub fn enter(&mut self) -> Result<(), ReentrancyError> {
+        if self.is_entered() {
+            return Err(ReentrancyError);
+        }
+        self.state_api.create_entry(&REENTRANCY_GUARD_KEY).unwrap_abort();
+        Ok(())
+    }
+
+    /// Record that the protected invocation has finished, so that the contract
+    /// can be invoked again.
+    pub fn exit(&mut self) {
+        if let Some(entry) = self.state_api.lookup_entry(&REENTRANCY_GUARD_KEY) {
+            self.state_api.delete_entry(entry).unwrap_abort();
+        }
+    }
+}
+
+impl<S: HasStateApi> Roles<S> {
+    /// Create new roles without any members. Use
+    /// [`grant_unchecked`](Self::grant_unchecked) to grant the initial roles,
+    /// typically [`Role::ADMIN`], in the init function of the contract.
+    pub fn new(state_builder: &mut StateBuilder<S>) -> Self {
+        Roles {
+            members: state_builder.new_map(),
+            admins:  state_builder.new_map(),
+        }
+    }
+
+    /// Return whether the address has the role.
+    pub fn has_role(&self, role: Role, address: &Address) -> bool {
+        self.members.get(&role).map_or(false, |members| members.contains(address))
+    }
+
+    /// Return `Ok` if the address has the role, and
+    /// [`RolesError::Unauthorized`] otherwise.
+    pub fn ensure_role(&self, role: Role, address: &Address) -> Result<(), RolesError> {
+        if self.has_role(role, address) {
+            Ok(())
+        } else {
+            Err(RolesError::Unauthorized)
+        }
+    }
+
+    /// Get the admin role of the role, i.e., the role whose members are
+    /// allowed to grant and revoke it.
+    pub fn admin_role(&self, role: Role) -> Role {
+        self.admins.get(&role).map_or(Role::ADMIN, |admin_role| *admin_role)
+    }
+
+    /// Grant the role to the address on behalf of the `sender`, which must
+    /// have the admin role of the role. If the address did not already have
+    /// the role, a [`RoleEvent::RoleGranted`] is logged.
+    pub fn grant(
+        &mut self,
+        sender: &Address,
+        role: Role,
+        address: Address,
+        state_builder: &mut StateBuilder<S>,
+        logger: &mut impl HasLogger,
+    ) -> Result<(), RolesError> {
+        self.ensure_role(self.admin_role(role), sender)?;
+        if self.grant_unchecked(role, address, state_builder) {
+            logger.log(&RoleEvent::RoleGranted {
+                role,
+                address,
+                sender: *sender,
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Revoke the role from the address on behalf of the `sender`, which must
+    /// have the admin role of the role. If the address had the role, a
+    /// [`RoleEvent::RoleRevoked`] is logged.
+    pub fn revoke(
+        &mut self,
+        sender: &Address,
+        role: Role,
+        address: &Address,
+        logger: &mut impl HasLogger,
+    ) -> Result<(), RolesError> {
+        self.ensure_role(self.admin_role(role), sender)?;
+        if self.revoke_unchecked(role, address) {
+            logger.log(&RoleEvent::RoleRevoked {
+                role,
+                address: *address,
+                sender: *sender,
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Revoke the role from the `sender` itself. If the sender had the role, a
+    /// [`RoleEvent::RoleRevoked`] is logged.
+    pub fn renounce(
+        &mut self,
+        sender: &Address,
+        role: Role,
+        logger: &mut impl HasLogger,
+    ) -> Result<(), RolesError> {
+        if self.revoke_unchecked(role, sender) {
+            logger.log(&RoleEvent::RoleRevoked {
+                role,
+                address: *sender,
+                sender: *sender,
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Change the admin role of the role on behalf of the `sender`, which must
+    /// have the current admin role of the role. A
+    /// [`RoleEvent::RoleAdminChanged`] is logged.
+    pub fn set_admin_role(
+        &mut self,
+        sender: &Address,
+        role: Role,
+        admin_role: Role,
+        logger: &mut impl HasLogger,
+    ) -> Result<(), RolesError> {
+        let previous_admin_role = self.admin_role(role);
+        self.ensure_role(previous_admin_role, sender)?;
+        self.set_admin_role_unchecked(role, admin_role);
+        logger.log(&RoleEvent::RoleAdminChanged {
+            role,
+            previous_admin_role,
+            new_admin_role: admin_role,
+        })?;
+        Ok(())
+    }
+
+    /// Grant the role to the address without checking any permissions and
+    /// without logging an event. This is meant for setting up the initial
+    /// roles in the init function. Returns whether the address did not already
+    /// have the role.
+    pub fn grant_unchecked(
+        &mut self,
+        role: Role,
+        address: Address,
+        state_builder: &mut StateBuilder<S>,
+    ) -> bool {
+        self.members
+            .entry(role)
+            .or_insert_with(|| state_builder.new_set())
+            .modify(|members| members.insert(address))
+    }
+
+    /// Revoke the role from the address without checking any permissions and
+    /// without logging an event. Returns whether the address had the role.
+    pub fn revoke_unchecked(&mut self, role: Role, address: &Address) -> bool {
+        match self.members.get_mut(&role) {
+            Some(mut members) => members.remove(address),
+            None => false,
+        }
+    }
+
+    /// Change the admin role of the role without checking any permissions and
+    /// without logging an event.
+    pub fn set_admin_role_unchecked(&mut self, role: Role, admin_role: Role) {
+        let _ = self.admins.insert(role, admin_role);
+    }
+}
+
+/// Serialized as a single byte, `0` for an account and `1` for a contract.
+impl Serial for SenderKind {
+    fn serial<W: Write>(&self, out: &mut W) -> Result<(), W::Err> {
+        match self {
+            SenderKind::Account => 0u8.serial(out),
+            SenderKind::Contract => 1u8.serial(out),
+        }
+    }
+}
+
+impl Deserial for SenderKind {
+    fn deserial<R: Read>(source: &mut R) -> ParseResult<Self> {
+        match u8::deserial(source)? {
+            0 => Ok(SenderKind::Account),
+            1 => Ok(SenderKind::Contract),
+            _ => Err(ParseError::default()),
+        }
+    }
+}
+
+impl Serial for EntrypointAccess {
+    fn serial<W: Write>(&self, out: &mut W) -> Result<(), W::Err> {
+        self.sender.serial(out)?;
+        self.policy.serial(out)
+    }
+}
+
+impl Deserial for EntrypointAccess {
+    fn deserial<R: Read>(source: &mut R) -> ParseResult<Self> {
+        Ok(EntrypointAccess {
+            sender: source.get()?,
+            policy: source.get()?,
+        })
+    }
+}
+
+impl Serial for ContractMetadata {
+    fn serial<W: Write>(&self, out: &mut W) -> Result<(), W::Err> {
+        self.version.serial(out)?;
+        self.standards.serial(out)?;
+        self.repository.serial(out)?;
+        self.doc.serial(out)
+    }
+}
+
+impl Deserial for ContractMetadata {
+    fn deserial<R: Read>(source: &mut R) -> ParseResult<Self> {
+        Ok(ContractMetadata {
+            version:    source.get()?,
+            standards:  source.get()?,
+            repository: source.get()?,
+            doc:        source.get()?,
+        })
+    }
+}
+
+impl Serial for EntrypointMetadata {
+    fn serial<W: Write>(&self, out: &mut W) -> Result<(), W::Err> {
+        self.doc.serial(out)?;
+        self.view.serial(out)
+    }
+}
+
+impl Deserial for EntrypointMetadata {
+    fn deserial<R: Read>(source: &mut R) -> ParseResult<Self> {
+        Ok(EntrypointMetadata {
+            doc:  source.get()?,
+            view: source.get()?,
+        })
+    }
+}
+
+impl Serial for Role {
+    fn serial<W: Write>(&self, out: &mut W) -> Result<(), W::Err> { self.0.serial(out) }
+}
+
+impl Deserial for Role {
+    fn deserial<R: Read>(source: &mut R) -> ParseResult<Self> { Ok(Role(source.get()?)) }
+}
+
+impl schema::SchemaType for Role {
+    fn get_type() -> schema::Type { schema::Type::U8 }
+}
+
+impl Serial for RoleEvent {
+    fn serial<W: Write>(&self, out: &mut W) -> Result<(), W::Err> {
+        match self {
+            RoleEvent::RoleGranted {
+                role,
+                address,
+                sender,
+            } => {
+                0u8.serial(out)?;
+                role.serial(out)?;
+                address.serial(out)?;
+                sender.serial(out)
+            }
+            RoleEvent::RoleRevoked {
+                role,
+                address,
+                sender,
+            } => {
+                1u8.serial(out)?;
+                role.serial(out)?;
+                address.serial(out)?;
+                sender.serial(out)
+            }
+            RoleEvent::RoleAdminChanged {
+                role,
+                previous_admin_role,
+                new_admin_role,
+            } => {
+                2u8.serial(out)?;
+                role.serial(out)?;
+                previous_admin_role.serial(out)?;
+                new_admin_role.serial(out)
+            }
+        }
+    }
+}
+
+impl Deserial for RoleEvent {
+    fn deserial<R: Read>(source: &mut R) -> ParseResult<Self> {
+        match u8::deserial(source)? {
+            0 => Ok(RoleEvent::RoleGranted {
+                role:    source.get()?,
+                address: source.get()?,
+                sender:  source.get()?,
+            }),
+            1 => Ok(RoleEvent::RoleRevoked {
+                role:    source.get()?,
+                address: source.get()?,
+                sender:  source.get()?,
+            }),
+            2 => Ok(RoleEvent::RoleAdminChanged {
+                role:                source.get()?,
+                previous_admin_role: source.get()?,
+                new_admin_role:      source.get()?,
+            }),
+            _ => Err(ParseError::default()),
+        }
+    }
+}
+
+impl schema::SchemaType for RoleEvent {
+    fn get_type() -> schema::Type {
+        let role_change = schema::Fields::Named(Vec::from([
+            (String::from("role"), Role::get_type()),
+            (String::from("address"), Address::get_type()),
+            (String::from("sender"), Address::get_type()),
+        ]));
+        schema::Type::Enum(Vec::from([
+            (String::from("RoleGranted"), role_change.clone()),
+            (String::from("RoleRevoked"), role_change),
+            (
+                String::from("RoleAdminChanged"),
+                schema::Fields::Named(Vec::from([
+                    (String::from("role"), Role::get_type()),
+                    (String::from("previous_admin_role"), Role::get_type()),
+                    (String::from("new_admin_role"), Role::get_type()),
+                ])),
+            ),
+        ]))
+    }
+}
+
+impl ErrorEnvelope {
+    /// The bytes that every serialized error envelope starts with, which allow
+    /// telling it apart from other return values. The first byte is `255`,
+    /// which is unlikely to start the serialization of an error enum, since it
+    /// is the tag of its 256th variant.
+    pub const PREFIX: [u8; 4] = [255, b'E', b'R', b'R'];
+
+    /// Parse the return value of a rejection as an error envelope, returning
+    /// `None` if it is not one.
+    pub fn from_return_value(return_value: &[u8]) -> Option<Self> { from_bytes(return_value).ok() }
+}
+
+impl Serial for ErrorEnvelope {
+    fn serial<W: Write>(&self, out: &mut W) -> Result<(), W::Err> {
+        out.write_all(&Self::PREFIX)?;
+        self.error_code.serial(out)?;
+        self.variant.serial(out)?;
+        self.message.serial(out)?;
+        self.error.serial(out)
+    }
+}
+
+impl Deserial for ErrorEnvelope {
+    fn deserial<R: Read>(source: &mut R) -> ParseResult<Self> {
+        let mut prefix = [0u8; 4];
+        source.read_exact(&mut prefix)?;
+        if prefix != Self::PREFIX {
+            return Err(ParseError::default());
+        }
+        Ok(ErrorEnvelope {
+            error_code: source.get()?,
+            variant:    source.get()?,
+            message:    source.get()?,
+            error:      source.get()?,
+        })
+    }
+}
+
+/// The schema of an error envelope is a
+/// [`TaggedEnum`](schema::Type::TaggedEnum) with the single tag `255`, whose
+/// first field is the rest of the [prefix](ErrorEnvelope::PREFIX). This allows
+/// merging it with the schema of the error enum, so that the schema decodes
+/// both the error and the envelope.
+impl schema::SchemaType for ErrorEnvelope {
+    fn get_type() -> schema::Type {
+        let fields = schema::Fields::Named(Vec::from([
+            (String::from("prefix"), schema::Type::ByteArray(3)),
+            (String::from("error_code"), i32::get_type()),
+            (String::from("variant"), String::get_type()),
+            (String::from("message"), String::get_type()),
+            (String::from("error"), schema::Type::ByteList(schema::SizeLength::U32)),
+        ]));
+        schema::Type::TaggedEnum(BTreeMap::from([(
+            Self::PREFIX[0],
+            (String::from("ErrorEnvelope"), fields),
+        )]))
+    }
+}
+
 impl Serial for PublicKeyEd25519 {
     fn serial<W: Write>(&self, out: &mut W) -> Result<(), W::Err> { self.0.serial(out) }
 }
@@ -2675,6 +3506,15 @@ unsafe impl<T, V, S: HasStateApi> StateClone<S> for StateMap<T, V, S> {
     }
 }
 
+unsafe impl<S: HasStateApi> StateClone<S> for Roles<S> {
+    unsafe fn clone_state(&self, cloned_state_api: &S) -> Self {
+        Self {
+            members: self.members.clone_state(cloned_state_api),
+            admins:  self.admins.clone_state(cloned_state_api),
+        }
+    }
+}
+
 unsafe impl<T: DeserialWithState<S> + Serial, S: HasStateApi> StateClone<S> for StateBox<T, S> {
     unsafe fn clone_state(&self, cloned_state_api: &S) -> Self {
         let inner_value = match &*self.inner.get() {
@@ -41,8 +41,11 @@
 //! This library has the following features:
 //! [`std`](#std-build-with-the-rust-standard-library),
 //! [`build-schema`](#build-schema-build-for-generating-a-module-schema),
-//! [`wasm-test`](#wasm-test-build-for-testing-in-wasm), and
-//! [`crypto-primitives`][crypto-feature].
+//! [`wasm-test`](#wasm-test-build-for-testing-in-wasm),
+//! [`crypto-primitives`][crypto-feature],
+//! [`error-messages`](#error-messages-include-error-messages-in-rejections),
+//! and [`schema-json`](#
+//! schema-json-convert-between-json-and-binary-using-a-schema).
 //!
 //! [crypto-feature]:
 //! #crypto-primitives-for-testing-crypto-with-actual-implementations
@@ -110,6 +113,30 @@
 //! `crypto-primitives` feature when targeting `wasm32-unknown-unknown`.
 //! The issue arises when compiling the [`secp256k1`](https://docs.rs/secp256k1/latest/secp256k1/) crate.
 //!
+//! ## `error-messages`: Include error messages in rejections
+//! Error enums deriving [`Reject`](derive.Reject.html) with the
+//! `#[concordium(with_messages)]` attribute have a message for each variant.
+//! With this feature enabled, the return value of a rejection with such an
+//! error is an [`ErrorEnvelope`] containing the error code, the name of the
+//! variant and the message, which tools can display without the schema of the
+//! contract. The schema of such an error, derived with
+//! [`SchemaType`](derive.SchemaType.html), then includes the envelope as well.
+//! The feature is disabled by default, since the messages increase the size of
+//! the contract.
+//!
+//! ## `schema-json`: Convert between JSON and binary using a schema
+//! Build with this feature to enable the [`schema_json`] module, which converts
+//! values between their JSON representation and their binary serialization
+//! using a [`schema::Type`], without depending on the Rust types of a contract.
+//! This is meant for tools and tests, and requires the `std` feature.
+//!
+//! The feature also enables
+//! [`test_infrastructure::check_schema`](test_infrastructure/fn.check_schema.
+//! html), which checks that the schema of a type agrees with its serialization,
+//! and the `#[concordium(check_schema)]` attribute of
+//! [`derive(SchemaType)`](derive.SchemaType.html), which generates a unit test
+//! calling it when the `build-schema` feature is enabled as well.
+//!
 //! # Traits
 //! To support testing of smart contracts most of the functionality is
 //! accessible via traits. This library generally provides two implementations
@@ -181,6 +208,11 @@
 //! | [CallContractError::MessageFailed] | `-2147483629` |
 //! | [CallContractError::LogicReject] | `-2147483628` |
 //! | [CallContractError::Trap] | `-2147483627` |
+//! | [AccessDeniedError::WrongSenderKind] | `-2147483626` |
+//! | [AccessDeniedError::PolicyNotSatisfied] | `-2147483625` |
+//! | [RolesError::Unauthorized] | `-2147483624` |
+//! | [ReentrancyError] | `-2147483623` |
+//! | [CallContractError::InvalidReturnValue] | `-2147483622` |
 //!
 //! [1]: https://doc.rust-lang.org/std/primitive.unit.html
 //! Other error codes may be added in the future and custom error codes should
@@ -259,6 +291,8 @@ pub mod collections {
 pub mod constants;
 mod impls;
 pub mod prims;
+#[cfg(feature = "schema-json")]
+pub mod schema_json;
 mod traits;
 mod types;
 pub use concordium_contracts_common::*;
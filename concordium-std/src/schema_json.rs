@@ -0,0 +1,1183 @@
+//! Conversion between the JSON representation of values and their binary
+//! serialization, driven by a [`schema::Type`](crate::schema::Type).
+//!
+//! This allows tools, such as test fixtures, CLIs and indexers, to construct
+//! parameters and to display return values and events without depending on the
+//! Rust types of the contract. The binary format is the one produced by the
+//! [`Serial`](crate::Serial) implementation of a type whose
+//! [`SchemaType`](crate::schema::SchemaType) is the given schema.
+//!
+//! The JSON representation of values is the following:
+//! - `Unit` is the empty array `[]`, and `Bool` is a JSON boolean.
+//! - Integers of at most 64 bits are JSON numbers, while `U128`, `I128`,
+//!   `Amount` (in microCCD), `ULeb128` and `ILeb128` are strings of decimal
+//!   digits. Strings are also accepted for the smaller integers when encoding.
+//! - `AccountAddress` is the Base58Check string of the address, and
+//!   `ContractAddress` is an object `{"index": 1, "subindex": 0}`.
+//! - `Timestamp` is an RFC 3339 string, such as `"2022-09-01T12:00:00Z"`, and
+//!   `Duration` is a string such as `"1d 2h 3m 4s 5ms"`.
+//! - `Pair` is an array of two elements, `List`, `Set` and `Array` are arrays,
+//!   and `Map` is an array of key-value pairs `[key, value]`. The order of the
+//!   elements is preserved, so sets and maps must be given in the order their
+//!   `Serial` implementation produces, which for `BTreeSet` and `BTreeMap` is
+//!   ascending.
+//! - `Struct` is an object for named fields, an array for unnamed fields, and
+//!   the empty array if there are no fields.
+//! - `Enum` and `TaggedEnum` are objects `{"Variant": fields}` with the name of
+//!   the variant as the only key, and the fields represented as for structs.
+//! - `String` is a JSON string, `ContractName` is an object `{"contract":
+//!   "name"}` and `ReceiveName` is an object `{"contract": "name", "func":
+//!   "entrypoint"}`.
+//! - `ByteList` and `ByteArray` are hex strings.
+//!
+//! This module requires the `schema-json` feature.
+//!
+//! # Example
+//! ```ignore
+//! let ty = <TransferParams<TokenIdVec, TokenAmountU64> as SchemaType>::get_type();
+//! let parameter = schema_json::encode(&ty, &serde_json::json!([...]))?;
+//! ```
+use crate::{
+    schema::{Fields, SizeLength, Type},
+    Date, Timestamp,
+};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use std::{
+    convert::{TryFrom, TryInto},
+    fmt,
+    str::FromStr,
+};
+
+/// A segment of the path to a value within a JSON value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+    /// A named field, the name of an enum variant, or the position of an
+    /// unnamed field or an element of a pair, which is displayed as `.name`.
+    Field(String),
+    /// The index of an element of a list, set, map or array, which is displayed
+    /// as `[index]`.
+    Index(usize),
+}
+
+/// The ways converting a value between JSON and binary can fail.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JsonErrorKind {
+    /// The JSON value does not have the expected form, which is described by
+    /// the string.
+    Expected(&'static str),
+    /// The integer does not fit in the type.
+    IntegerOutOfRange,
+    /// The length of a collection, string or byte list does not fit in its
+    /// size length.
+    TooLong,
+    /// An array does not have the number of elements required by the type.
+    WrongLength {
+        expected: usize,
+        found:    usize,
+    },
+    /// An object is missing a field of the type.
+    MissingField(String),
+    /// An object has a field that is not part of the type.
+    UnknownField(String),
+    /// The enum has no variant with the given name.
+    UnknownVariant(String),
+    /// The binary data contains a tag that is not a variant of the enum.
+    UnknownTag(u32),
+    /// The binary data is malformed, e.g., it ends before the value does.
+    Parse,
+    /// The given number of bytes remain after decoding the value.
+    TrailingBytes(usize),
+}
+
+/// An error converting a value between JSON and binary, together with the path
+/// to the value where it occurred, e.g., `transfers[2].to.Contract.1`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonError {
+    /// The path from the root value to the value the error occurred in, which
+    /// is empty if it occurred in the root value.
+    pub path: Vec<PathSegment>,
+    /// The kind of error.
+    pub kind: JsonErrorKind,
+}
+
+impl JsonError {
+    fn new(kind: JsonErrorKind) -> Self {
+        Self {
+            path: Vec::new(),
+            kind,
+        }
+    }
+
+    /// Add a segment to the front of the path, when the error propagates to the
+    /// enclosing value.
+    fn within(mut self, segment: PathSegment) -> Self {
+        self.path.insert(0, segment);
+        self
+    }
+
+    fn within_field(self, name: &str) -> Self { self.within(PathSegment::Field(name.into())) }
+
+    fn within_index(self, index: usize) -> Self { self.within(PathSegment::Index(index)) }
+
+    /// The path to the value where the error occurred, e.g.,
+    /// `transfers[2].to.Contract.1`.
+    pub fn path_string(&self) -> String {
+        let mut out = String::new();
+        for segment in self.path.iter() {
+            match segment {
+                PathSegment::Field(name) if out.is_empty() => out.push_str(name),
+                PathSegment::Field(name) => {
+                    out.push('.');
+                    out.push_str(name);
+                }
+                PathSegment::Index(index) => {
+                    out.push('[');
+                    out.push_str(&index.to_string());
+                    out.push(']');
+                }
+            }
+        }
+        out
+    }
+}
+
+impl fmt::Display for JsonErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use JsonErrorKind::*;
+        match self {
+            Expected(expected) => write!(f, "Expected {}.", expected),
+            IntegerOutOfRange => write!(f, "The integer is out of range for the type."),
+            TooLong => write!(f, "The length does not fit in the size length of the type."),
+            WrongLength {
+                expected,
+                found,
+            } => write!(f, "Expected {} elements, but found {}.", expected, found),
+            MissingField(name) => write!(f, "The field '{}' is missing.", name),
+            UnknownField(name) => write!(f, "The field '{}' is not part of the type.", name),
+            UnknownVariant(name) => write!(f, "The enum has no variant '{}'.", name),
+            UnknownTag(tag) => write!(f, "The enum has no variant with tag {}.", tag),
+            Parse => write!(f, "The binary data could not be parsed."),
+            TrailingBytes(n) => write!(f, "{} bytes remain after the value.", n),
+        }
+    }
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.kind)
+        } else {
+            write!(f, "{}: {}", self.path_string(), self.kind)
+        }
+    }
+}
+
+impl std::error::Error for JsonError {}
+
+type JsonResult<A> = Result<A, JsonError>;
+
+fn expected<A>(description: &'static str) -> JsonResult<A> {
+    Err(JsonError::new(JsonErrorKind::Expected(description)))
+}
+
+/// Encode the JSON representation of a value of the type into its binary
+/// serialization.
+pub fn encode(ty: &Type, value: &Value) -> JsonResult<Vec<u8>> {
+    let mut out = Vec::new();
+    encode_into(ty, value, &mut out)?;
+    Ok(out)
+}
+
+/// Encode the JSON representation of a value of the type, appending its
+/// binary serialization to `out`. If encoding fails, part of the value may
+/// have been appended.
+pub fn encode_into(ty: &Type, value: &Value, out: &mut Vec<u8>) -> JsonResult<()> {
+    match ty {
+        Type::Unit => expect_empty_array(value)?,
+        Type::Bool => match value {
+            Value::Bool(b) => out.push(u8::from(*b)),
+            _ => return expected("a boolean"),
+        },
+        Type::U8 => out.push(integer::<u8>(value)?),
+        Type::U16 => out.extend_from_slice(&integer::<u16>(value)?.to_le_bytes()),
+        Type::U32 => out.extend_from_slice(&integer::<u32>(value)?.to_le_bytes()),
+        Type::U64 | Type::Amount => out.extend_from_slice(&integer::<u64>(value)?.to_le_bytes()),
+        Type::U128 => out.extend_from_slice(&integer::<u128>(value)?.to_le_bytes()),
+        Type::I8 => out.extend_from_slice(&integer::<i8>(value)?.to_le_bytes()),
+        Type::I16 => out.extend_from_slice(&integer::<i16>(value)?.to_le_bytes()),
+        Type::I32 => out.extend_from_slice(&integer::<i32>(value)?.to_le_bytes()),
+        Type::I64 => out.extend_from_slice(&integer::<i64>(value)?.to_le_bytes()),
+        Type::I128 => out.extend_from_slice(&integer::<i128>(value)?.to_le_bytes()),
+        Type::AccountAddress => match value.as_str().and_then(decode_account_address) {
+            Some(address) => out.extend_from_slice(&address),
+            None => return expected("an account address in Base58Check"),
+        },
+        Type::ContractAddress => {
+            let object = match value {
+                Value::Object(object) => object,
+                _ => return expected("a contract address {\"index\": .., \"subindex\": ..}"),
+            };
+            expect_only_fields(object, &["index", "subindex"])?;
+            for name in ["index", "subindex"].iter() {
+                let field = get_field(object, name)?;
+                let n = integer::<u64>(field).map_err(|e| e.within_field(name))?;
+                out.extend_from_slice(&n.to_le_bytes());
+            }
+        }
+        Type::Timestamp => match value.as_str().and_then(parse_timestamp) {
+            Some(timestamp) => out.extend_from_slice(&timestamp.timestamp_millis().to_le_bytes()),
+            None => return expected("a timestamp in RFC 3339 format"),
+        },
+        Type::Duration => match value.as_str().and_then(parse_duration) {
+            Some(millis) => out.extend_from_slice(&millis.to_le_bytes()),
+            None => return expected("a duration such as \"1d 2h 3m 4s 5ms\""),
+        },
+        Type::Pair(first, second) => {
+            let elements = expect_array(value, 2)?;
+            encode_into(first, &elements[0], out).map_err(|e| e.within_field("0"))?;
+            encode_into(second, &elements[1], out).map_err(|e| e.within_field("1"))?;
+        }
+        Type::List(size_length, item) | Type::Set(size_length, item) => {
+            let elements = match value {
+                Value::Array(elements) => elements,
+                _ => return expected("an array"),
+            };
+            write_length(size_length, elements.len(), out)?;
+            for (i, element) in elements.iter().enumerate() {
+                encode_into(item, element, out).map_err(|e| e.within_index(i))?;
+            }
+        }
+        Type::Map(size_length, key, item) => {
+            let entries = match value {
+                Value::Array(entries) => entries,
+                _ => return expected("an array of key-value pairs"),
+            };
+            write_length(size_length, entries.len(), out)?;
+            for (i, entry) in entries.iter().enumerate() {
+                let encode_entry = |out: &mut Vec<u8>| {
+                    let entry = expect_array(entry, 2)?;
+                    encode_into(key, &entry[0], out).map_err(|e| e.within_field("0"))?;
+                    encode_into(item, &entry[1], out).map_err(|e| e.within_field("1"))
+                };
+                encode_entry(out).map_err(|e| e.within_index(i))?;
+            }
+        }
+        Type::Array(length, item) => {
+            let elements = expect_array(value, *length as usize)?;
+            for (i, element) in elements.iter().enumerate() {
+                encode_into(item, element, out).map_err(|e| e.within_index(i))?;
+            }
+        }
+        Type::Struct(fields) => encode_fields(fields, value, out)?,
+        Type::Enum(variants) => {
+            let (name, fields) = expect_variant(value)?;
+            let tag = match variants.iter().position(|(variant, _)| variant == name) {
+                Some(tag) => tag,
+                None => return Err(JsonError::new(JsonErrorKind::UnknownVariant(name.clone()))),
+            };
+            out.extend_from_slice(&(tag as u32).to_le_bytes()[..enum_tag_bytes(variants.len())]);
+            encode_fields(&variants[tag].1, fields, out).map_err(|e| e.within_field(name))?;
+        }
+        Type::TaggedEnum(variants) => {
+            let (name, fields) = expect_variant(value)?;
+            let (tag, variant_fields) = match variants
+                .iter()
+                .find(|(_, (variant, _))| variant == name)
+            {
+                Some((tag, (_, variant_fields))) => (*tag, variant_fields),
+                None => return Err(JsonError::new(JsonErrorKind::UnknownVariant(name.clone()))),
+            };
+            out.push(tag);
+            encode_fields(variant_fields, fields, out).map_err(|e| e.within_field(name))?;
+        }
+        Type::String(size_length) => match value {
+            Value::String(s) => write_bytes(size_length, s.as_bytes(), out)?,
+            _ => return expected("a string"),
+        },
+        Type::ContractName(size_length) => {
+            let object = match value {
+                Value::Object(object) => object,
+                _ => return expected("a contract name {\"contract\": ..}"),
+            };
+            expect_only_fields(object, &["contract"])?;
+            let contract = get_string_field(object, "contract")?;
+            write_bytes(size_length, format!("init_{}", contract).as_bytes(), out)?;
+        }
+        Type::ReceiveName(size_length) => {
+            let object = match value {
+                Value::Object(object) => object,
+                _ => return expected("a receive name {\"contract\": .., \"func\": ..}"),
+            };
+            expect_only_fields(object, &["contract", "func"])?;
+            let contract = get_string_field(object, "contract")?;
+            let func = get_string_field(object, "func")?;
+            write_bytes(size_length, format!("{}.{}", contract, func).as_bytes(), out)?;
+        }
+        Type::ULeb128(max_bytes) => {
+            let (negative, magnitude) = leb128_integer(value)?;
+            if negative {
+                return Err(JsonError::new(JsonErrorKind::IntegerOutOfRange));
+            }
+            write_leb128(uleb128_groups(magnitude), *max_bytes, out)?;
+        }
+        Type::ILeb128(max_bytes) => {
+            let (negative, magnitude) = leb128_integer(value)?;
+            write_leb128(ileb128_groups(negative, magnitude), *max_bytes, out)?;
+        }
+        Type::ByteList(size_length) => match value.as_str().and_then(decode_hex) {
+            Some(bytes) => write_bytes(size_length, &bytes, out)?,
+            None => return expected("a hex string"),
+        },
+        Type::ByteArray(length) => match value.as_str().and_then(decode_hex) {
+            Some(bytes) if bytes.len() == *length as usize => out.extend_from_slice(&bytes),
+            Some(bytes) => {
+                return Err(JsonError::new(JsonErrorKind::WrongLength {
+                    expected: *length as usize,
+                    found:    bytes.len(),
+                }))
+            }
+            None => return expected("a hex string"),
+        },
+    }
+    Ok(())
+}
+
+/// The number of bytes of the tag of an enum with the given number of
+/// variants, where the tag is the index of the variant.
+pub(crate) fn enum_tag_bytes(variants: usize) -> usize {
+    if variants <= 1 << 8 {
+        1
+    } else if variants <= 1 << 16 {
+        2
+    } else {
+        4
+    }
+}
+
+fn encode_fields(fields: &Fields, value: &Value, out: &mut Vec<u8>) -> JsonResult<()> {
+    match fields {
+        Fields::Named(fields) => {
+            let object = match value {
+                Value::Object(object) => object,
+                _ => return expected("an object"),
+            };
+            let names: Vec<&str> = fields.iter().map(|(name, _)| name.as_str()).collect();
+            expect_only_fields(object, &names)?;
+            for (name, ty) in fields.iter() {
+                encode_into(ty, get_field(object, name)?, out).map_err(|e| e.within_field(name))?;
+            }
+            Ok(())
+        }
+        Fields::Unnamed(fields) => {
+            let elements = expect_array(value, fields.len())?;
+            for (i, (ty, element)) in fields.iter().zip(elements.iter()).enumerate() {
+                encode_into(ty, element, out).map_err(|e| e.within_field(&i.to_string()))?;
+            }
+            Ok(())
+        }
+        Fields::None => expect_empty_array(value),
+    }
+}
+
+fn expect_empty_array(value: &Value) -> JsonResult<()> {
+    expect_array(value, 0)?;
+    Ok(())
+}
+
+fn expect_array(value: &Value, length: usize) -> JsonResult<&Vec<Value>> {
+    match value {
+        Value::Array(elements) if elements.len() == length => Ok(elements),
+        Value::Array(elements) => Err(JsonError::new(JsonErrorKind::WrongLength {
+            expected: length,
+            found:    elements.len(),
+        })),
+        _ => expected("an array"),
+    }
+}
+
+/// The name and the fields of an enum value `{"Variant": fields}`.
+fn expect_variant(value: &Value) -> JsonResult<(&String, &Value)> {
+    match value {
+        Value::Object(object) if object.len() == 1 => {
+            Ok(object.iter().next().expect("The object has one field."))
+        }
+        _ => expected("an object with the name of the variant as the only field"),
+    }
+}
+
+fn expect_only_fields(object: &Map<String, Value>, names: &[&str]) -> JsonResult<()> {
+    match object.keys().find(|key| !names.contains(&key.as_str())) {
+        Some(key) => Err(JsonError::new(JsonErrorKind::UnknownField(key.clone()))),
+        None => Ok(()),
+    }
+}
+
+fn get_field<'a>(object: &'a Map<String, Value>, name: &str) -> JsonResult<&'a Value> {
+    object.get(name).ok_or_else(|| JsonError::new(JsonErrorKind::MissingField(name.into())))
+}
+
+fn get_string_field<'a>(object: &'a Map<String, Value>, name: &str) -> JsonResult<&'a str> {
+    match get_field(object, name)? {
+        Value::String(s) => Ok(s.as_str()),
+        _ => expected("a string").map_err(|e: JsonError| e.within_field(name)),
+    }
+}
+
+/// Parse an integer given as a JSON number or a string of decimal digits.
+fn integer<A: FromStr + TryFrom<u64> + TryFrom<i64>>(value: &Value) -> JsonResult<A> {
+    let n = match value {
+        Value::Number(n) => match (n.as_u64(), n.as_i64()) {
+            (Some(n), _) => A::try_from(n).ok(),
+            (None, Some(n)) => A::try_from(n).ok(),
+            (None, None) => return expected("an integer"),
+        },
+        Value::String(s) if is_decimal(s) => s.parse().ok(),
+        _ => return expected("an integer"),
+    };
+    n.ok_or_else(|| JsonError::new(JsonErrorKind::IntegerOutOfRange))
+}
+
+/// Whether the string is an optional minus sign followed by decimal digits.
+fn is_decimal(s: &str) -> bool {
+    let digits = s.strip_prefix('-').unwrap_or(s);
+    !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// The number of bytes of a length with the size length.
+pub(crate) fn size_length_bytes(size_length: &SizeLength) -> usize {
+    match size_length {
+        SizeLength::U8 => 1,
+        SizeLength::U16 => 2,
+        SizeLength::U32 => 4,
+        SizeLength::U64 => 8,
+    }
+}
+
+fn write_length(size_length: &SizeLength, length: usize, out: &mut Vec<u8>) -> JsonResult<()> {
+    let bytes = size_length_bytes(size_length);
+    let length = length as u64;
+    if bytes < 8 && length >> (8 * bytes) != 0 {
+        return Err(JsonError::new(JsonErrorKind::TooLong));
+    }
+    out.extend_from_slice(&length.to_le_bytes()[..bytes]);
+    Ok(())
+}
+
+fn write_bytes(size_length: &SizeLength, bytes: &[u8], out: &mut Vec<u8>) -> JsonResult<()> {
+    write_length(size_length, bytes.len(), out)?;
+    out.extend_from_slice(bytes);
+    Ok(())
+}
+
+/// Decode the binary serialization of a value of the type into its JSON
+/// representation. All of the bytes must be consumed.
+pub fn decode(ty: &Type, bytes: &[u8]) -> JsonResult<Value> {
+    let mut reader = Reader {
+        bytes,
+    };
+    let value = decode_from(ty, &mut reader)?;
+    if !reader.bytes.is_empty() {
+        return Err(JsonError::new(JsonErrorKind::TrailingBytes(reader.bytes.len())));
+    }
+    Ok(value)
+}
+
+/// The remaining bytes of the binary data being decoded.
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> JsonResult<&'a [u8]> {
+        if n > self.bytes.len() {
+            return Err(JsonError::new(JsonErrorKind::Parse));
+        }
+        let (taken, rest) = self.bytes.split_at(n);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    /// Read a little-endian unsigned integer of the given number of bytes,
+    /// which is at most 8.
+    fn uint(&mut self, n: usize) -> JsonResult<u64> {
+        let mut buf = [0u8; 8];
+        buf[..n].copy_from_slice(self.take(n)?);
+        Ok(u64::from_le_bytes(buf))
+    }
+
+    fn array16(&mut self) -> JsonResult<[u8; 16]> {
+        Ok(self.take(16)?.try_into().expect("Exactly 16 bytes were taken."))
+    }
+
+    fn length(&mut self, size_length: &SizeLength) -> JsonResult<usize> {
+        let length = self.uint(size_length_bytes(size_length))?;
+        length.try_into().map_err(|_| JsonError::new(JsonErrorKind::Parse))
+    }
+
+    fn string(&mut self, size_length: &SizeLength) -> JsonResult<String> {
+        let length = self.length(size_length)?;
+        String::from_utf8(self.take(length)?.to_vec())
+            .map_err(|_| JsonError::new(JsonErrorKind::Parse))
+    }
+}
+
+fn decode_from(ty: &Type, reader: &mut Reader) -> JsonResult<Value> {
+    let value = match ty {
+        Type::Unit => Value::Array(Vec::new()),
+        Type::Bool => match reader.uint(1)? {
+            0 => Value::Bool(false),
+            1 => Value::Bool(true),
+            _ => return Err(JsonError::new(JsonErrorKind::Parse)),
+        },
+        Type::U8 => reader.uint(1)?.into(),
+        Type::U16 => reader.uint(2)?.into(),
+        Type::U32 => reader.uint(4)?.into(),
+        Type::U64 => reader.uint(8)?.into(),
+        Type::Amount => reader.uint(8)?.to_string().into(),
+        Type::U128 => u128::from_le_bytes(reader.array16()?).to_string().into(),
+        Type::I8 => (reader.uint(1)? as u8 as i8).into(),
+        Type::I16 => (reader.uint(2)? as u16 as i16).into(),
+        Type::I32 => (reader.uint(4)? as u32 as i32).into(),
+        Type::I64 => (reader.uint(8)? as i64).into(),
+        Type::I128 => i128::from_le_bytes(reader.array16()?).to_string().into(),
+        Type::AccountAddress => {
+            let address: [u8; 32] =
+                reader.take(32)?.try_into().expect("Exactly 32 bytes were taken.");
+            encode_account_address(&address).into()
+        }
+        Type::ContractAddress => {
+            let mut object = Map::new();
+            object.insert(
+                "index".into(),
+                reader.uint(8).map_err(|e| e.within_field("index"))?.into(),
+            );
+            object.insert(
+                "subindex".into(),
+                reader.uint(8).map_err(|e| e.within_field("subindex"))?.into(),
+            );
+            Value::Object(object)
+        }
+        Type::Timestamp => {
+            let timestamp = Timestamp::from_timestamp_millis(reader.uint(8)?);
+            match format_timestamp(timestamp) {
+                Some(timestamp) => timestamp.into(),
+                None => return Err(JsonError::new(JsonErrorKind::Parse)),
+            }
+        }
+        Type::Duration => format_duration(reader.uint(8)?).into(),
+        Type::Pair(first, second) => Value::Array(vec![
+            decode_from(first, reader).map_err(|e| e.within_field("0"))?,
+            decode_from(second, reader).map_err(|e| e.within_field("1"))?,
+        ]),
+        Type::List(size_length, item) | Type::Set(size_length, item) => {
+            let length = reader.length(size_length)?;
+            let elements = (0..length)
+                .map(|i| decode_from(item, reader).map_err(|e| e.within_index(i)))
+                .collect::<JsonResult<_>>()?;
+            Value::Array(elements)
+        }
+        Type::Map(size_length, key, item) => {
+            let length = reader.length(size_length)?;
+            let mut entries = Vec::new();
+            for i in 0..length {
+                let key =
+                    decode_from(key, reader).map_err(|e| e.within_field("0").within_index(i))?;
+                let value =
+                    decode_from(item, reader).map_err(|e| e.within_field("1").within_index(i))?;
+                entries.push(Value::Array(vec![key, value]));
+            }
+            Value::Array(entries)
+        }
+        Type::Array(length, item) => {
+            let elements = (0..*length as usize)
+                .map(|i| decode_from(item, reader).map_err(|e| e.within_index(i)))
+                .collect::<JsonResult<_>>()?;
+            Value::Array(elements)
+        }
+        Type::Struct(fields) => decode_fields(fields, reader)?,
+        Type::Enum(variants) => {
+            let tag = reader.uint(enum_tag_bytes(variants.len()))? as u32;
+            match variants.get(tag as usize) {
+                Some((name, fields)) => variant_value(name, fields, reader)?,
+                None => return Err(JsonError::new(JsonErrorKind::UnknownTag(tag))),
+            }
+        }
+        Type::TaggedEnum(variants) => {
+            let tag = reader.uint(1)? as u8;
+            match variants.get(&tag) {
+                Some((name, fields)) => variant_value(name, fields, reader)?,
+                None => return Err(JsonError::new(JsonErrorKind::UnknownTag(tag.into()))),
+            }
+        }
+        Type::String(size_length) => reader.string(size_length)?.into(),
+        Type::ContractName(size_length) => {
+            let name = reader.string(size_length)?;
+            let contract = match name.strip_prefix("init_") {
+                Some(contract) => contract,
+                None => return Err(JsonError::new(JsonErrorKind::Parse)),
+            };
+            let mut object = Map::new();
+            object.insert("contract".into(), contract.into());
+            Value::Object(object)
+        }
+        Type::ReceiveName(size_length) => {
+            let name = reader.string(size_length)?;
+            let (contract, func) = match name.find('.') {
+                Some(dot) => (&name[..dot], &name[dot + 1..]),
+                None => return Err(JsonError::new(JsonErrorKind::Parse)),
+            };
+            let mut object = Map::new();
+            object.insert("contract".into(), contract.into());
+            object.insert("func".into(), func.into());
+            Value::Object(object)
+        }
+        Type::ULeb128(max_bytes) => {
+            let groups = read_leb128(reader, *max_bytes)?;
+            base128_to_decimal(&groups).into()
+        }
+        Type::ILeb128(max_bytes) => {
+            let mut groups = read_leb128(reader, *max_bytes)?;
+            if groups.last().map_or(false, |group| group & 0x40 != 0) {
+                // The value is negative, so the magnitude is the two's complement.
+                for group in groups.iter_mut() {
+                    *group ^= 0x7f;
+                }
+                increment_base128(&mut groups);
+                format!("-{}", base128_to_decimal(&groups)).into()
+            } else {
+                base128_to_decimal(&groups).into()
+            }
+        }
+        Type::ByteList(size_length) => {
+            let length = reader.length(size_length)?;
+            encode_hex(reader.take(length)?).into()
+        }
+        Type::ByteArray(length) => encode_hex(reader.take(*length as usize)?).into(),
+    };
+    Ok(value)
+}
+
+fn decode_fields(fields: &Fields, reader: &mut Reader) -> JsonResult<Value> {
+    match fields {
+        Fields::Named(fields) => {
+            let mut object = Map::new();
+            for (name, ty) in fields.iter() {
+                let value = decode_from(ty, reader).map_err(|e| e.within_field(name))?;
+                object.insert(name.clone(), value);
+            }
+            Ok(Value::Object(object))
+        }
+        Fields::Unnamed(fields) => {
+            let elements = fields
+                .iter()
+                .enumerate()
+                .map(|(i, ty)| decode_from(ty, reader).map_err(|e| e.within_field(&i.to_string())))
+                .collect::<JsonResult<_>>()?;
+            Ok(Value::Array(elements))
+        }
+        Fields::None => Ok(Value::Array(Vec::new())),
+    }
+}
+
+fn variant_value(name: &str, fields: &Fields, reader: &mut Reader) -> JsonResult<Value> {
+    let fields = decode_fields(fields, reader).map_err(|e| e.within_field(name))?;
+    let mut object = Map::new();
+    object.insert(name.into(), fields);
+    Ok(Value::Object(object))
+}
+
+/// Parse an integer of arbitrary size for LEB128, given as a JSON number or a
+/// string of decimal digits, into its sign and the little-endian base 128
+/// digits of its magnitude.
+fn leb128_integer(value: &Value) -> JsonResult<(bool, Vec<u8>)> {
+    let s = match value {
+        Value::Number(n) if n.is_u64() || n.is_i64() => n.to_string(),
+        Value::String(s) if is_decimal(s) => s.clone(),
+        _ => return expected("an integer"),
+    };
+    let (negative, digits) = match s.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, s.as_str()),
+    };
+    let magnitude = decimal_to_base128(digits);
+    // Negative zero is zero.
+    Ok((negative && magnitude.iter().any(|&group| group != 0), magnitude))
+}
+
+/// The groups of the unsigned LEB128 encoding of the magnitude.
+fn uleb128_groups(mut magnitude: Vec<u8>) -> Vec<u8> {
+    while magnitude.len() > 1 && magnitude.last() == Some(&0) {
+        magnitude.pop();
+    }
+    magnitude
+}
+
+/// The groups of the signed LEB128 encoding of the integer with the given sign
+/// and magnitude, which are the two's complement of the magnitude if it is
+/// negative.
+fn ileb128_groups(negative: bool, magnitude: Vec<u8>) -> Vec<u8> {
+    let mut groups = if negative {
+        let mut groups = magnitude;
+        decrement_base128(&mut groups);
+        let mut groups = uleb128_groups(groups);
+        for group in groups.iter_mut() {
+            *group ^= 0x7f;
+        }
+        groups
+    } else {
+        uleb128_groups(magnitude)
+    };
+    // The sign is the bit 6 of the last group, so extend it if it differs.
+    let last = *groups.last().expect("There is at least one group.");
+    if negative && last & 0x40 == 0 {
+        groups.push(0x7f);
+    } else if !negative && last & 0x40 != 0 {
+        groups.push(0);
+    }
+    groups
+}
+
+fn write_leb128(groups: Vec<u8>, max_bytes: u32, out: &mut Vec<u8>) -> JsonResult<()> {
+    if groups.len() > max_bytes as usize {
+        return Err(JsonError::new(JsonErrorKind::IntegerOutOfRange));
+    }
+    let last = groups.len() - 1;
+    out.extend(groups.iter().enumerate().map(|(i, &group)| {
+        if i == last {
+            group
+        } else {
+            group | 0x80
+        }
+    }));
+    Ok(())
+}
+
+/// Read the 7-bit groups of a LEB128 encoded integer of at most `max_bytes`.
+fn read_leb128(reader: &mut Reader, max_bytes: u32) -> JsonResult<Vec<u8>> {
+    let mut groups = Vec::new();
+    for _ in 0..max_bytes {
+        let byte = reader.uint(1)? as u8;
+        groups.push(byte & 0x7f);
+        if byte & 0x80 == 0 {
+            return Ok(groups);
+        }
+    }
+    Err(JsonError::new(JsonErrorKind::Parse))
+}
+
+/// The little-endian base 128 digits of a string of decimal digits.
+fn decimal_to_base128(decimal: &str) -> Vec<u8> {
+    let mut digits: Vec<u32> = decimal.bytes().map(|b| u32::from(b - b'0')).collect();
+    let mut groups = Vec::new();
+    while !digits.is_empty() {
+        // Divide the decimal digits by 128, keeping the remainder.
+        let mut remainder = 0;
+        let mut quotient = Vec::with_capacity(digits.len());
+        for digit in digits {
+            let acc = remainder * 10 + digit;
+            if !quotient.is_empty() || acc >= 128 {
+                quotient.push(acc / 128);
+            }
+            remainder = acc % 128;
+        }
+        groups.push(remainder as u8);
+        digits = quotient;
+    }
+    groups
+}
+
+/// The string of decimal digits of little-endian base 128 digits.
+fn base128_to_decimal(groups: &[u8]) -> String {
+    // Little-endian decimal digits.
+    let mut decimal: Vec<u8> = vec![0];
+    for &group in groups.iter().rev() {
+        let mut carry = u32::from(group);
+        for digit in decimal.iter_mut() {
+            let acc = u32::from(*digit) * 128 + carry;
+            *digit = (acc % 10) as u8;
+            carry = acc / 10;
+        }
+        while carry > 0 {
+            decimal.push((carry % 10) as u8);
+            carry /= 10;
+        }
+    }
+    decimal.iter().rev().map(|&digit| char::from(b'0' + digit)).collect()
+}
+
+fn increment_base128(groups: &mut Vec<u8>) {
+    for group in groups.iter_mut() {
+        if *group == 0x7f {
+            *group = 0;
+        } else {
+            *group += 1;
+            return;
+        }
+    }
+    groups.push(1);
+}
+
+/// Subtract one from base 128 digits, which must not be zero.
+fn decrement_base128(groups: &mut [u8]) {
+    for group in groups.iter_mut() {
+        if *group == 0 {
+            *group = 0x7f;
+        } else {
+            *group -= 1;
+            return;
+        }
+    }
+}
+
+fn decode_hex(s: &str) -> Option<Vec<u8>> {
+    if s.len() % 2 != 0 || !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    (0..s.len()).step_by(2).map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok()).collect()
+}
+
+fn encode_hex(bytes: &[u8]) -> String { bytes.iter().map(|byte| format!("{:02x}", byte)).collect() }
+
+const BASE58_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// The version byte of account addresses in Base58Check.
+const ACCOUNT_ADDRESS_VERSION: u8 = 1;
+
+fn account_address_checksum(payload: &[u8]) -> [u8; 4] {
+    let hash = Sha256::digest(Sha256::digest(payload));
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// Encode an account address using Base58Check.
+fn encode_account_address(address: &[u8; 32]) -> String {
+    let mut bytes = Vec::with_capacity(37);
+    bytes.push(ACCOUNT_ADDRESS_VERSION);
+    bytes.extend_from_slice(address);
+    let checksum = account_address_checksum(&bytes);
+    bytes.extend_from_slice(&checksum);
+    // Little-endian base 58 digits. There are no leading zero bytes, since the
+    // version byte is not zero.
+    let mut digits: Vec<u8> = Vec::new();
+    for &byte in bytes.iter() {
+        let mut carry = u32::from(byte);
+        for digit in digits.iter_mut() {
+            let acc = u32::from(*digit) * 256 + carry;
+            *digit = (acc % 58) as u8;
+            carry = acc / 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+    digits.iter().rev().map(|&digit| char::from(BASE58_ALPHABET[usize::from(digit)])).collect()
+}
+
+/// Decode an account address from Base58Check, checking the version byte and
+/// the checksum.
+fn decode_account_address(s: &str) -> Option<[u8; 32]> {
+    let mut bytes = [0u8; 37];
+    for c in s.bytes() {
+        let mut carry = BASE58_ALPHABET.iter().position(|&a| a == c)? as u32;
+        for byte in bytes.iter_mut().rev() {
+            let acc = u32::from(*byte) * 58 + carry;
+            *byte = acc as u8;
+            carry = acc >> 8;
+        }
+        if carry != 0 {
+            return None;
+        }
+    }
+    if bytes[0] != ACCOUNT_ADDRESS_VERSION || account_address_checksum(&bytes[..33]) != bytes[33..]
+    {
+        return None;
+    }
+    let address: [u8; 32] = bytes[1..33].try_into().ok()?;
+    // Reject non-canonical encodings, e.g., with leading ones.
+    if encode_account_address(&address) != s {
+        return None;
+    }
+    Some(address)
+}
+
+/// Number of milliseconds in a day.
+const MILLIS_PER_DAY: u64 = 24 * 60 * 60 * 1000;
+
+/// Parse a timestamp in RFC 3339 format with at most millisecond precision,
+/// such as `2022-09-01T12:00:00.123+02:00`.
+fn parse_timestamp(s: &str) -> Option<Timestamp> {
+    let bytes = s.as_bytes();
+    if bytes.len() < 20
+        || bytes[4] != b'-'
+        || bytes[7] != b'-'
+        || !matches!(bytes[10], b'T' | b't' | b' ')
+        || bytes[13] != b':'
+        || bytes[16] != b':'
+    {
+        return None;
+    }
+    let number = |from: usize, to: usize| -> Option<u64> {
+        bytes[from..to].iter().try_fold(0u64, |acc, b| {
+            if b.is_ascii_digit() {
+                Some(acc * 10 + u64::from(b - b'0'))
+            } else {
+                None
+            }
+        })
+    };
+    let date = Date::new(number(0, 4)? as u16, number(5, 7)? as u8, number(8, 10)? as u8)?;
+    let (hour, minute, second) = (number(11, 13)?, number(14, 16)?, number(17, 19)?);
+    if hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+    let mut rest = &s[19..];
+    let mut millis = 0;
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let digits = fraction.bytes().take_while(u8::is_ascii_digit).count();
+        let (fraction, after) = fraction.split_at(digits);
+        // Timestamps have millisecond precision.
+        if digits == 0 || fraction.bytes().skip(3).any(|b| b != b'0') {
+            return None;
+        }
+        for (i, b) in fraction.bytes().take(3).enumerate() {
+            millis += u64::from(b - b'0') * 10u64.pow(2 - i as u32);
+        }
+        rest = after;
+    }
+    let local = date.to_timestamp()?.timestamp_millis()
+        + ((hour * 60 + minute) * 60 + second) * 1000
+        + millis;
+    let offset_bytes = rest.as_bytes();
+    let utc = match offset_bytes {
+        b"Z" | b"z" => local,
+        [sign @ (b'+' | b'-'), _, _, b':', _, _] => {
+            let hours = number(s.len() - 5, s.len() - 3)?;
+            let minutes = number(s.len() - 2, s.len())?;
+            if hours > 23 || minutes > 59 {
+                return None;
+            }
+            let offset = (hours * 60 + minutes) * 60 * 1000;
+            if *sign == b'+' {
+                local.checked_sub(offset)?
+            } else {
+                local.checked_add(offset)?
+            }
+        }
+        _ => return None,
+    };
+    Some(Timestamp::from_timestamp_millis(utc))
+}
+
+/// Format a timestamp in RFC 3339 format in UTC with millisecond precision.
+/// Returns `None` if the year of the timestamp has more than 4 digits.
+fn format_timestamp(timestamp: Timestamp) -> Option<String> {
+    let date = Date::from_timestamp(timestamp)?;
+    let millis = timestamp.timestamp_millis() % MILLIS_PER_DAY;
+    Some(format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        date.year(),
+        date.month(),
+        date.day(),
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        millis % 1000
+    ))
+}
+
+/// The units of durations and their number of milliseconds.
+const DURATION_UNITS: [(&str, u64); 5] =
+    [("d", MILLIS_PER_DAY), ("h", 3_600_000), ("m", 60_000), ("s", 1000), ("ms", 1)];
+
+/// Parse a duration such as `1d 2h 3m 4s 5ms` into milliseconds.
+fn parse_duration(s: &str) -> Option<u64> {
+    let mut millis: u64 = 0;
+    let mut parts = 0;
+    for part in s.split_whitespace() {
+        let digits = part.bytes().take_while(u8::is_ascii_digit).count();
+        let (n, unit) = part.split_at(digits);
+        let (_, unit_millis) = DURATION_UNITS.iter().find(|(name, _)| *name == unit)?;
+        let n: u64 = n.parse().ok()?;
+        millis = millis.checked_add(n.checked_mul(*unit_millis)?)?;
+        parts += 1;
+    }
+    if parts == 0 {
+        return None;
+    }
+    Some(millis)
+}
+
+/// Format milliseconds as a duration such as `1d 2h 3m 4s 5ms`.
+fn format_duration(mut millis: u64) -> String {
+    let mut parts = Vec::new();
+    for (name, unit_millis) in DURATION_UNITS.iter() {
+        if millis >= *unit_millis {
+            parts.push(format!("{}{}", millis / unit_millis, name));
+            millis %= unit_millis;
+        }
+    }
+    if parts.is_empty() {
+        "0ms".into()
+    } else {
+        parts.join(" ")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{collections::BTreeMap, schema::SchemaType};
+    use serde_json::json;
+
+    /// Check that the value encodes to the bytes, and decodes back to itself.
+    fn check(ty: &Type, value: Value, bytes: &[u8]) {
+        assert_eq!(encode(ty, &value).as_deref(), Ok(bytes), "Encoding {}", value);
+        assert_eq!(decode(ty, bytes), Ok(value));
+    }
+
+    #[test]
+    fn integers() {
+        check(&Type::U8, json!(255), &[255]);
+        check(&Type::I16, json!(-2), &[0xfe, 0xff]);
+        check(&Type::U64, json!(u64::MAX), &[0xff; 8]);
+        check(&Type::U128, json!(u128::MAX.to_string()), &[0xff; 16]);
+        check(&Type::I128, json!("-1"), &[0xff; 16]);
+        check(&Type::Amount, json!("1000000"), &1_000_000u64.to_le_bytes());
+        assert_eq!(encode(&Type::U16, &json!("300")), Ok(vec![44, 1]));
+        assert_eq!(
+            encode(&Type::U8, &json!(256)),
+            Err(JsonError::new(JsonErrorKind::IntegerOutOfRange))
+        );
+        assert_eq!(encode(&Type::U8, &json!(1.5)), expected("an integer"));
+    }
+
+    #[test]
+    fn leb128() {
+        check(&Type::ULeb128(2), json!("300"), &[0xac, 0x02]);
+        check(&Type::ULeb128(1), json!("0"), &[0]);
+        check(&Type::ILeb128(1), json!("-1"), &[0x7f]);
+        check(&Type::ILeb128(1), json!("-64"), &[0x40]);
+        check(&Type::ILeb128(2), json!("-65"), &[0xbf, 0x7f]);
+        check(&Type::ILeb128(2), json!("64"), &[0xc0, 0x00]);
+        let large = "340282366920938463463374607431768211456"; // 2^128
+        let bytes = encode(&Type::ULeb128(19), &json!(large)).unwrap();
+        assert_eq!(bytes.len(), 19);
+        assert_eq!(decode(&Type::ULeb128(19), &bytes), Ok(json!(large)));
+        assert_eq!(
+            encode(&Type::ULeb128(18), &json!(large)),
+            Err(JsonError::new(JsonErrorKind::IntegerOutOfRange))
+        );
+        assert_eq!(
+            encode(&Type::ULeb128(4), &json!("-1")),
+            Err(JsonError::new(JsonErrorKind::IntegerOutOfRange))
+        );
+    }
+
+    #[test]
+    fn addresses_and_times() {
+        let address = "3kBx2h5Y2veb4hZgAJWPrr8RyQESKm5TjzF3ti1QQ4VSYLwK1G";
+        let address_bytes =
+            decode_hex("69752406cc939fc90ca6a73b57cee109963547f942006d219144924f8485fb0d").unwrap();
+        check(&Type::AccountAddress, json!(address), &address_bytes);
+        assert!(encode(
+            &Type::AccountAddress,
+            &json!("3kBx2h5Y2veb4hZgAJWPrr8RyQESKm5TjzF3ti1QQ4VSYLwK1H")
+        )
+        .is_err());
+        check(&Type::ContractAddress, json!({"index": 3, "subindex": 0}), &[
+            3, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        ]);
+        check(
+            &Type::Timestamp,
+            json!("2022-09-01T12:00:00.123Z"),
+            &1_662_033_600_123u64.to_le_bytes(),
+        );
+        assert_eq!(
+            encode(&Type::Timestamp, &json!("2022-09-01T14:00:00.123+02:00")),
+            Ok(1_662_033_600_123u64.to_le_bytes().to_vec())
+        );
+        check(&Type::Duration, json!("1d 2h 3m 4s 5ms"), &93_784_005u64.to_le_bytes());
+        check(&Type::Duration, json!("0ms"), &[0; 8]);
+    }
+
+    #[test]
+    fn collections_and_names() {
+        check(&Type::List(SizeLength::U8, Box::new(Type::Bool)), json!([true, false]), &[2, 1, 0]);
+        check(
+            &Type::Map(SizeLength::U16, Box::new(Type::String(SizeLength::U8)), Box::new(Type::U8)),
+            json!([["a", 1]]),
+            &[1, 0, 1, b'a', 1],
+        );
+        check(&Type::Set(SizeLength::U32, Box::new(Type::U8)), json!([1, 2]), &[2, 0, 0, 0, 1, 2]);
+        check(&Type::Array(2, Box::new(Type::U8)), json!([1, 2]), &[1, 2]);
+        check(&Type::ByteList(SizeLength::U8), json!("00ff"), &[2, 0, 255]);
+        check(&Type::ByteArray(2), json!("00ff"), &[0, 255]);
+        check(&Type::ContractName(SizeLength::U16), json!({"contract": "c"}), &[
+            6, 0, b'i', b'n', b'i', b't', b'_', b'c',
+        ]);
+        check(&Type::ReceiveName(SizeLength::U16), json!({"contract": "c", "func": "f"}), &[
+            3, 0, b'c', b'.', b'f',
+        ]);
+        assert_eq!(
+            encode(&Type::List(SizeLength::U8, Box::new(Type::Unit)), &json!(vec![json!([]); 256])),
+            Err(JsonError::new(JsonErrorKind::TooLong))
+        );
+    }
+
+    #[test]
+    fn enums_and_structs() {
+        let ty = Type::Enum(vec![
+            ("None".into(), Fields::None),
+            ("Some".into(), Fields::Unnamed(vec![Type::U8])),
+        ]);
+        check(&ty, json!({"None": []}), &[0]);
+        check(&ty, json!({"Some": [5]}), &[1, 5]);
+        assert_eq!(decode(&ty, &[2]), Err(JsonError::new(JsonErrorKind::UnknownTag(2))));
+        let mut variants = BTreeMap::new();
+        variants.insert(254, ("Mint".into(), Fields::Named(vec![("amount".into(), Type::U8)])));
+        check(&Type::TaggedEnum(variants), json!({"Mint": {"amount": 1}}), &[254, 1]);
+        assert_eq!(
+            encode(
+                &Type::Struct(Fields::Named(vec![("a".into(), Type::U8)])),
+                &json!({"a": 1, "b": 2})
+            ),
+            Err(JsonError::new(JsonErrorKind::UnknownField("b".into())))
+        );
+        check(&<Option<(u32, bool)> as SchemaType>::get_type(), json!({"Some": [[7, true]]}), &[
+            1, 7, 0, 0, 0, 1,
+        ]);
+    }
+
+    #[test]
+    fn error_paths() {
+        let address = Type::Enum(vec![
+            ("Account".into(), Fields::Unnamed(vec![Type::AccountAddress])),
+            (
+                "Contract".into(),
+                Fields::Unnamed(vec![Type::ContractAddress, Type::ReceiveName(SizeLength::U16)]),
+            ),
+        ]);
+        let transfer = Type::Struct(Fields::Named(vec![("to".into(), address)]));
+        let ty = Type::Struct(Fields::Named(vec![(
+            "transfers".into(),
+            Type::List(SizeLength::U16, Box::new(transfer)),
+        )]));
+        let receiver =
+            json!({"Contract": [{"index": 1, "subindex": 0}, {"contract": "c", "func": "f"}]});
+        let value = json!({"transfers": [{"to": receiver}, {"to": receiver}, {"to": {"Contract": [{"index": 1, "subindex": 0}, "c.f"]}}]});
+        let error = encode(&ty, &value).unwrap_err();
+        assert_eq!(error.path_string(), "transfers[2].to.Contract.1");
+        assert_eq!(
+            error.to_string(),
+            "transfers[2].to.Contract.1: Expected a receive name {\"contract\": .., \"func\": ..}."
+        );
+        let bytes = encode(&ty, &json!({"transfers": [{"to": receiver}]})).unwrap();
+        let error = decode(&ty, &bytes[..bytes.len() - 1]).unwrap_err();
+        assert_eq!(error.path_string(), "transfers[0].to.Contract.1");
+        assert_eq!(
+            decode(&Type::U8, &[1, 2]),
+            Err(JsonError::new(JsonErrorKind::TrailingBytes(1)))
+        );
+    }
+}
@@ -98,7 +98,8 @@ pub struct TestChainMeta {
 
 /// Policy type used by init and receive contexts for testing.
 /// This type should not be used directly, but rather through
-/// its `HasPolicy` interface.
+/// its `HasPolicy` interface. It is constructed with [`TestPolicyBuilder`], or
+/// converted from an [`OwnedPolicy`].
 #[derive(Debug, Clone)]
 pub struct TestPolicy {
     /// Current position in the vector of policies. Used to implement
@@ -107,8 +108,8 @@ pub struct TestPolicy {
     policy:   Policy<Rc<[(AttributeTag, AttributeValue)]>>,
 }
 
-impl TestPolicy {
-    fn new(policy: OwnedPolicy) -> Self {
+impl From<OwnedPolicy> for TestPolicy {
+    fn from(policy: OwnedPolicy) -> Self {
         let policy = Policy {
             identity_provider: policy.identity_provider,
             created_at:        policy.created_at,
@@ -352,14 +353,15 @@ impl TestChainMeta {
 }
 
 impl<'a, C> TestContext<'a, C> {
-    /// Push a new sender policy to the context.
+    /// Push a new sender policy to the context, either a [`TestPolicy`]
+    /// constructed with [`TestPolicyBuilder`] or an [`OwnedPolicy`].
     /// When the first policy is pushed this will set the policy vector
     /// to 'Some', even if it was undefined previously.
-    pub fn push_policy(&mut self, value: OwnedPolicy) -> &mut Self {
+    pub fn push_policy(&mut self, value: impl Into<TestPolicy>) -> &mut Self {
         if let Some(policies) = self.common.policies.as_mut() {
-            policies.push(TestPolicy::new(value));
+            policies.push(value.into());
         } else {
-            self.common.policies = Some(vec![TestPolicy::new(value)])
+            self.common.policies = Some(vec![value.into()])
         }
         self
     }
@@ -431,6 +433,231 @@ impl<'a> TestReceiveContext<'a> {
     }
 }
 
+/// A builder for the [`TestPolicy`] values that are pushed to a test context
+/// with [`TestContext::push_policy`], or passed directly to functions taking a
+/// policy implementing [`HasPolicy`], such as the predicates of the
+/// `require_policy` attribute of [`#[receive]`](../attr.receive.html).
+///
+/// Attribute values are encoded the same way as on the chain, so that they are
+/// returned unchanged by [`HasPolicy::next_item`] and
+/// [`HasPolicy::attributes`]. Countries are ISO 3166-1 alpha-2 codes, and
+/// dates are represented as [`Date`].
+///
+/// The policy starts out with identity provider `0`, no attributes, and a
+/// validity period spanning all timestamps.
+///
+/// ```rust
+/// # use concordium_std::{*, test_infrastructure::*};
+/// let mut ctx = TestReceiveContext::empty();
+/// ctx.push_policy(
+///     TestPolicyBuilder::new()
+///         .country_of_residence(*b"DK")
+///         .date_of_birth(Date::new(1990, 1, 31).unwrap())
+///         .build(),
+/// );
+/// ctx.push_policy(TestPolicyBuilder::adult_eu_resident().build());
+/// ```
+#[derive(Debug, Clone)]
+pub struct TestPolicyBuilder {
+    identity_provider: IdentityProvider,
+    created_at:        Timestamp,
+    valid_to:          Timestamp,
+    items:             Vec<(AttributeTag, AttributeValue)>,
+}
+
+impl Default for TestPolicyBuilder {
+    fn default() -> Self {
+        Self {
+            identity_provider: 0,
+            created_at:        Timestamp::from_timestamp_millis(0),
+            valid_to:          Timestamp::from_timestamp_millis(u64::MAX),
+            items:             Vec::new(),
+        }
+    }
+}
+
+impl TestPolicyBuilder {
+    /// Create a builder for a policy without any attributes.
+    pub fn new() -> Self { Default::default() }
+
+    /// A policy of an adult residing in, and with the nationality of, Denmark,
+    /// which is a member of the EU. The date of birth is 1950-01-01, so the
+    /// holder is an adult at any timestamp.
+    pub fn adult_eu_resident() -> Self {
+        let mut builder = Self::new();
+        builder.country_of_residence(*b"DK").nationality(*b"DK").date_of_birth(Date {
+            year:  1950,
+            month: 1,
+            day:   1,
+        });
+        builder
+    }
+
+    /// A policy of a person residing in, and with the nationality of, Denmark,
+    /// which is a member of the EU. The date of birth is chosen such that the
+    /// holder is `age` years old at the given timestamp, which is typically
+    /// the slot time of the test context.
+    pub fn eu_resident_of_age(age: u16, at: Timestamp) -> Self {
+        let mut builder = Self::new();
+        builder.country_of_residence(*b"DK").nationality(*b"DK").date_of_birth_for_age(age, at);
+        builder
+    }
+
+    /// A policy of an adult residing in, and with the nationality of, the
+    /// United States, which is not a member of the EU. The date of birth is
+    /// 1950-01-01, so the holder is an adult at any timestamp.
+    pub fn adult_non_eu_resident() -> Self {
+        let mut builder = Self::new();
+        builder.country_of_residence(*b"US").nationality(*b"US").date_of_birth(Date {
+            year:  1950,
+            month: 1,
+            day:   1,
+        });
+        builder
+    }
+
+    /// Set the identity provider that signed the identity object.
+    pub fn identity_provider(&mut self, value: IdentityProvider) -> &mut Self {
+        self.identity_provider = value;
+        self
+    }
+
+    /// Set the time at which the credential was created.
+    pub fn created_at(&mut self, value: Timestamp) -> &mut Self {
+        self.created_at = value;
+        self
+    }
+
+    /// Set the time at which the credential is no longer valid.
+    pub fn valid_to(&mut self, value: Timestamp) -> &mut Self {
+        self.valid_to = value;
+        self
+    }
+
+    /// Set an attribute to a raw value, replacing any existing value for the
+    /// same tag. The value must be at most 31 bytes.
+    pub fn attribute(&mut self, tag: AttributeTag, value: &[u8]) -> &mut Self {
+        let len = value.len();
+        if len > 31 {
+            fail!("Attribute values are at most 31 bytes, but the value has {} bytes.", len);
+        }
+        let mut inner = [0u8; 32];
+        inner[0] = len as u8;
+        inner[1..=len].copy_from_slice(value);
+        // Safe since the first byte is the length of the value, which is at
+        // most 31.
+        let value = unsafe { AttributeValue::new_unchecked(inner) };
+        if let Some(item) = self.items.iter_mut().find(|item| item.0 == tag) {
+            item.1 = value;
+        } else {
+            self.items.push((tag, value));
+        }
+        self
+    }
+
+    /// Set the first name.
+    pub fn first_name(&mut self, value: &str) -> &mut Self {
+        self.attribute(attributes::FIRST_NAME, value.as_bytes())
+    }
+
+    /// Set the last name.
+    pub fn last_name(&mut self, value: &str) -> &mut Self {
+        self.attribute(attributes::LAST_NAME, value.as_bytes())
+    }
+
+    /// Set the sex as an ISO/IEC 5218 code, i.e., `"0"` (not known), `"1"`
+    /// (male), `"2"` (female), or `"9"` (not applicable).
+    pub fn sex(&mut self, value: &str) -> &mut Self {
+        self.attribute(attributes::SEX, value.as_bytes())
+    }
+
+    /// Set the date of birth.
+    pub fn date_of_birth(&mut self, value: Date) -> &mut Self {
+        self.attribute(attributes::DOB, &value.to_attribute_bytes())
+    }
+
+    /// Set the date of birth such that the holder is `age` years old at the
+    /// given timestamp. If the birthday would be on the 29th of February in a
+    /// year that is not a leap year, the 28th is used instead.
+    pub fn date_of_birth_for_age(&mut self, age: u16, at: Timestamp) -> &mut Self {
+        let now = match Date::from_timestamp(at) {
+            Some(now) => now,
+            None => fail!("The timestamp is too far in the future to compute a date of birth."),
+        };
+        let year = match now.year.checked_sub(age) {
+            Some(year) => year,
+            None => fail!("The age {} is too high for a date of birth.", age),
+        };
+        // The only date that can be invalid in another year is the 29th of
+        // February.
+        let date_of_birth = Date::new(year, now.month, now.day).unwrap_or(Date {
+            year,
+            month: 2,
+            day: 28,
+        });
+        self.date_of_birth(date_of_birth)
+    }
+
+    /// Set the country of residence.
+    pub fn country_of_residence(&mut self, value: [u8; 2]) -> &mut Self {
+        self.attribute(attributes::COUNTRY_OF_RESIDENCE, &value)
+    }
+
+    /// Set the nationality.
+    pub fn nationality(&mut self, value: [u8; 2]) -> &mut Self {
+        self.attribute(attributes::NATIONALITY, &value)
+    }
+
+    /// Set the type of the identity document, i.e., `"0"` (not applicable),
+    /// `"1"` (passport), `"2"` (national ID card), `"3"` (driving license), or
+    /// `"4"` (immigration card).
+    pub fn id_doc_type(&mut self, value: &str) -> &mut Self {
+        self.attribute(attributes::ID_DOC_TYPE, value.as_bytes())
+    }
+
+    /// Set the number of the identity document.
+    pub fn id_doc_number(&mut self, value: &str) -> &mut Self {
+        self.attribute(attributes::ID_DOC_NUMBER, value.as_bytes())
+    }
+
+    /// Set the issuer of the identity document, as an ISO 3166-1 alpha-2 or
+    /// ISO 3166-2 code.
+    pub fn id_doc_issuer(&mut self, value: &str) -> &mut Self {
+        self.attribute(attributes::ID_DOC_ISSUER, value.as_bytes())
+    }
+
+    /// Set the date the identity document was issued.
+    pub fn id_doc_issued_at(&mut self, value: Date) -> &mut Self {
+        self.attribute(attributes::ID_DOC_ISSUED_AT, &value.to_attribute_bytes())
+    }
+
+    /// Set the date the identity document expires.
+    pub fn id_doc_expires_at(&mut self, value: Date) -> &mut Self {
+        self.attribute(attributes::ID_DOC_EXPIRES_AT, &value.to_attribute_bytes())
+    }
+
+    /// Set the national ID number.
+    pub fn national_id_no(&mut self, value: &str) -> &mut Self {
+        self.attribute(attributes::NATIONAL_ID_NO, value.as_bytes())
+    }
+
+    /// Set the tax ID number.
+    pub fn tax_id_no(&mut self, value: &str) -> &mut Self {
+        self.attribute(attributes::TAX_ID_NO, value.as_bytes())
+    }
+
+    /// Construct the policy. The attributes are in the order they were first
+    /// set.
+    pub fn build(&self) -> TestPolicy {
+        TestPolicy::from(Policy {
+            identity_provider: self.identity_provider,
+            created_at:        self.created_at,
+            valid_to:          self.valid_to,
+            items:             self.items.clone(),
+        })
+    }
+}
+
 // Error handling when unwrapping
 fn unwrap_ctx_field<A>(opt: Option<A>, name: &str) -> A {
     match opt {
@@ -755,12 +982,10 @@ impl TestStateBuilder {
 
 /// A closure used in tests for mocking calls to
 /// [`HasCryptoPrimitives::verify_ed25519_signature`].
-#[cfg(not(feature = "crypto-primitives"))]
 type MockFnVerifyEd25519 = Box<dyn FnMut(PublicKeyEd25519, SignatureEd25519, &[u8]) -> bool>;
 
 /// A closure used in tests for mocking calls to
 /// [`HasCryptoPrimitives::verify_ecdsa_secp256k1_signature`].
-#[cfg(not(feature = "crypto-primitives"))]
 type MockFnEcdsaSecp256k1 =
     Box<dyn FnMut(PublicKeyEcdsaSecp256k1, SignatureEcdsaSecp256k1, [u8; 32]) -> bool>;
 
@@ -768,9 +993,19 @@ type MockFnEcdsaSecp256k1 =
 /// [`HasCryptoPrimitives::hash_sha2_256`],
 /// [`HasCryptoPrimitives::hash_sha3_256`], or [`HasCryptoPrimitives::
 /// hash_keccak_256`].
-#[cfg(not(feature = "crypto-primitives"))]
 type MockFnHash<T> = Box<dyn FnMut(&[u8]) -> T>;
 
+/// Determines what [`TestCryptoPrimitives`] does when a method is called for
+/// which no mock has been set up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CryptoPrimitivesMode {
+    /// Fail the test.
+    Mocked,
+    /// Use the actual implementation of the primitive.
+    #[cfg(feature = "crypto-primitives")]
+    Real,
+}
+
 /// A [`HasCryptoPrimitives`] implementation used for unit testing smart
 /// contracts.
 ///
@@ -778,23 +1013,37 @@ type MockFnHash<T> = Box<dyn FnMut(&[u8]) -> T>;
 /// two different ways:
 ///
 /// 1. By setting up mock responses for the functions you need, for example with
-/// the [`setup_hash_sha_256_mock`](Self::setup_hash_sha2_256_mock) method.
+/// the [`setup_hash_sha2_256_mock`](Self::setup_hash_sha2_256_mock) method.
+/// Use [`TestCryptoPrimitives::mocked`] to get an instance where calling a
+/// method without a mock fails the test.
 /// 2. Or, by using the actual implementations. For this, you need to enable the
-/// "crypto-primitives" feature.
+/// "crypto-primitives" feature and use [`TestCryptoPrimitives::real`]. Mocks
+/// that are set up take precedence over the actual implementations.
+///
+/// In both cases the inputs of every call are recorded and can be inspected
+/// afterwards, e.g., with
+/// [`verify_ed25519_signature_calls`](Self::verify_ed25519_signature_calls).
+///
+/// With the "crypto-primitives" feature, the [`TestKeyPairEd25519`] and
+/// [`TestKeyPairEcdsaSecp256k1`] types can be used to produce valid signatures
+/// inside tests.
 pub struct TestCryptoPrimitives {
-    #[cfg(not(feature = "crypto-primitives"))]
-    verify_ed25519_signature_mock:         RefCell<Option<MockFnVerifyEd25519>>,
-    #[cfg(not(feature = "crypto-primitives"))]
+    mode: CryptoPrimitivesMode,
+    verify_ed25519_signature_mock: RefCell<Option<MockFnVerifyEd25519>>,
     verify_ecdsa_secp256k1_signature_mock: RefCell<Option<MockFnEcdsaSecp256k1>>,
-    #[cfg(not(feature = "crypto-primitives"))]
-    hash_sha2_256_mock:                    RefCell<Option<MockFnHash<HashSha2256>>>,
-    #[cfg(not(feature = "crypto-primitives"))]
-    hash_sha3_256_mock:                    RefCell<Option<MockFnHash<HashSha3256>>>,
-    #[cfg(not(feature = "crypto-primitives"))]
-    hash_keccak_256_mock:                  RefCell<Option<MockFnHash<HashKeccak256>>>,
+    hash_sha2_256_mock: RefCell<Option<MockFnHash<HashSha2256>>>,
+    hash_sha3_256_mock: RefCell<Option<MockFnHash<HashSha3256>>>,
+    hash_keccak_256_mock: RefCell<Option<MockFnHash<HashKeccak256>>>,
+    verify_ed25519_signature_calls: RefCell<Vec<(PublicKeyEd25519, SignatureEd25519, Vec<u8>)>>,
+    verify_ecdsa_secp256k1_signature_calls:
+        RefCell<Vec<(PublicKeyEcdsaSecp256k1, SignatureEcdsaSecp256k1, [u8; 32])>>,
+    hash_sha2_256_calls: RefCell<Vec<Vec<u8>>>,
+    hash_sha3_256_calls: RefCell<Vec<Vec<u8>>>,
+    hash_keccak_256_calls: RefCell<Vec<Vec<u8>>>,
 }
 
 /// Create a new [`TestCryptoPrimitives`], for which no mocks has been set up.
+/// See [`TestCryptoPrimitives::new`].
 impl Default for TestCryptoPrimitives {
     fn default() -> Self { Self::new() }
 }
@@ -802,25 +1051,50 @@ impl Default for TestCryptoPrimitives {
 impl TestCryptoPrimitives {
     /// Create a new [`TestCryptoPrimitives`], for which no mocks has been set
     /// up.
+    ///
+    /// This is [`real`](Self::real) if the "crypto-primitives" feature is
+    /// enabled and [`mocked`](Self::mocked) otherwise. Prefer using one of
+    /// those directly to make the behaviour of the test explicit.
     pub fn new() -> Self {
+        #[cfg(feature = "crypto-primitives")]
+        return Self::real();
         #[cfg(not(feature = "crypto-primitives"))]
-        return Self {
-            verify_ed25519_signature_mock:         RefCell::new(None),
+        Self::mocked()
+    }
+
+    /// Create a new [`TestCryptoPrimitives`] where every method must be mocked
+    /// before it is used. Calling a method for which no mock has been set up
+    /// fails the test.
+    pub fn mocked() -> Self { Self::with_mode(CryptoPrimitivesMode::Mocked) }
+
+    /// Create a new [`TestCryptoPrimitives`] that uses the actual
+    /// implementations of the cryptographic primitives, unless a mock has been
+    /// set up for the method.
+    ///
+    /// This is only available if the "crypto-primitives" feature is enabled.
+    #[cfg(feature = "crypto-primitives")]
+    pub fn real() -> Self { Self::with_mode(CryptoPrimitivesMode::Real) }
+
+    fn with_mode(mode: CryptoPrimitivesMode) -> Self {
+        Self {
+            mode,
+            verify_ed25519_signature_mock: RefCell::new(None),
             verify_ecdsa_secp256k1_signature_mock: RefCell::new(None),
-            hash_sha2_256_mock:                    RefCell::new(None),
-            hash_sha3_256_mock:                    RefCell::new(None),
-            hash_keccak_256_mock:                  RefCell::new(None),
-        };
-        #[cfg(feature = "crypto-primitives")]
-        Self {}
+            hash_sha2_256_mock: RefCell::new(None),
+            hash_sha3_256_mock: RefCell::new(None),
+            hash_keccak_256_mock: RefCell::new(None),
+            verify_ed25519_signature_calls: RefCell::new(Vec::new()),
+            verify_ecdsa_secp256k1_signature_calls: RefCell::new(Vec::new()),
+            hash_sha2_256_calls: RefCell::new(Vec::new()),
+            hash_sha3_256_calls: RefCell::new(Vec::new()),
+            hash_keccak_256_calls: RefCell::new(Vec::new()),
+        }
     }
 
-    #[cfg(not(feature = "crypto-primitives"))]
     /// Set up a mock for [`verify_ed25519_signature`][link].
     ///
-    /// This is not available if the "crypto-primitives" feature is enabled. For
-    /// more information on why, see the documentation of
-    /// [`TestCryptoPrimitives`].
+    /// The mock takes precedence over the actual implementation used by
+    /// [`TestCryptoPrimitives::real`].
     ///
     /// [link]: HasCryptoPrimitives::verify_ed25519_signature
     pub fn setup_verify_ed25519_signature_mock<F>(&self, mock: F)
@@ -829,12 +1103,10 @@ impl TestCryptoPrimitives {
         *self.verify_ed25519_signature_mock.borrow_mut() = Some(Box::new(mock));
     }
 
-    #[cfg(not(feature = "crypto-primitives"))]
     /// Set up a mock for [`verify_ecdsa_secp256k1_signature`][link].
     ///
-    /// This is not available if the "crypto-primitives" feature is enabled. For
-    /// more information on why, see the documentation of
-    /// [`TestCryptoPrimitives`].
+    /// The mock takes precedence over the actual implementation used by
+    /// [`TestCryptoPrimitives::real`].
     ///
     /// [link]: HasCryptoPrimitives::verify_ecdsa_secp256k1_signature
     pub fn setup_verify_ecdsa_secp256k1_signature_mock<F>(&self, mock: F)
@@ -844,53 +1116,81 @@ impl TestCryptoPrimitives {
         *self.verify_ecdsa_secp256k1_signature_mock.borrow_mut() = Some(Box::new(mock));
     }
 
-    #[cfg(not(feature = "crypto-primitives"))]
     /// Set up a mock for
     /// [`hash_sha2_256`](HasCryptoPrimitives::hash_sha2_256).
     ///
-    /// This is not available if the "crypto-primitives" feature is enabled. For
-    /// more information on why, see the documentation of
-    /// [`TestCryptoPrimitives`].
+    /// The mock takes precedence over the actual implementation used by
+    /// [`TestCryptoPrimitives::real`].
     pub fn setup_hash_sha2_256_mock<F>(&self, mock: F)
     where
         F: FnMut(&[u8]) -> HashSha2256 + 'static, {
         *self.hash_sha2_256_mock.borrow_mut() = Some(Box::new(mock));
     }
 
-    #[cfg(not(feature = "crypto-primitives"))]
     /// Set up a mock for
     /// [`hash_sha3_256`](HasCryptoPrimitives::hash_sha3_256).
     ///
-    /// This is not available if the "crypto-primitives" feature is enabled. For
-    /// more information on why, see the documentation of
-    /// [`TestCryptoPrimitives`].
+    /// The mock takes precedence over the actual implementation used by
+    /// [`TestCryptoPrimitives::real`].
     pub fn setup_hash_sha3_256_mock<F>(&self, mock: F)
     where
         F: FnMut(&[u8]) -> HashSha3256 + 'static, {
         *self.hash_sha3_256_mock.borrow_mut() = Some(Box::new(mock));
     }
 
-    #[cfg(not(feature = "crypto-primitives"))]
     /// Set up a mock for
     /// [`hash_keccak_256`](HasCryptoPrimitives::hash_keccak_256).
     ///
-    /// This is not available if the "crypto-primitives" feature is enabled. For
-    /// more information on why, see the documentation of
-    /// [`TestCryptoPrimitives`].
+    /// The mock takes precedence over the actual implementation used by
+    /// [`TestCryptoPrimitives::real`].
     pub fn setup_hash_keccak_256_mock<F>(&self, mock: F)
     where
         F: FnMut(&[u8]) -> HashKeccak256 + 'static, {
         *self.hash_keccak_256_mock.borrow_mut() = Some(Box::new(mock));
     }
 
+    /// Get the inputs of all calls to
+    /// [`verify_ed25519_signature`](HasCryptoPrimitives::verify_ed25519_signature)
+    /// in the order they were made.
+    pub fn verify_ed25519_signature_calls(
+        &self,
+    ) -> Vec<(PublicKeyEd25519, SignatureEd25519, Vec<u8>)> {
+        self.verify_ed25519_signature_calls.borrow().clone()
+    }
+
+    /// Get the inputs of all calls to
+    /// [`verify_ecdsa_secp256k1_signature`](HasCryptoPrimitives::verify_ecdsa_secp256k1_signature)
+    /// in the order they were made.
+    pub fn verify_ecdsa_secp256k1_signature_calls(
+        &self,
+    ) -> Vec<(PublicKeyEcdsaSecp256k1, SignatureEcdsaSecp256k1, [u8; 32])> {
+        self.verify_ecdsa_secp256k1_signature_calls.borrow().clone()
+    }
+
+    /// Get the data passed to all calls to
+    /// [`hash_sha2_256`](HasCryptoPrimitives::hash_sha2_256) in the order they
+    /// were made.
+    pub fn hash_sha2_256_calls(&self) -> Vec<Vec<u8>> { self.hash_sha2_256_calls.borrow().clone() }
+
+    /// Get the data passed to all calls to
+    /// [`hash_sha3_256`](HasCryptoPrimitives::hash_sha3_256) in the order they
+    /// were made.
+    pub fn hash_sha3_256_calls(&self) -> Vec<Vec<u8>> { self.hash_sha3_256_calls.borrow().clone() }
+
+    /// Get the data passed to all calls to
+    /// [`hash_keccak_256`](HasCryptoPrimitives::hash_keccak_256) in the order
+    /// they were made.
+    pub fn hash_keccak_256_calls(&self) -> Vec<Vec<u8>> {
+        self.hash_keccak_256_calls.borrow().clone()
+    }
+
     /// Fail with an error message that tells you to set up mocks
-    /// OR enable the crypto-primitives feature.
-    #[cfg(not(feature = "crypto-primitives"))]
-    fn fail_with_crypto_primitives_error(method_name: &str) -> ! {
+    /// OR use the actual implementations.
+    fn fail_with_missing_mock_error(method_name: &str) -> ! {
         fail!(
-            "To use {}, you need to either enable the \"concordium-std/crypto-primitives\" \
-             feature, which makes it use an actual implemenation, or set up a mock with the \
-             setup_{}_mock method on TestCryptoPrimitives.",
+            "To use {}, you need to either set up a mock with the setup_{}_mock method on \
+             TestCryptoPrimitives, or construct it with TestCryptoPrimitives::real, which \
+             requires the \"concordium-std/crypto-primitives\" feature.",
             method_name,
             method_name
         )
@@ -904,24 +1204,29 @@ impl HasCryptoPrimitives for TestCryptoPrimitives {
         signature: SignatureEd25519,
         message: &[u8],
     ) -> bool {
-        #[cfg(feature = "crypto-primitives")]
-        {
-            use std::convert::TryFrom;
-            let signature = ed25519_zebra::Signature::try_from(&signature.0[..]);
-            let public_key = ed25519_zebra::VerificationKey::try_from(&public_key.0[..]);
-            match (signature, public_key) {
-                (Ok(ref signature), Ok(public_key)) => {
-                    public_key.verify(signature, message).is_ok()
+        self.verify_ed25519_signature_calls.borrow_mut().push((
+            public_key,
+            signature,
+            message.to_vec(),
+        ));
+        if let Some(ref mut mock) = *self.verify_ed25519_signature_mock.borrow_mut() {
+            return mock(public_key, signature, message);
+        }
+        match self.mode {
+            #[cfg(feature = "crypto-primitives")]
+            CryptoPrimitivesMode::Real => {
+                use std::convert::TryFrom;
+                let signature = ed25519_zebra::Signature::try_from(&signature.0[..]);
+                let public_key = ed25519_zebra::VerificationKey::try_from(&public_key.0[..]);
+                match (signature, public_key) {
+                    (Ok(ref signature), Ok(public_key)) => {
+                        public_key.verify(signature, message).is_ok()
+                    }
+                    _ => false,
                 }
-                _ => false,
             }
-        }
-        #[cfg(not(feature = "crypto-primitives"))]
-        {
-            if let Some(ref mut mock) = *self.verify_ed25519_signature_mock.borrow_mut() {
-                mock(public_key, signature, message)
-            } else {
-                Self::fail_with_crypto_primitives_error("verify_ed25519_signature")
+            CryptoPrimitivesMode::Mocked => {
+                Self::fail_with_missing_mock_error("verify_ed25519_signature")
             }
         }
     }
@@ -932,78 +1237,195 @@ impl HasCryptoPrimitives for TestCryptoPrimitives {
         signature: SignatureEcdsaSecp256k1,
         message_hash: [u8; 32],
     ) -> bool {
-        #[cfg(feature = "crypto-primitives")]
-        {
-            let signature = secp256k1::ecdsa::Signature::from_compact(&signature.0[..]);
-            let public_key = secp256k1::PublicKey::from_slice(&public_key.0[..]);
-            let message_hash = secp256k1::Message::from_slice(&message_hash[..]);
-            match (signature, public_key, message_hash) {
-                (Ok(ref signature), Ok(public_key), Ok(message_hash)) => {
-                    let verifier = secp256k1::Secp256k1::verification_only();
-                    verifier.verify_ecdsa(&message_hash, signature, &public_key).is_ok()
+        self.verify_ecdsa_secp256k1_signature_calls.borrow_mut().push((
+            public_key,
+            signature,
+            message_hash,
+        ));
+        if let Some(ref mut mock) = *self.verify_ecdsa_secp256k1_signature_mock.borrow_mut() {
+            return mock(public_key, signature, message_hash);
+        }
+        match self.mode {
+            #[cfg(feature = "crypto-primitives")]
+            CryptoPrimitivesMode::Real => {
+                let signature = secp256k1::ecdsa::Signature::from_compact(&signature.0[..]);
+                let public_key = secp256k1::PublicKey::from_slice(&public_key.0[..]);
+                let message_hash = secp256k1::Message::from_slice(&message_hash[..]);
+                match (signature, public_key, message_hash) {
+                    (Ok(ref signature), Ok(public_key), Ok(message_hash)) => {
+                        let verifier = secp256k1::Secp256k1::verification_only();
+                        verifier.verify_ecdsa(&message_hash, signature, &public_key).is_ok()
+                    }
+                    _ => false,
                 }
-                _ => false,
             }
-        }
-        #[cfg(not(feature = "crypto-primitives"))]
-        {
-            if let Some(ref mut mock) = *self.verify_ecdsa_secp256k1_signature_mock.borrow_mut() {
-                mock(public_key, signature, message_hash)
-            } else {
-                Self::fail_with_crypto_primitives_error("verify_ecdsa_secp256k1")
+            CryptoPrimitivesMode::Mocked => {
+                Self::fail_with_missing_mock_error("verify_ecdsa_secp256k1_signature")
             }
         }
     }
 
     fn hash_sha2_256(&self, data: &[u8]) -> HashSha2256 {
-        #[cfg(feature = "crypto-primitives")]
-        {
-            use sha2::Digest;
-            HashSha2256(sha2::Sha256::digest(data).into())
+        self.hash_sha2_256_calls.borrow_mut().push(data.to_vec());
+        if let Some(ref mut mock) = *self.hash_sha2_256_mock.borrow_mut() {
+            return mock(data);
         }
-        #[cfg(not(feature = "crypto-primitives"))]
-        {
-            if let Some(ref mut mock) = *self.hash_sha2_256_mock.borrow_mut() {
-                mock(data)
-            } else {
-                Self::fail_with_crypto_primitives_error("hash_sha2_256")
+        match self.mode {
+            #[cfg(feature = "crypto-primitives")]
+            CryptoPrimitivesMode::Real => {
+                use sha2::Digest;
+                HashSha2256(sha2::Sha256::digest(data).into())
             }
+            CryptoPrimitivesMode::Mocked => Self::fail_with_missing_mock_error("hash_sha2_256"),
         }
     }
 
     fn hash_sha3_256(&self, data: &[u8]) -> HashSha3256 {
-        #[cfg(feature = "crypto-primitives")]
-        {
-            use sha3::Digest;
-            HashSha3256(sha3::Sha3_256::digest(data).into())
+        self.hash_sha3_256_calls.borrow_mut().push(data.to_vec());
+        if let Some(ref mut mock) = *self.hash_sha3_256_mock.borrow_mut() {
+            return mock(data);
         }
-        #[cfg(not(feature = "crypto-primitives"))]
-        {
-            if let Some(ref mut mock) = *self.hash_sha3_256_mock.borrow_mut() {
-                mock(data)
-            } else {
-                Self::fail_with_crypto_primitives_error("hash_sha3_256")
+        match self.mode {
+            #[cfg(feature = "crypto-primitives")]
+            CryptoPrimitivesMode::Real => {
+                use sha3::Digest;
+                HashSha3256(sha3::Sha3_256::digest(data).into())
             }
+            CryptoPrimitivesMode::Mocked => Self::fail_with_missing_mock_error("hash_sha3_256"),
         }
     }
 
     fn hash_keccak_256(&self, data: &[u8]) -> HashKeccak256 {
-        #[cfg(feature = "crypto-primitives")]
-        {
-            use sha3::Digest;
-            HashKeccak256(sha3::Keccak256::digest(data).into())
+        self.hash_keccak_256_calls.borrow_mut().push(data.to_vec());
+        if let Some(ref mut mock) = *self.hash_keccak_256_mock.borrow_mut() {
+            return mock(data);
         }
-        #[cfg(not(feature = "crypto-primitives"))]
-        {
-            if let Some(ref mut mock) = *self.hash_keccak_256_mock.borrow_mut() {
-                mock(data)
-            } else {
-                Self::fail_with_crypto_primitives_error("hash_keccak_256")
+        match self.mode {
+            #[cfg(feature = "crypto-primitives")]
+            CryptoPrimitivesMode::Real => {
+                use sha3::Digest;
+                HashKeccak256(sha3::Keccak256::digest(data).into())
             }
+            CryptoPrimitivesMode::Mocked => Self::fail_with_missing_mock_error("hash_keccak_256"),
         }
     }
 }
 
+/// Derive 32 bytes of secret key material deterministically from a seed.
+/// The `domain` separates the keys of different signature schemes, and the
+/// `counter` allows retrying when the bytes are not a valid key.
+#[cfg(feature = "crypto-primitives")]
+fn test_secret_key_bytes(domain: &[u8], seed: u64, counter: u32) -> [u8; 32] {
+    use sha2::Digest;
+    let mut hasher = sha2::Sha256::new();
+    hasher.update(b"concordium-std test key");
+    hasher.update(domain);
+    hasher.update(seed.to_le_bytes());
+    hasher.update(counter.to_le_bytes());
+    hasher.finalize().into()
+}
+
+/// An Ed25519 key pair for signing messages in tests.
+///
+/// The key pair is derived deterministically from a seed, so tests using it
+/// are reproducible. The secret key is **not** secret in any way and must never
+/// be used outside of tests.
+///
+/// This is only available if the "crypto-primitives" feature is enabled.
+///
+/// ```rust
+/// # use concordium_std::{*, test_infrastructure::*};
+/// let key_pair = TestKeyPairEd25519::from_seed(0);
+/// let signature = key_pair.sign(b"Concordium");
+/// let crypto_primitives = TestCryptoPrimitives::real();
+/// assert!(crypto_primitives.verify_ed25519_signature(
+///     key_pair.public_key(),
+///     signature,
+///     b"Concordium"
+/// ));
+/// ```
+#[cfg(feature = "crypto-primitives")]
+#[derive(Clone, Copy)]
+pub struct TestKeyPairEd25519 {
+    secret_key: [u8; 32],
+    public_key: PublicKeyEd25519,
+}
+
+#[cfg(feature = "crypto-primitives")]
+impl TestKeyPairEd25519 {
+    /// Derive a key pair from the seed. The same seed always gives the same
+    /// key pair.
+    pub fn from_seed(seed: u64) -> Self {
+        let secret_key = test_secret_key_bytes(b"ed25519", seed, 0);
+        let signing_key = ed25519_zebra::SigningKey::from(secret_key);
+        let public_key = ed25519_zebra::VerificationKey::from(&signing_key);
+        Self {
+            secret_key,
+            public_key: PublicKeyEd25519(public_key.into()),
+        }
+    }
+
+    /// The public key of the key pair.
+    pub fn public_key(&self) -> PublicKeyEd25519 { self.public_key }
+
+    /// Sign a message with the secret key of the key pair.
+    pub fn sign(&self, message: &[u8]) -> SignatureEd25519 {
+        let signing_key = ed25519_zebra::SigningKey::from(self.secret_key);
+        SignatureEd25519(signing_key.sign(message).into())
+    }
+}
+
+/// An ECDSA key pair over Secp256k1 for signing message hashes in tests.
+///
+/// The key pair is derived deterministically from a seed, so tests using it
+/// are reproducible. The secret key is **not** secret in any way and must never
+/// be used outside of tests.
+///
+/// This is only available if the "crypto-primitives" feature is enabled.
+#[cfg(feature = "crypto-primitives")]
+#[derive(Clone, Copy)]
+pub struct TestKeyPairEcdsaSecp256k1 {
+    secret_key: secp256k1::SecretKey,
+    public_key: PublicKeyEcdsaSecp256k1,
+}
+
+#[cfg(feature = "crypto-primitives")]
+impl TestKeyPairEcdsaSecp256k1 {
+    /// Derive a key pair from the seed. The same seed always gives the same
+    /// key pair.
+    pub fn from_seed(seed: u64) -> Self {
+        let mut counter = 0;
+        // Almost all 32 byte strings are valid secret keys, so this loop
+        // terminates after the first iteration with overwhelming probability.
+        let secret_key = loop {
+            let bytes = test_secret_key_bytes(b"ecdsa_secp256k1", seed, counter);
+            if let Ok(secret_key) = secp256k1::SecretKey::from_slice(&bytes) {
+                break secret_key;
+            }
+            counter += 1;
+        };
+        let signer = secp256k1::Secp256k1::signing_only();
+        let public_key = secp256k1::PublicKey::from_secret_key(&signer, &secret_key);
+        Self {
+            secret_key,
+            public_key: PublicKeyEcdsaSecp256k1(public_key.serialize()),
+        }
+    }
+
+    /// The public key of the key pair.
+    pub fn public_key(&self) -> PublicKeyEcdsaSecp256k1 { self.public_key }
+
+    /// Sign a message hash with the secret key of the key pair. The result is
+    /// in the compact format expected by
+    /// [`verify_ecdsa_secp256k1_signature`](HasCryptoPrimitives::verify_ecdsa_secp256k1_signature).
+    pub fn sign(&self, message_hash: [u8; 32]) -> SignatureEcdsaSecp256k1 {
+        let signer = secp256k1::Secp256k1::signing_only();
+        let message = secp256k1::Message::from_slice(&message_hash)
+            .expect("A 32 byte hash is always a valid message.");
+        SignatureEcdsaSecp256k1(signer.sign_ecdsa(&message, &self.secret_key).serialize_compact())
+    }
+}
+
 impl HasStateEntry for TestStateEntry {
     type Error = TestStateError;
     type StateEntryData = Rc<RefCell<TestStateEntryData>>;
@@ -1209,6 +1631,11 @@ impl<State> MockFn<State> {
                             return_value: Cursor::new(to_bytes(&return_value)),
                         }),
                         CallContractError::Trap => Err(CallContractError::Trap),
+                        CallContractError::InvalidReturnValue {
+                            reason,
+                        } => Err(CallContractError::InvalidReturnValue {
+                            reason,
+                        }),
                     },
                 }
             },
@@ -1298,6 +1725,15 @@ pub struct TestHost<State> {
     missing_accounts: BTreeSet<AccountAddress>,
 }
 
+impl<State> HasViewHost<State> for TestHost<State> {
+    /// Get an immutable reference to the contract state.
+    fn state(&self) -> &State { &self.state }
+
+    /// Get the contract balance.
+    /// This can be set with `set_self_balance` and defaults to 0.
+    fn self_balance(&self) -> Amount { *self.contract_balance.borrow() }
+}
+
 impl<State: Serial + DeserialWithState<TestStateApi> + StateClone<TestStateApi>> HasHost<State>
     for TestHost<State>
 {
@@ -1472,16 +1908,9 @@ impl<State: Serial + DeserialWithState<TestStateApi> + StateClone<TestStateApi>>
             .expect_report("commit_state: Cannot truncate state. Entry was deleted.");
     }
 
-    /// Get an immutable reference to the contract state.
-    fn state(&self) -> &State { &self.state }
-
     /// Get a mutable reference to the contract state.
     fn state_mut(&mut self) -> &mut State { &mut self.state }
 
-    /// Get the contract balance.
-    /// This can be set with `set_self_balance` and defaults to 0.
-    fn self_balance(&self) -> Amount { *self.contract_balance.borrow() }
-
     /// Get the state builder.
     fn state_builder(&mut self) -> &mut StateBuilder<Self::StateApiType> { &mut self.state_builder }
 
@@ -1632,17 +2061,281 @@ impl<State: StateClone<TestStateApi>> TestHost<State> {
     }
 }
 
+/// Check that the [`Serial`] and [`Deserial`] implementations of a type agree
+/// with its [`SchemaType`](schema::SchemaType), panicking with a description
+/// of the first disagreement found.
+///
+/// The check generates random values from the schema of the type and, for
+/// each value that the type deserializes,
+/// - checks that deserializing consumes exactly the bytes of the value,
+/// - checks that the serialization of the deserialized value is decoded by the
+///   schema, using [`schema_json::decode`](crate::schema_json::decode), and
+/// - checks that it is the same as the generated value, unless the schema
+///   contains sets or maps, whose elements the type may reorder or deduplicate.
+///
+/// Values that the type fails to deserialize are skipped, since a type can
+/// restrict the values allowed by its schema, but the check fails if all the
+/// values are skipped. The values are generated from a fixed seed, so the
+/// check is reproducible.
+///
+/// Derived schemas can be checked with `#[concordium(check_schema)]` on
+/// [`derive(SchemaType)`](derive.SchemaType.html) instead.
+///
+/// This is only available if the "schema-json" feature is enabled.
+///
+/// ```rust
+/// # use concordium_std::{*, test_infrastructure::*};
+/// check_schema::<Option<(u32, AccountAddress)>>();
+/// ```
+#[cfg(feature = "schema-json")]
+pub fn check_schema<T: schema::SchemaType + Serial + Deserial>() { check_schema_samples::<T>(256) }
+
+/// Like [`check_schema`], but with the given number of random values.
+#[cfg(feature = "schema-json")]
+pub fn check_schema_samples<T: schema::SchemaType + Serial + Deserial>(samples: usize) {
+    let ty = T::get_type();
+    let type_name = core::any::type_name::<T>();
+    let mut rng = SchemaRng(0x636f_6e63_6f72_6469);
+    let mut deserialized = 0;
+    for _ in 0..samples {
+        let mut bytes = Vec::new();
+        random_schema_value(&ty, &mut rng, &mut bytes);
+        let value = crate::schema_json::decode(&ty, &bytes).unwrap_or_else(|e| {
+            panic!("The value generated from the schema of {} is invalid: {}", type_name, e)
+        });
+        let mut cursor = Cursor::new(&bytes[..]);
+        let t = match T::deserial(&mut cursor) {
+            Ok(t) => t,
+            Err(_) => continue,
+        };
+        if cursor.offset != bytes.len() {
+            panic!(
+                "Deserializing {} from {} consumed {} of its {} bytes.",
+                type_name,
+                value,
+                cursor.offset,
+                bytes.len()
+            );
+        }
+        let serialized = to_bytes(&t);
+        let reserialized = crate::schema_json::decode(&ty, &serialized).unwrap_or_else(|e| {
+            panic!(
+                "The serialization of {} deserialized from {} does not match its schema: {}",
+                type_name, value, e
+            )
+        });
+        if serialized != bytes && !schema_has_collections(&ty) {
+            panic!(
+                "Deserializing {} from {} and serializing it again gives {}.",
+                type_name, value, reserialized
+            );
+        }
+        deserialized += 1;
+    }
+    if samples > 0 && deserialized == 0 {
+        panic!(
+            "{} failed to deserialize all {} values generated from its schema.",
+            type_name, samples
+        );
+    }
+}
+
+/// The pseudo-random number generator used by [`check_schema`], which is
+/// SplitMix64.
+#[cfg(feature = "schema-json")]
+struct SchemaRng(u64);
+
+#[cfg(feature = "schema-json")]
+impl SchemaRng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in the range `0..n`, where `n` must not be zero.
+    fn below(&mut self, n: u64) -> u64 { self.next_u64() % n }
+
+    /// Random bytes, which are all zeros or all ones with a small probability
+    /// to cover the extreme values of integers.
+    fn bytes(&mut self, n: usize, out: &mut Vec<u8>) {
+        match self.below(8) {
+            0 => out.resize(out.len() + n, 0),
+            1 => out.resize(out.len() + n, 0xff),
+            _ => out.extend((0..n).map(|_| self.next_u64() as u8)),
+        }
+    }
+
+    /// A short string of ASCII letters and digits.
+    fn name(&mut self) -> String {
+        const CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+        let length = 1 + self.below(8);
+        (0..length).map(|_| char::from(CHARS[self.below(CHARS.len() as u64) as usize])).collect()
+    }
+}
+
+/// Whether the schema contains sets or maps.
+#[cfg(feature = "schema-json")]
+fn schema_has_collections(ty: &schema::Type) -> bool {
+    use schema::{Fields, Type};
+    let fields_have_collections = |fields: &Fields| match fields {
+        Fields::Named(fields) => fields.iter().any(|(_, ty)| schema_has_collections(ty)),
+        Fields::Unnamed(fields) => fields.iter().any(schema_has_collections),
+        Fields::None => false,
+    };
+    match ty {
+        Type::Set(..) | Type::Map(..) => true,
+        Type::Pair(first, second) => {
+            schema_has_collections(first) || schema_has_collections(second)
+        }
+        Type::List(_, item) | Type::Array(_, item) => schema_has_collections(item),
+        Type::Struct(fields) => fields_have_collections(fields),
+        Type::Enum(variants) => variants.iter().any(|(_, fields)| fields_have_collections(fields)),
+        Type::TaggedEnum(variants) => {
+            variants.values().any(|(_, fields)| fields_have_collections(fields))
+        }
+        _ => false,
+    }
+}
+
+/// Append the serialization of a random value of the schema to `out`.
+#[cfg(feature = "schema-json")]
+fn random_schema_value(ty: &schema::Type, rng: &mut SchemaRng, out: &mut Vec<u8>) {
+    use crate::schema_json::{encode_into, enum_tag_bytes, size_length_bytes};
+    use schema::{Fields, SizeLength, Type};
+    let write_length = |size_length: &SizeLength, length: usize, out: &mut Vec<u8>| {
+        out.extend_from_slice(&(length as u64).to_le_bytes()[..size_length_bytes(size_length)])
+    };
+    let random_fields = |fields: &Fields, rng: &mut SchemaRng, out: &mut Vec<u8>| match fields {
+        Fields::Named(fields) => {
+            fields.iter().for_each(|(_, ty)| random_schema_value(ty, rng, out))
+        }
+        Fields::Unnamed(fields) => fields.iter().for_each(|ty| random_schema_value(ty, rng, out)),
+        Fields::None => (),
+    };
+    match ty {
+        Type::Unit => (),
+        Type::Bool => out.push(rng.below(2) as u8),
+        Type::U8 | Type::I8 => rng.bytes(1, out),
+        Type::U16 | Type::I16 => rng.bytes(2, out),
+        Type::U32 | Type::I32 => rng.bytes(4, out),
+        Type::U64 | Type::I64 | Type::Amount | Type::Duration => rng.bytes(8, out),
+        Type::U128 | Type::I128 => rng.bytes(16, out),
+        Type::AccountAddress => rng.bytes(32, out),
+        Type::ContractAddress => rng.bytes(16, out),
+        // Timestamps before the year 10000, which can be represented in RFC 3339.
+        Type::Timestamp => out.extend_from_slice(&rng.below(253_402_300_800_000).to_le_bytes()),
+        Type::Pair(first, second) => {
+            random_schema_value(first, rng, out);
+            random_schema_value(second, rng, out);
+        }
+        Type::List(size_length, item) | Type::Set(size_length, item) => {
+            let length = rng.below(5) as usize;
+            write_length(size_length, length, out);
+            for _ in 0..length {
+                random_schema_value(item, rng, out);
+            }
+        }
+        Type::Map(size_length, key, value) => {
+            let length = rng.below(5) as usize;
+            write_length(size_length, length, out);
+            for _ in 0..length {
+                random_schema_value(key, rng, out);
+                random_schema_value(value, rng, out);
+            }
+        }
+        Type::Array(length, item) => {
+            for _ in 0..*length {
+                random_schema_value(item, rng, out);
+            }
+        }
+        Type::Struct(fields) => random_fields(fields, rng, out),
+        Type::Enum(variants) => {
+            // Enums without variants have no values.
+            if variants.is_empty() {
+                return;
+            }
+            let tag = rng.below(variants.len() as u64) as usize;
+            out.extend_from_slice(&(tag as u32).to_le_bytes()[..enum_tag_bytes(variants.len())]);
+            random_fields(&variants[tag].1, rng, out);
+        }
+        Type::TaggedEnum(variants) => {
+            if let Some((tag, (_, fields))) =
+                variants.iter().nth(rng.below(variants.len().max(1) as u64) as usize)
+            {
+                out.push(*tag);
+                random_fields(fields, rng, out);
+            }
+        }
+        Type::String(size_length) => {
+            let name = rng.name();
+            write_length(size_length, name.len(), out);
+            out.extend_from_slice(name.as_bytes());
+        }
+        Type::ContractName(size_length) => {
+            let name = format!("init_{}", rng.name());
+            write_length(size_length, name.len(), out);
+            out.extend_from_slice(name.as_bytes());
+        }
+        Type::ReceiveName(size_length) => {
+            let name = format!("{}.{}", rng.name(), rng.name());
+            write_length(size_length, name.len(), out);
+            out.extend_from_slice(name.as_bytes());
+        }
+        Type::ULeb128(_) | Type::ILeb128(_) => {
+            // A random integer of a random number of bits, which is shrunk until
+            // it fits in the maximum number of bytes.
+            let mut n =
+                (u128::from(rng.next_u64()) << 64 | u128::from(rng.next_u64())) >> rng.below(128);
+            let negative = matches!(ty, Type::ILeb128(_)) && rng.below(2) == 0;
+            loop {
+                let value = if negative && n != 0 {
+                    format!("-{}", n)
+                } else {
+                    n.to_string()
+                };
+                if encode_into(ty, &value.into(), out).is_ok() || n == 0 {
+                    break;
+                }
+                n >>= 7;
+            }
+        }
+        Type::ByteList(size_length) => {
+            let length = rng.below(9) as usize;
+            write_length(size_length, length, out);
+            rng.bytes(length, out);
+        }
+        Type::ByteArray(length) => rng.bytes(*length as usize, out),
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::TestStateApi;
+    #[cfg(feature = "crypto-primitives")]
+    use crate::test_infrastructure::{TestKeyPairEcdsaSecp256k1, TestKeyPairEd25519};
     use crate::{
         cell::RefCell,
+        merge_event_types,
         rc::Rc,
-        test_infrastructure::{TestStateBuilder, TestStateEntry},
-        Deletable, DeserialWithState, EntryRaw, HasStateApi, HasStateEntry, StateBox, StateClone,
-        StateMap, StateSet, INITIAL_NEXT_ITEM_PREFIX,
+        test_infrastructure::{
+            MockFn, TestCryptoPrimitives, TestHost, TestLogger, TestPolicyBuilder,
+            TestReceiveContext, TestStateBuilder, TestStateEntry,
+        },
+        CallContractError, ContractInterface, Date, Deletable, DeserialWithState, EntryRaw,
+        ErrorEnvelope, EventTags, HasCommonData, HasCryptoPrimitives, HasHost, HasLogger,
+        HasPolicy, HasStateApi, HasStateEntry, HashSha2256, MergeEventTypesError, PublicKeyEd25519,
+        ReentrancyError, ReentrancyGuard, Reject, Role, RoleEvent, Roles, RolesError,
+        SignatureEd25519, StateBox, StateClone, StateMap, StateSet, INITIAL_NEXT_ITEM_PREFIX,
+    };
+    use concordium_contracts_common::{
+        attributes,
+        schema::{Fields, Type},
+        to_bytes, AccountAddress, Address, Amount, ContractAddress, Cursor, Deserial,
+        EntrypointName, OwnedEntrypointName, Read, Seek, SeekFrom, Timestamp, Write,
     };
-    use concordium_contracts_common::{to_bytes, Cursor, Deserial, Read, Seek, SeekFrom, Write};
 
     #[test]
     // Perform a number of operations from Seek, Read, Write and HasStateApi
@@ -2202,4 +2895,506 @@ mod test {
         assert_eq!(*b2_ref_clone.get().get(), 101010);
         assert_eq!(b2_loaded_clone_cursor_pos, b2_loaded_cursor_pos);
     }
+
+    #[test]
+    /// Test that the inputs of calls are recorded and that mocks are used.
+    fn crypto_primitives_mocked_records_calls() {
+        let crypto_primitives = TestCryptoPrimitives::mocked();
+        crypto_primitives.setup_verify_ed25519_signature_mock(|_, _, message| message == b"ok");
+        crypto_primitives.setup_hash_sha2_256_mock(|data| HashSha2256([data.len() as u8; 32]));
+
+        let public_key = PublicKeyEd25519([1; 32]);
+        let signature = SignatureEd25519([2; 64]);
+        assert!(crypto_primitives.verify_ed25519_signature(public_key, signature, b"ok"));
+        assert!(!crypto_primitives.verify_ed25519_signature(public_key, signature, b"not ok"));
+        assert_eq!(crypto_primitives.hash_sha2_256(&[0; 7]), HashSha2256([7; 32]));
+
+        assert_eq!(crypto_primitives.verify_ed25519_signature_calls(), vec![
+            (public_key, signature, b"ok".to_vec()),
+            (public_key, signature, b"not ok".to_vec())
+        ]);
+        assert_eq!(crypto_primitives.hash_sha2_256_calls(), vec![vec![0; 7]]);
+        assert!(crypto_primitives.hash_sha3_256_calls().is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    /// Test that using a method without a mock fails in mocked mode.
+    fn crypto_primitives_mocked_fails_without_mock() {
+        TestCryptoPrimitives::mocked().hash_keccak_256(&[]);
+    }
+
+    #[test]
+    #[cfg(feature = "crypto-primitives")]
+    /// Test that signatures produced by the test key pairs are accepted by the
+    /// actual implementations, and that the key pairs are deterministic.
+    fn crypto_primitives_real_with_test_key_pairs() {
+        let crypto_primitives = TestCryptoPrimitives::real();
+
+        let ed25519 = TestKeyPairEd25519::from_seed(42);
+        assert_eq!(ed25519.public_key(), TestKeyPairEd25519::from_seed(42).public_key());
+        assert_ne!(ed25519.public_key(), TestKeyPairEd25519::from_seed(43).public_key());
+        let signature = ed25519.sign(b"Concordium");
+        assert!(crypto_primitives.verify_ed25519_signature(
+            ed25519.public_key(),
+            signature,
+            b"Concordium"
+        ));
+        assert!(!crypto_primitives.verify_ed25519_signature(
+            ed25519.public_key(),
+            signature,
+            b"Concordium!"
+        ));
+
+        let secp256k1 = TestKeyPairEcdsaSecp256k1::from_seed(42);
+        let message_hash = crypto_primitives.hash_sha2_256(b"Concordium").0;
+        let signature = secp256k1.sign(message_hash);
+        assert!(crypto_primitives.verify_ecdsa_secp256k1_signature(
+            secp256k1.public_key(),
+            signature,
+            message_hash
+        ));
+        assert!(!crypto_primitives.verify_ecdsa_secp256k1_signature(
+            TestKeyPairEcdsaSecp256k1::from_seed(43).public_key(),
+            signature,
+            message_hash
+        ));
+
+        // Mocks take precedence over the actual implementations.
+        crypto_primitives.setup_verify_ed25519_signature_mock(|_, _, _| false);
+        assert!(!crypto_primitives.verify_ed25519_signature(
+            ed25519.public_key(),
+            ed25519.sign(b"Concordium"),
+            b"Concordium"
+        ));
+    }
+
+    #[test]
+    /// Test that the attributes set with the policy builder are returned
+    /// unchanged by the `HasPolicy` interface.
+    fn policy_builder_round_trip() {
+        let mut ctx = TestReceiveContext::empty();
+        ctx.push_policy(
+            TestPolicyBuilder::new()
+                .identity_provider(3)
+                .country_of_residence(*b"DK")
+                .date_of_birth(Date::new(2000, 2, 29).unwrap())
+                .id_doc_expires_at(Date::new(2031, 12, 1).unwrap())
+                .first_name("John")
+                .country_of_residence(*b"DE")
+                .build(),
+        );
+        let mut policy = ctx.policies().next().expect("There is one policy.");
+        assert_eq!(policy.identity_provider(), 3);
+
+        let mut buf = [0u8; 31];
+        let mut items = Vec::new();
+        while let Some((tag, len)) = policy.next_item(&mut buf) {
+            items.push((tag, buf[..usize::from(len)].to_vec()));
+        }
+        assert_eq!(items, vec![
+            (attributes::COUNTRY_OF_RESIDENCE, b"DE".to_vec()),
+            (attributes::DOB, b"20000229".to_vec()),
+            (attributes::ID_DOC_EXPIRES_AT, b"20311201".to_vec()),
+            (attributes::FIRST_NAME, b"John".to_vec())
+        ]);
+        let attributes: Vec<_> =
+            policy.attributes().map(|(tag, value)| (tag, value.as_ref().to_vec())).collect();
+        assert_eq!(attributes, items);
+    }
+
+    #[test]
+    /// Test the date of birth computed for a given age, including on the 29th
+    /// of February.
+    fn policy_builder_date_of_birth_for_age() {
+        // 2024-02-29T12:00:00Z
+        let at = Timestamp::from_timestamp_millis(1_709_208_000_000);
+        assert_eq!(Date::from_timestamp(at), Date::new(2024, 2, 29));
+        let policy = TestPolicyBuilder::eu_resident_of_age(18, at).build();
+        assert_eq!(policy.get_attribute(attributes::DOB).unwrap().as_ref(), b"20060228");
+        let policy = TestPolicyBuilder::eu_resident_of_age(20, at).build();
+        assert_eq!(policy.get_attribute(attributes::DOB).unwrap().as_ref(), b"20040229");
+    }
+
+    #[test]
+    /// Test the conversion of timestamps to dates.
+    fn date_from_timestamp() {
+        assert_eq!(
+            Date::from_timestamp(Timestamp::from_timestamp_millis(0)),
+            Date::new(1970, 1, 1)
+        );
+        // 2000-03-01T00:00:00Z
+        assert_eq!(
+            Date::from_timestamp(Timestamp::from_timestamp_millis(951_868_800_000)),
+            Date::new(2000, 3, 1)
+        );
+        // 2000-02-29T23:59:59.999Z
+        assert_eq!(
+            Date::from_timestamp(Timestamp::from_timestamp_millis(951_868_799_999)),
+            Date::new(2000, 2, 29)
+        );
+        assert_eq!(Date::from_timestamp(Timestamp::from_timestamp_millis(u64::MAX)), None);
+        assert_eq!(Date::new(1900, 2, 29), None);
+        assert_eq!(Date::new(2023, 4, 31), None);
+    }
+
+    #[test]
+    /// Test the typed attribute accessors of `HasPolicy`.
+    fn policy_attribute_accessors() {
+        let mut ctx = TestReceiveContext::empty();
+        ctx.push_policy(
+            TestPolicyBuilder::new()
+                .country_of_residence(*b"DK")
+                .date_of_birth(Date::new(2004, 2, 29).unwrap())
+                .id_doc_expires_at(Date::new(2031, 12, 1).unwrap())
+                .build(),
+        );
+        ctx.push_policy(
+            TestPolicyBuilder::new()
+                .attribute(attributes::COUNTRY_OF_RESIDENCE, b"DNK")
+                .attribute(attributes::DOB, b"2004-02-29")
+                .build(),
+        );
+        let mut policies = ctx.policies();
+        let policy = policies.next().expect("There are two policies.");
+        assert_eq!(policy.country_of_residence(), Some(*b"DK"));
+        assert_eq!(policy.nationality(), None);
+        assert_eq!(policy.date_of_birth(), Date::new(2004, 2, 29));
+        assert_eq!(policy.id_doc_expiry(), Date::new(2031, 12, 1));
+        assert_eq!(
+            policy.get_attribute(attributes::COUNTRY_OF_RESIDENCE).map(|v| v.as_ref().to_vec()),
+            Some(b"DK".to_vec())
+        );
+        // 2022-02-28T00:00:00Z and 2022-03-01T00:00:00Z
+        assert_eq!(policy.age_at(Timestamp::from_timestamp_millis(1_646_006_400_000)), Some(17));
+        assert_eq!(policy.age_at(Timestamp::from_timestamp_millis(1_646_092_800_000)), Some(18));
+        // Before the date of birth.
+        assert_eq!(policy.age_at(Timestamp::from_timestamp_millis(0)), None);
+
+        // Malformed values are ignored.
+        let policy = policies.next().expect("There are two policies.");
+        assert_eq!(policy.country_of_residence(), None);
+        assert_eq!(policy.date_of_birth(), None);
+        assert_eq!(policy.age_at(Timestamp::from_timestamp_millis(1_646_092_800_000)), None);
+    }
+
+    #[test]
+    fn roles_grant_revoke_and_admin_roles() {
+        const MINTER: Role = Role(1);
+        const MINTER_ADMIN: Role = Role(2);
+        let owner = Address::Account(AccountAddress([0u8; 32]));
+        let alice = Address::Account(AccountAddress([1u8; 32]));
+        let bob = Address::Account(AccountAddress([2u8; 32]));
+        let mut state_builder = TestStateBuilder::new();
+        let mut logger = TestLogger::init();
+        let mut roles = Roles::new(&mut state_builder);
+        assert!(roles.grant_unchecked(Role::ADMIN, owner, &mut state_builder));
+        assert!(!roles.grant_unchecked(Role::ADMIN, owner, &mut state_builder));
+
+        assert_eq!(
+            roles.grant(&alice, MINTER, bob, &mut state_builder, &mut logger),
+            Err(RolesError::Unauthorized),
+            "Only the admin role can grant roles."
+        );
+        roles.grant(&owner, MINTER, bob, &mut state_builder, &mut logger).expect("Owner is admin.");
+        roles.grant(&owner, MINTER, bob, &mut state_builder, &mut logger).expect("Owner is admin.");
+        assert!(roles.has_role(MINTER, &bob));
+        assert!(!roles.has_role(MINTER, &alice));
+        assert_eq!(logger.logs, [to_bytes(&RoleEvent::RoleGranted {
+            role:    MINTER,
+            address: bob,
+            sender:  owner,
+        })]);
+
+        roles.grant(&owner, MINTER_ADMIN, alice, &mut state_builder, &mut logger).unwrap();
+        roles.set_admin_role(&owner, MINTER, MINTER_ADMIN, &mut logger).unwrap();
+        assert_eq!(roles.admin_role(MINTER), MINTER_ADMIN);
+        assert_eq!(roles.admin_role(MINTER_ADMIN), Role::ADMIN);
+        assert_eq!(
+            roles.revoke(&owner, MINTER, &bob, &mut logger),
+            Err(RolesError::Unauthorized),
+            "The owner is no longer the admin of the minter role."
+        );
+        roles.revoke(&alice, MINTER, &bob, &mut logger).expect("Alice is the minter admin.");
+        assert!(!roles.has_role(MINTER, &bob));
+
+        roles.renounce(&alice, MINTER_ADMIN, &mut logger).unwrap();
+        assert!(!roles.has_role(MINTER_ADMIN, &alice));
+        assert_eq!(&logger.logs[3..], [
+            to_bytes(&RoleEvent::RoleRevoked {
+                role:    MINTER,
+                address: bob,
+                sender:  alice,
+            }),
+            to_bytes(&RoleEvent::RoleRevoked {
+                role:    MINTER_ADMIN,
+                address: alice,
+                sender:  alice,
+            })
+        ]);
+        assert_eq!(Reject::from(RolesError::Unauthorized).error_code.get(), i32::MIN + 24);
+    }
+
+    #[test]
+    fn contract_interface_parses_responses() {
+        struct Client(ContractAddress);
+        impl ContractInterface for Client {
+            fn address(&self) -> ContractAddress { self.0 }
+        }
+        let address = ContractAddress {
+            index:    1,
+            subindex: 0,
+        };
+        let mut host = TestHost::new((), TestStateBuilder::new());
+        host.setup_mock_entrypoint(
+            address,
+            OwnedEntrypointName::new_unchecked("get".into()),
+            MockFn::returning_ok(42u64),
+        );
+        host.setup_mock_entrypoint(
+            address,
+            OwnedEntrypointName::new_unchecked("fail".into()),
+            MockFn::returning_err(CallContractError::LogicReject {
+                reason:       -1,
+                return_value: 7u8,
+            }),
+        );
+        let client = Client(address);
+
+        let value: Result<u64, CallContractError<u8>> =
+            client.invoke(&mut host, EntrypointName::new_unchecked("get"), &(), Amount::zero());
+        assert!(matches!(value, Ok(42)), "The return value is parsed.");
+        let error: Result<(), CallContractError<u8>> =
+            client.invoke(&mut host, EntrypointName::new_unchecked("fail"), &(), Amount::zero());
+        assert!(
+            matches!(
+                error,
+                Err(CallContractError::LogicReject {
+                    reason:       -1,
+                    return_value: 7,
+                })
+            ),
+            "The return value of the rejection is parsed as the error type."
+        );
+
+        host.setup_mock_entrypoint(
+            address,
+            OwnedEntrypointName::new_unchecked("empty".into()),
+            MockFn::returning_ok(()),
+        );
+        let value: Result<u64, CallContractError<u8>> =
+            client.invoke(&mut host, EntrypointName::new_unchecked("empty"), &(), Amount::zero());
+        assert!(
+            matches!(
+                value,
+                Err(CallContractError::InvalidReturnValue {
+                    reason: None,
+                })
+            ),
+            "An unexpected return value is an error."
+        );
+        let error: Result<(), CallContractError<u64>> =
+            client.invoke(&mut host, EntrypointName::new_unchecked("fail"), &(), Amount::zero());
+        assert!(
+            matches!(
+                error,
+                Err(CallContractError::InvalidReturnValue {
+                    reason: Some(-1),
+                })
+            ),
+            "An unexpected return value of the rejection is an error."
+        );
+        assert_eq!(
+            Reject::from(CallContractError::<()>::InvalidReturnValue {
+                reason: None,
+            })
+            .error_code
+            .get(),
+            i32::MIN + 26
+        );
+    }
+
+    #[test]
+    fn reentrancy_guard_rejects_nested_invocations() {
+        let address = ContractAddress {
+            index:    0,
+            subindex: 0,
+        };
+        let entrypoint = OwnedEntrypointName::new_unchecked("callback".into());
+        let mut host = TestHost::new((), TestStateBuilder::new());
+        let mut guard = ReentrancyGuard::new(host.state_builder());
+        // The mock calls back into the contract, which tries to enter the guard.
+        let nested_guard = guard.clone();
+        host.setup_mock_entrypoint(
+            address,
+            entrypoint.clone(),
+            MockFn::new_v1(move |_, _, _, _| {
+                let entered = nested_guard.clone().enter();
+                Ok((false, entered == Err(ReentrancyError)))
+            }),
+        );
+
+        guard.enter().expect("Entering the guard succeeds.");
+        let (_, rv) = host
+            .invoke_contract(&address, &(), entrypoint.as_entrypoint_name(), Amount::zero())
+            .expect("The invocation succeeds.");
+        let rejected = bool::deserial(&mut rv.expect("V1 contracts return a value."));
+        assert_eq!(rejected, Ok(true), "The nested invocation is rejected.");
+        guard.exit();
+        assert!(!guard.is_entered(), "The guard is exited.");
+        guard.enter().expect("The guard can be entered again after exiting.");
+        assert_eq!(Reject::from(ReentrancyError).error_code.get(), i32::MIN + 25);
+    }
+
+    #[test]
+    fn merge_event_types_checks_tags() {
+        let enum_type = Type::Enum(vec![
+            ("Minted".to_string(), Fields::None),
+            ("Burned".to_string(), Fields::Unnamed(vec![Type::U64])),
+        ]);
+        let tagged_type = Type::TaggedEnum(
+            vec![(u8::MAX, ("Transferred".to_string(), Fields::None))].into_iter().collect(),
+        );
+        assert_eq!(merge_event_types(vec![]), Ok(None));
+        assert_eq!(
+            merge_event_types(vec![Type::U8, Type::U8]),
+            Ok(Some(Type::U8)),
+            "Equal event types are merged, even if not tagged."
+        );
+        assert_eq!(
+            merge_event_types(vec![enum_type.clone(), tagged_type.clone(), enum_type.clone()]),
+            Ok(Some(Type::TaggedEnum(
+                vec![
+                    (0, ("Minted".to_string(), Fields::None)),
+                    (1, ("Burned".to_string(), Fields::Unnamed(vec![Type::U64]))),
+                    (u8::MAX, ("Transferred".to_string(), Fields::None)),
+                ]
+                .into_iter()
+                .collect()
+            )))
+        );
+        assert_eq!(
+            merge_event_types(vec![enum_type.clone(), Type::U8]),
+            Err(MergeEventTypesError::NotTagged)
+        );
+        let conflicting_type = Type::Enum(vec![("Paused".to_string(), Fields::None)]);
+        assert_eq!(
+            merge_event_types(vec![enum_type, tagged_type, conflicting_type]),
+            Err(MergeEventTypesError::ConflictingTag(0))
+        );
+    }
+
+    #[test]
+    fn event_tags_detect_duplicates() {
+        const STANDARD: EventTags = EventTags {
+            tags:      &[u8::MAX, u8::MAX - 1],
+            flattened: &[],
+        };
+        let tags = EventTags {
+            tags:      &[0, 1],
+            flattened: &[&STANDARD],
+        };
+        assert!(tags.contains(1));
+        assert!(tags.contains(u8::MAX - 1), "Tags of flattened event types are included.");
+        assert!(!tags.contains(2));
+        assert!(!tags.has_duplicates());
+        let overlapping = EventTags {
+            tags:      &[0, u8::MAX],
+            flattened: &[&STANDARD],
+        };
+        assert!(overlapping.has_duplicates());
+        let flattened_twice = EventTags {
+            tags:      &[],
+            flattened: &[&STANDARD, &STANDARD],
+        };
+        assert!(flattened_twice.has_duplicates());
+    }
+
+    #[test]
+    fn error_envelope_serialization() {
+        let envelope = ErrorEnvelope {
+            error_code: -3,
+            variant:    "Unauthorized".into(),
+            message:    "Only the owner can do this.".into(),
+            error:      vec![2],
+        };
+        let bytes = to_bytes(&envelope);
+        assert_eq!(bytes[..4], ErrorEnvelope::PREFIX);
+        assert_eq!(ErrorEnvelope::from_return_value(&bytes), Some(envelope));
+        assert_eq!(
+            ErrorEnvelope::from_return_value(&[2]),
+            None,
+            "Return values without the prefix are not error envelopes."
+        );
+    }
+
+    #[cfg(feature = "schema-json")]
+    #[test]
+    fn error_envelope_schema_decodes_envelopes() {
+        use crate::schema::SchemaType;
+        let error_type = Type::Enum(vec![
+            ("ParseParams".into(), Fields::None),
+            ("Unauthorized".into(), Fields::None),
+        ]);
+        let ty = merge_event_types(vec![error_type, ErrorEnvelope::get_type()])
+            .expect("The error envelope uses the tag 255.")
+            .expect("There are event types.");
+        let envelope = ErrorEnvelope {
+            error_code: -2,
+            variant:    "Unauthorized".into(),
+            message:    "Only the owner can do this.".into(),
+            error:      vec![1],
+        };
+        assert_eq!(
+            crate::schema_json::decode(&ty, &to_bytes(&envelope)),
+            Ok(serde_json::json!({"ErrorEnvelope": {
+                "prefix": "455252",
+                "error_code": -2,
+                "variant": "Unauthorized",
+                "message": "Only the owner can do this.",
+                "error": "01",
+            }}))
+        );
+        assert_eq!(
+            crate::schema_json::decode(&ty, &[1]),
+            Ok(serde_json::json!({"Unauthorized": []})),
+            "The schema still decodes the error itself."
+        );
+    }
+
+    #[cfg(feature = "schema-json")]
+    #[test]
+    fn check_schema_consistent() {
+        use crate::test_infrastructure::check_schema;
+        check_schema::<Option<(u32, AccountAddress)>>();
+        check_schema::<Vec<(String, Timestamp)>>();
+        check_schema::<crate::collections::BTreeMap<u8, Amount>>();
+        check_schema::<ContractAddress>();
+    }
+
+    /// A type whose schema does not match its serialization, which is a `u32`.
+    #[cfg(feature = "schema-json")]
+    struct WrongSchema(u32);
+
+    #[cfg(feature = "schema-json")]
+    impl crate::Serial for WrongSchema {
+        fn serial<W: Write>(&self, out: &mut W) -> Result<(), W::Err> { self.0.serial(out) }
+    }
+
+    #[cfg(feature = "schema-json")]
+    impl Deserial for WrongSchema {
+        fn deserial<R: Read>(source: &mut R) -> crate::ParseResult<Self> {
+            u32::deserial(source).map(WrongSchema)
+        }
+    }
+
+    #[cfg(feature = "schema-json")]
+    impl crate::schema::SchemaType for WrongSchema {
+        fn get_type() -> Type { Type::U64 }
+    }
+
+    #[cfg(feature = "schema-json")]
+    #[test]
+    #[should_panic(expected = "consumed 4 of its 8 bytes")]
+    fn check_schema_inconsistent() { crate::test_infrastructure::check_schema::<WrongSchema>(); }
 }
@@ -5,10 +5,11 @@
 #[cfg(not(feature = "std"))]
 use crate::vec::Vec;
 use crate::{
-    types::{LogError, StateError},
-    CallContractResult, EntryRaw, HashKeccak256, HashSha2256, HashSha3256, Key, OccupiedEntryRaw,
-    PublicKeyEcdsaSecp256k1, PublicKeyEd25519, ReadOnlyCallContractResult, SignatureEcdsaSecp256k1,
-    SignatureEd25519, StateBuilder, TransferResult, VacantEntryRaw,
+    convert::TryInto,
+    types::{Date, EventTags, LogError, StateError},
+    CallContractError, CallContractResult, EntryRaw, HashKeccak256, HashSha2256, HashSha3256, Key,
+    OccupiedEntryRaw, PublicKeyEcdsaSecp256k1, PublicKeyEd25519, ReadOnlyCallContractResult,
+    SignatureEcdsaSecp256k1, SignatureEd25519, StateBuilder, TransferResult, VacantEntryRaw,
 };
 use concordium_contracts_common::*;
 
@@ -68,6 +69,60 @@ pub trait HasPolicy: Sized {
     fn next_item(&mut self, buf: &mut [u8; 31]) -> Option<(AttributeTag, u8)>;
     /// Get an iterator over all the attributes of the policy.
     fn attributes(&self) -> Self::Iterator;
+
+    /// Get the value of the attribute with the given tag, if it is revealed
+    /// in the policy. See the [`attributes`](crate::attributes) module for the
+    /// tags.
+    fn get_attribute(&self, tag: AttributeTag) -> Option<AttributeValue> {
+        self.attributes().find(|(t, _)| *t == tag).map(|(_, value)| value)
+    }
+
+    /// Get the country of residence as an ISO 3166-1 alpha-2 code, e.g.,
+    /// `*b"DK"`. Returns `None` if the attribute is not in the policy or is
+    /// malformed.
+    fn country_of_residence(&self) -> Option<[u8; 2]> {
+        let value = self.get_attribute(attributes::COUNTRY_OF_RESIDENCE)?;
+        value.as_ref().try_into().ok()
+    }
+
+    /// Get the nationality as an ISO 3166-1 alpha-2 code, e.g., `*b"DK"`.
+    /// Returns `None` if the attribute is not in the policy or is malformed.
+    fn nationality(&self) -> Option<[u8; 2]> {
+        let value = self.get_attribute(attributes::NATIONALITY)?;
+        value.as_ref().try_into().ok()
+    }
+
+    /// Get the date of birth. Returns `None` if the attribute is not in the
+    /// policy or is malformed.
+    fn date_of_birth(&self) -> Option<Date> {
+        Date::from_attribute_bytes(self.get_attribute(attributes::DOB)?.as_ref())
+    }
+
+    /// Get the age in whole years at the given time, e.g., the slot time of
+    /// the block. Returns `None` if the date of birth is not in the policy, or
+    /// if it is after the given time.
+    ///
+    /// ```rust
+    /// # use concordium_std::*;
+    /// /// Check that all credentials of the sender belong to adults that do not
+    /// /// reside in the US.
+    /// fn is_allowed(ctx: &impl HasReceiveContext) -> bool {
+    ///     let now = ctx.metadata().slot_time();
+    ///     ctx.policies().all(|policy| {
+    ///         policy.age_at(now).map_or(false, |age| age >= 18)
+    ///             && policy.country_of_residence() != Some(*b"US")
+    ///     })
+    /// }
+    /// ```
+    fn age_at(&self, time: Timestamp) -> Option<u16> {
+        self.date_of_birth()?.years_until(Date::from_timestamp(time)?)
+    }
+
+    /// Get the date the identity document of the credential holder expires.
+    /// Returns `None` if the attribute is not in the policy or is malformed.
+    fn id_doc_expiry(&self) -> Option<Date> {
+        Date::from_attribute_bytes(self.get_attribute(attributes::ID_DOC_EXPIRES_AT)?.as_ref())
+    }
 }
 
 /// Common data accessible to both init and receive methods.
@@ -226,13 +281,36 @@ pub trait HasStateApi: Clone {
     fn delete_iterator(&mut self, iter: Self::IterType);
 }
 
+/// A type that gives read-only access to the host, meaning that it supports
+/// querying the balance of the contract and reading its state, but not
+/// modifying the state, transferring CCD or invoking other contracts.
+///
+/// This is the host given to entrypoints declared with
+/// [`#[view]`](crate::view). Every [`HasHost`] is also a [`HasViewHost`].
+///
+/// The trait is parameterized by the `State` type. This is the type of the
+/// contract state that the particular contract operates on.
+pub trait HasViewHost<State> {
+    /// Get an immutable reference to the contract state.
+    fn state(&self) -> &State;
+
+    /// Get the contract's own current balance. Upon entry to the entrypoint the
+    /// balance that is returned is the sum of balance of the contract at
+    /// the time of the invocation and the amount that is being transferred to
+    /// the contract.
+    fn self_balance(&self) -> Amount;
+}
+
 /// A type that can serve as the host, meaning that it supports interactions
 /// with the chain, such as querying balance of the contract, accessing its
 /// state, and invoking operations on other contracts and accounts.
 ///
+/// Reading the state and the balance of the contract is provided by the
+/// supertrait [`HasViewHost`].
+///
 /// The trait is parameterized by the `State` type. This is the type of the
 /// contract state that the particular contract operates on.
-pub trait HasHost<State>: Sized {
+pub trait HasHost<State>: HasViewHost<State> + Sized {
     /// The type of low-level state that is associated with the host.
     /// This provides access to low-level state operations.
     type StateApiType: HasStateApi;
@@ -335,9 +413,6 @@ pub trait HasHost<State>: Sized {
         self.invoke_contract_raw_read_only(to, Parameter(&param), method, amount)
     }
 
-    /// Get an immutable reference to the contract state.
-    fn state(&self) -> &State;
-
     /// Get a mutable reference to the contract state.
     fn state_mut(&mut self) -> &mut State;
 
@@ -356,12 +431,85 @@ pub trait HasHost<State>: Sized {
     /// types" is stable this will likely be possible to remove, and the
     /// types of `state_builder` and `state_mut` can be refined.
     fn state_and_builder(&mut self) -> (&mut State, &mut StateBuilder<Self::StateApiType>);
+}
 
-    /// Get the contract's own current balance. Upon entry to the entrypoint the
-    /// balance that is returned is the sum of balance of the contract at
-    /// the time of the invocation and the amount that is being transferred to
-    /// the contract.
-    fn self_balance(&self) -> Amount;
+/// A typed interface to a contract instance, for invoking its entrypoints with
+/// typed parameters and getting typed return values.
+///
+/// The return value of a rejection is parsed as the error type declared by the
+/// entrypoint, such that a [`LogicReject`](CallContractError::LogicReject)
+/// contains the error of the invoked contract instead of the raw response.
+/// See [`parse_call_response`](crate::parse_call_response) for the details.
+///
+/// Implementors only need to provide the [`address`](Self::address) of the
+/// instance, and typically add a method for each entrypoint of the interface
+/// that uses [`invoke`](Self::invoke) or
+/// [`invoke_read_only`](Self::invoke_read_only) with the declared types.
+/// The clients generated by [`#[contract]`](crate::contract) implement this
+/// trait, and the `concordium-cis2` library provides an implementation for the
+/// CIS-2 interface.
+///
+/// ```ignore
+/// struct CounterClient(ContractAddress);
+///
+/// impl ContractInterface for CounterClient {
+///     fn address(&self) -> ContractAddress { self.0 }
+/// }
+///
+/// impl CounterClient {
+///     fn add<State>(
+///         &self,
+///         host: &mut impl HasHost<State>,
+///         n: u64,
+///     ) -> Result<u64, CallContractError<CounterError>> {
+///         self.invoke(host, EntrypointName::new_unchecked("add"), &n, Amount::zero())
+///     }
+/// }
+/// ```
+pub trait ContractInterface {
+    /// The address of the contract instance.
+    fn address(&self) -> ContractAddress;
+
+    /// Invoke the entrypoint of the contract instance with the parameter and
+    /// amount provided, and parse the return value as `R`, or the return
+    /// value of a rejection as `E`.
+    ///
+    /// Returns [`InvalidReturnValue`](CallContractError::InvalidReturnValue)
+    /// if the response cannot be parsed, i.e., if the contract does not adhere
+    /// to the types.
+    fn invoke<State, H: HasHost<State>, P: Serial, R: Deserial, E: Deserial>(
+        &self,
+        host: &mut H,
+        entrypoint: EntrypointName,
+        parameter: &P,
+        amount: Amount,
+    ) -> Result<R, CallContractError<E>> {
+        crate::parse_call_response(host.invoke_contract(
+            &self.address(),
+            parameter,
+            entrypoint,
+            amount,
+        ))
+    }
+
+    /// Like [`invoke`](Self::invoke), but using
+    /// [`invoke_contract_read_only`](HasHost::invoke_contract_read_only), which
+    /// only needs an immutable reference to the host. See its documentation
+    /// for when this can be used.
+    fn invoke_read_only<State, H: HasHost<State>, P: Serial, R: Deserial, E: Deserial>(
+        &self,
+        host: &H,
+        entrypoint: EntrypointName,
+        parameter: &P,
+        amount: Amount,
+    ) -> Result<R, CallContractError<E>> {
+        crate::parse_read_only_call_response(host.invoke_contract_read_only(
+            &self.address(),
+            parameter,
+            entrypoint,
+            amount,
+        ))
+    }
 }
 
 /// A type that can be deleted from the state.
@@ -405,6 +553,21 @@ pub trait HasLogger {
     }
 }
 
+/// Event types whose serialization starts with a one byte tag identifying the
+/// kind of event, such as `Cis2Event` from the `concordium-cis2` library.
+///
+/// Tagged event types can be flattened into other event types deriving
+/// [`Event`](derive.Event.html), so that contracts can log the events of a
+/// standard along with their own events using a single event type.
+pub trait TaggedEvent: Serial + Sized {
+    /// The tags used by the event type.
+    const TAGS: EventTags;
+
+    /// Attempt to read an event from the given source, where the tag has
+    /// already been read.
+    fn deserial_tagged<R: Read>(tag: u8, source: &mut R) -> ParseResult<Self>;
+}
+
 /// Objects which provide cryptographic primitives.
 pub trait HasCryptoPrimitives {
     /// Verify an ed25519 signature.
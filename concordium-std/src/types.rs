@@ -1,5 +1,6 @@
 use crate::{
-    cell::UnsafeCell, marker::PhantomData, num::NonZeroU32, Cursor, HasStateApi, Serial, Vec,
+    cell::UnsafeCell, marker::PhantomData, num::NonZeroU32, Address, Cursor, HasStateApi, Serial,
+    String, Vec,
 };
 
 #[derive(Debug)]
@@ -605,6 +606,16 @@ pub enum CallContractError<ReturnValueType> {
     },
     /// Execution of a contract call triggered a runtime error.
     Trap,
+    /// The return value of the invoked contract could not be parsed as the
+    /// type declared by the entrypoint. This is only returned by the typed
+    /// invocations of [`ContractInterface`](crate::ContractInterface), which
+    /// parse the return value. Note that the invocation itself might have
+    /// succeeded and modified the state of the invoked contract.
+    InvalidReturnValue {
+        /// The reason the invoked contract rejected with, or `None` if the
+        /// invocation succeeded.
+        reason: Option<i32>,
+    },
 }
 
 #[repr(i32)]
@@ -654,6 +665,19 @@ pub struct PolicyAttributesIter {
     pub(crate) cursor: AttributesCursor,
 }
 
+/// A calendar date in the Gregorian calendar.
+///
+/// This is the format of the date attributes of an identity, such as
+/// [`DOB`](crate::attributes::DOB) and
+/// [`ID_DOC_EXPIRES_AT`](crate::attributes::ID_DOC_EXPIRES_AT), which are
+/// represented in policies as the 8 ASCII digits `YYYYMMDD`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub(crate) year:  u16,
+    pub(crate) month: u8,
+    pub(crate) day:   u8,
+}
+
 /// A type representing the logger.
 #[derive(Default)]
 pub struct Logger {
@@ -675,6 +699,250 @@ pub enum LogError {
 #[derive(Clone, Copy, Debug)]
 pub struct NotPayableError;
 
+/// Error triggered when the sender of a message does not satisfy the access
+/// requirements of a receive function, which are declared with the
+/// `requires_sender` and `require_policy` attributes of
+/// [`#[receive]`](attr.receive.html).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AccessDeniedError {
+    /// The sender is not of the kind required by `requires_sender`.
+    WrongSenderKind,
+    /// A policy of the sender does not satisfy the predicate given by
+    /// `require_policy`.
+    PolicyNotSatisfied,
+}
+
+/// The kind of sender that a receive function requires, which is declared with
+/// the `requires_sender` attribute of [`#[receive]`](attr.receive.html).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SenderKind {
+    /// The sender must be an account.
+    Account,
+    /// The sender must be a contract.
+    Contract,
+}
+
+/// Error triggered when a receive function that is protected by a
+/// [`ReentrancyGuard`], e.g., using the `non_reentrant` attribute of
+/// [`#[receive]`](attr.receive.html), is invoked while another protected
+/// invocation of the same contract instance is in progress.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ReentrancyError;
+
+/// A guard against reentrancy, i.e., a contract being invoked again, e.g., by
+/// a contract that it invokes, before the first invocation has finished.
+///
+/// The guard records that an invocation is in progress in a dedicated entry of
+/// the contract state, which is separate from the state of the contract and
+/// the items allocated by the [`StateBuilder`]. Since invoking other contracts
+/// stores the state first, nested invocations see the entry and are rejected
+/// with [`ReentrancyError`].
+///
+/// Receive functions with the `non_reentrant` attribute of
+/// [`#[receive]`](attr.receive.html) are protected by a guard automatically.
+/// The guard can also be used directly, e.g., to only protect part of a
+/// function, or to test reentrancy with a
+/// [`TestHost`](crate::test_infrastructure::TestHost):
+///
+/// ```
+/// # use concordium_std::*;
+/// # use concordium_std::test_infrastructure::*;
+/// let mut host = TestHost::new((), TestStateBuilder::new());
+/// let mut guard = ReentrancyGuard::new(host.state_builder());
+/// guard.enter()?;
+/// // A nested invocation is rejected.
+/// assert_eq!(ReentrancyGuard::new(host.state_builder()).enter(), Err(ReentrancyError));
+/// guard.exit();
+/// assert!(!guard.is_entered());
+/// # Ok::<(), ReentrancyError>(())
+/// ```
+///
+/// Note that the entry is removed when the invocation is rolled back, so a
+/// function which returns an error does not have to exit the guard.
+#[derive(Clone)]
+pub struct ReentrancyGuard<S> {
+    pub(crate) state_api: S,
+}
+
+/// The access requirements of a receive function, as declared with the
+/// `requires_sender` and `require_policy` attributes of
+/// [`#[receive]`](attr.receive.html). When building with the `build-schema`
+/// feature, this is exported alongside the schema of the function.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EntrypointAccess {
+    /// The kind of sender that is required, if any.
+    pub sender: Option<SenderKind>,
+    /// The path of the predicate that all policies of the sender must satisfy,
+    /// if any.
+    pub policy: Option<String>,
+}
+
+/// Metadata of a contract, which is declared with the `version`, `standards`
+/// and `repository` attributes of [`#[init]`](attr.init.html) and the
+/// documentation comment of the init function. When building with the
+/// `build-schema` feature, this is exported alongside the schema of the init
+/// function, so that tools such as blockchain explorers can display it.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ContractMetadata {
+    /// The version of the contract, e.g., `1.2.0`.
+    pub version:    Option<String>,
+    /// The identifiers of the standards supported by the contract, e.g.,
+    /// `CIS-0` and `CIS-2`.
+    pub standards:  Vec<String>,
+    /// The URL of the source repository of the contract.
+    pub repository: Option<String>,
+    /// The documentation comment of the init function, which describes the
+    /// contract.
+    pub doc:        String,
+}
+
+/// Metadata of a receive function, which is its documentation comment and
+/// whether it is a view. When building with the `build-schema` feature, this is
+/// exported alongside the schema of the function if it has a documentation
+/// comment or is a view.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct EntrypointMetadata {
+    /// The documentation comment of the receive function.
+    pub doc:  String,
+    /// Whether the function is declared with [`#[view]`](crate::view), meaning
+    /// that it does not modify the state of the contract, and can be queried
+    /// without sending a transaction.
+    pub view: bool,
+}
+
+/// An identifier of a role managed by [`Roles`]. Contracts typically declare
+/// their roles as constants, e.g., `const MINTER: Role = Role(1);`.
+///
+/// The role [`Role::ADMIN`] is special in that it is the default admin role
+/// of every role, including itself.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Role(pub u8);
+
+/// Role-based access control for contracts, which is meant to be used as part
+/// of the contract state.
+///
+/// Each [`Role`] has a set of addresses that are its members, and an _admin
+/// role_, whose members are allowed to grant and revoke the role. Unless
+/// changed with [`set_admin_role`](Self::set_admin_role), the admin role of
+/// every role is [`Role::ADMIN`].
+///
+/// Since `Roles` contains a [`StateMap`], it must be deleted explicitly using
+/// [`Deletable::delete`](crate::Deletable::delete) when it is no longer
+/// needed. It implements [`Serial`], `DeserialWithState`, `Deletable` and
+/// `StateClone` so it can be used as a field of a state type using the derive
+/// macros for these traits.
+///
+/// ```
+/// # use concordium_std::*;
+/// # use concordium_std::test_infrastructure::*;
+/// const MINTER: Role = Role(1);
+///
+/// #[derive(Serial, DeserialWithState, Deletable, StateClone)]
+/// #[concordium(state_parameter = "S")]
+/// struct State<S: HasStateApi> {
+///     roles: Roles<S>,
+/// }
+///
+/// # let mut state_builder = TestStateBuilder::new();
+/// # let mut logger = TestLogger::init();
+/// let owner = Address::Account(AccountAddress([0u8; 32]));
+/// let minter = Address::Account(AccountAddress([1u8; 32]));
+/// // In the init function:
+/// let mut state = State {
+///     roles: Roles::new(&mut state_builder),
+/// };
+/// state.roles.grant_unchecked(Role::ADMIN, owner, &mut state_builder);
+/// // In a receive function, where the sender is the owner:
+/// state.roles.grant(&owner, MINTER, minter, &mut state_builder, &mut logger)?;
+/// assert!(state.roles.has_role(MINTER, &minter));
+/// # Ok::<(), RolesError>(())
+/// ```
+pub struct Roles<S> {
+    pub(crate) members: StateMap<Role, StateSet<Address, S>, S>,
+    pub(crate) admins:  StateMap<Role, Role, S>,
+}
+
+/// Errors that can occur when merging event types with
+/// [`merge_event_types`](crate::merge_event_types).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MergeEventTypesError {
+    /// The event types differ and at least one of them is not an enum with at
+    /// most 256 variants, so the events cannot be told apart by their tag.
+    NotTagged,
+    /// Two event types use the same tag for different events.
+    ConflictingTag(u8),
+}
+
+/// The tags used by an event type implementing
+/// [`TaggedEvent`](crate::TaggedEvent), including the tags of the event types
+/// flattened into it.
+#[derive(Debug, Clone, Copy)]
+pub struct EventTags {
+    /// The tags of the events defined by the event type itself.
+    pub tags:      &'static [u8],
+    /// The tags of the event types flattened into the event type.
+    pub flattened: &'static [&'static EventTags],
+}
+
+/// The schema of a contract declared with [`#[contract]`](attr.contract.html),
+/// which is generated by the macro as the function `contract_schema` in the
+/// module when building with the feature `build-schema`.
+///
+/// Unlike the schema embedded in the Wasm module, this allows tools to collect
+/// the schema of a contract natively, e.g., in a test.
+#[derive(Debug, Clone)]
+pub struct ContractSchema {
+    /// The name of the contract.
+    pub name:        String,
+    /// The types of the parameters, return values and errors of the init and
+    /// receive functions. Every function of the contract is included, even if
+    /// it declares no types.
+    pub functions:   crate::schema::ContractV2,
+    /// The event type of the contract, merged from the event types of its
+    /// functions, if any are declared.
+    pub event:       Option<crate::schema::Type>,
+    /// The metadata declared on the init function, or the default if the
+    /// contract has no init function.
+    pub metadata:    ContractMetadata,
+    /// The metadata of the receive functions by the name of their entrypoint.
+    pub entrypoints: crate::collections::BTreeMap<String, EntrypointMetadata>,
+}
+
+/// Errors that can occur when managing [`Roles`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RolesError {
+    /// The sender does not have the role required for the operation.
+    Unauthorized,
+    /// Logging the [`RoleEvent`] failed.
+    Log(LogError),
+}
+
+/// Events logged by [`Roles`] when roles are granted, revoked or get a new
+/// admin role. The events are serialized with the tags `0`, `1` and `2`
+/// respectively.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RoleEvent {
+    /// The `role` was granted to `address` by `sender`.
+    RoleGranted {
+        role:    Role,
+        address: Address,
+        sender:  Address,
+    },
+    /// The `role` was revoked from `address` by `sender`. If `address` and
+    /// `sender` are the same, the role was renounced.
+    RoleRevoked {
+        role:    Role,
+        address: Address,
+        sender:  Address,
+    },
+    /// The admin role of `role` was changed.
+    RoleAdminChanged {
+        role:                Role,
+        previous_admin_role: Role,
+        new_admin_role:      Role,
+    },
+}
+
 /// An error message, signalling rejection of a smart contract invocation.
 /// The client will see the error code as a reject reason; if a schema is
 /// provided, the error message corresponding to the error code will be
@@ -722,6 +990,28 @@ impl Reject {
     }
 }
 
+/// A standardized return value of a rejection, which allows tools such as
+/// wallets to display the error without the schema of the contract.
+///
+/// It is produced by [`derive(Reject)`](derive.Reject.html) for enums with the
+/// `#[concordium(with_messages)]` attribute, when the feature `error-messages`
+/// is enabled. It is serialized as [`ErrorEnvelope::PREFIX`], followed by the
+/// fields in order. Its schema uses the tag `255` of the prefix, and is part of
+/// the schema of such error enums when the feature is enabled, so the return
+/// value of a rejection can be decoded using the schema of the contract.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorEnvelope {
+    /// The error code of the rejection.
+    pub error_code: i32,
+    /// The name of the variant of the error.
+    pub variant:    String,
+    /// A human-readable description of the error.
+    pub message:    String,
+    /// The serialized error, which can be parsed using the schema of the
+    /// contract.
+    pub error:      Vec<u8>,
+}
+
 // Macros for failing a contract function
 
 /// The `bail` macro can be used for cleaner error handling. If the function has
@@ -951,6 +1241,24 @@ pub struct ExternHost<State> {
     pub state_builder: StateBuilder<ExternStateApi>,
 }
 
+/// A host that only gives read access to the state and the balance of the
+/// contract. Entrypoints declared with [`#[view]`](crate::view) are given this
+/// host, which ensures that they cannot modify the state, transfer CCD or
+/// invoke other contracts.
+pub struct ViewHost<'a, H> {
+    host: &'a H,
+}
+
+impl<'a, H> ViewHost<'a, H> {
+    /// Restrict the given host to read-only operations.
+    #[inline(always)]
+    pub fn new(host: &'a H) -> Self {
+        Self {
+            host,
+        }
+    }
+}
+
 #[derive(Default)]
 /// An state builder that allows the creation of [`StateMap`], [`StateSet`], and
 /// [`StateBox`]. It is parametrized by a parameter `S` that is assumed to
@@ -0,0 +1,18 @@
+//! Ensure that `#[contract]` rejects a receive function named `new`, which
+//! would collide with the constructor of the generated client.
+use concordium_std::*;
+
+#[contract(name = "test")]
+mod test {
+    use concordium_std::*;
+
+    #[receive(name = "new")]
+    fn new<S: HasStateApi>(
+        _ctx: &impl HasReceiveContext,
+        _host: &impl HasHost<u64, StateApiType = S>,
+    ) -> ReceiveResult<()> {
+        Ok(())
+    }
+}
+
+fn main() {}
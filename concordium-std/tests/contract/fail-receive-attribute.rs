@@ -0,0 +1,18 @@
+//! Ensure that `#[contract]` reports invalid attributes of the receive
+//! functions in the module.
+use concordium_std::*;
+
+#[contract(name = "test")]
+mod test {
+    use concordium_std::*;
+
+    #[receive(name = "increment", mutabel)]
+    fn increment<S: HasStateApi>(
+        _ctx: &impl HasReceiveContext,
+        _host: &mut impl HasHost<u64, StateApiType = S>,
+    ) -> ReceiveResult<()> {
+        Ok(())
+    }
+}
+
+fn main() {}
@@ -0,0 +1,90 @@
+//! Ensure that `#[contract]` fills in the contract name of the init and
+//! receive functions, and that the generated client can be used from another
+//! contract.
+use concordium_std::*;
+
+#[contract(name = "counter")]
+mod counter {
+    use concordium_std::*;
+
+    #[derive(Serialize, Reject, SchemaType)]
+    pub enum CounterError {
+        Overflow,
+    }
+
+    #[init]
+    fn init<S: HasStateApi>(
+        _ctx: &impl HasInitContext,
+        _state_builder: &mut StateBuilder<S>,
+    ) -> InitResult<u64> {
+        Ok(0)
+    }
+
+    #[receive(
+        name = "add",
+        parameter = "u64",
+        return_value = "u64",
+        error = "CounterError",
+        mutable
+    )]
+    fn add<S: HasStateApi>(
+        ctx: &impl HasReceiveContext,
+        host: &mut impl HasHost<u64, StateApiType = S>,
+    ) -> Result<u64, CounterError> {
+        let n: u64 = ctx.parameter_cursor().get().map_err(|_| CounterError::Overflow)?;
+        let total = host.state().checked_add(n).ok_or(CounterError::Overflow)?;
+        *host.state_mut() = total;
+        Ok(total)
+    }
+
+    #[receive(contract = "counter", name = "deposit", payable)]
+    fn deposit<S: HasStateApi>(
+        _ctx: &impl HasReceiveContext,
+        _host: &impl HasHost<u64, StateApiType = S>,
+        _amount: Amount,
+    ) -> ReceiveResult<()> {
+        Ok(())
+    }
+
+    #[view(name = "total", return_value = "u64")]
+    fn total(_ctx: &impl HasReceiveContext, host: &impl HasViewHost<u64>) -> ReceiveResult<u64> {
+        Ok(*host.state())
+    }
+
+    #[receive(fallback)]
+    fn fallback<S: HasStateApi>(
+        _ctx: &impl HasReceiveContext,
+        _host: &impl HasHost<u64, StateApiType = S>,
+    ) -> ReceiveResult<()> {
+        Ok(())
+    }
+}
+
+#[init(contract = "caller")]
+fn caller_init<S: HasStateApi>(
+    _ctx: &impl HasInitContext,
+    _state_builder: &mut StateBuilder<S>,
+) -> InitResult<()> {
+    Ok(())
+}
+
+#[receive(contract = "caller", name = "call", parameter = "ContractAddress", mutable)]
+fn caller_call<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<(), StateApiType = S>,
+) -> ReceiveResult<()> {
+    let client = counter::CounterClient::new(ctx.parameter_cursor().get()?);
+    let _total: u64 = match client.add(host, &5) {
+        Ok(total) => total,
+        Err(CallContractError::LogicReject {
+            return_value: counter::CounterError::Overflow,
+            ..
+        }) => 0,
+        Err(_) => bail!(),
+    };
+    client.deposit(host, &(), Amount::from_micro_ccd(10))?;
+    let _total: u64 = client.total(host, &())?;
+    Ok(())
+}
+
+fn main() {}
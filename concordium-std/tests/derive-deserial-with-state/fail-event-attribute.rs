@@ -0,0 +1,12 @@
+//! Ensure that the attributes of `derive(Event)` are rejected on fields, where
+//! they would otherwise be ignored.
+use concordium_std::*;
+
+#[derive(DeserialWithState)]
+#[concordium(state_parameter = "S")]
+struct State<S: HasStateApi> {
+    #[concordium(tag = 1)]
+    total: StateBox<u64, S>,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+//! Ensure that `derive(Event)` rejects a tag used by multiple events.
+use concordium_std::*;
+
+#[derive(Event)]
+enum ContractEvent {
+    #[concordium(tag = 1)]
+    Paused,
+    #[concordium(tag = 1)]
+    Unpaused,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+//! Ensure that `derive(Event)` rejects the attributes of `derive(Reject)`.
+use concordium_std::*;
+
+#[derive(Event)]
+enum ContractEvent {
+    #[concordium(tag = 0, error_code = -5)]
+    Paused,
+}
+
+fn main() {}
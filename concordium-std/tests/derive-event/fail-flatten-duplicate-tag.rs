@@ -0,0 +1,19 @@
+//! Ensure that `derive(Event)` rejects a tag that is also used by a flattened
+//! event type, even if the events are never serialized.
+use concordium_std::*;
+
+#[derive(Event)]
+enum StandardEvent {
+    #[concordium(tag = 10)]
+    Paused,
+}
+
+#[derive(Event)]
+enum ContractEvent {
+    #[concordium(flatten)]
+    Standard(StandardEvent),
+    #[concordium(tag = 10)]
+    Message,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+//! Ensure that `derive(Event)` rejects the tags reserved for the events of the
+//! CIS-2 standard and its extensions.
+use concordium_std::*;
+
+#[derive(Event)]
+enum ContractEvent {
+    #[concordium(tag = 0)]
+    Paused,
+    #[concordium(tag = 249)]
+    Unpaused,
+}
+
+fn main() {}
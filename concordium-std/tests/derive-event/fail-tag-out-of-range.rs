@@ -0,0 +1,10 @@
+//! Ensure that `derive(Event)` rejects a tag which does not fit in a `u8`.
+use concordium_std::*;
+
+#[derive(Event)]
+enum ContractEvent {
+    #[concordium(tag = 256)]
+    Paused,
+}
+
+fn main() {}
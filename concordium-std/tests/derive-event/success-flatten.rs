@@ -0,0 +1,43 @@
+//! Ensure that `derive(Event)` supports tagged, nested and flattened events,
+//! and that events are serialized with their tags.
+use concordium_std::*;
+
+#[derive(Debug, PartialEq, Eq, Event)]
+enum StandardEvent {
+    #[concordium(tag = 10)]
+    Paused,
+    #[concordium(tag = 11)]
+    Unpaused {
+        by: AccountAddress,
+    },
+}
+
+#[derive(Debug, PartialEq, Eq, Event)]
+enum ContractEvent<T: Serial + Deserial> {
+    #[concordium(flatten)]
+    Standard(StandardEvent),
+    #[concordium(tag = 0)]
+    Nested(StandardEvent),
+    #[concordium(tag = 248)]
+    Message(#[concordium(size_length = 1)] String, T),
+}
+
+fn main() {
+    let events = [
+        ContractEvent::Standard(StandardEvent::Unpaused {
+            by: AccountAddress([1; 32]),
+        }),
+        ContractEvent::Nested(StandardEvent::Paused),
+        ContractEvent::Message("hello".into(), 42u8),
+    ];
+    for event in events.iter() {
+        let bytes = to_bytes(event);
+        assert_eq!(from_bytes::<ContractEvent<u8>>(&bytes).as_ref(), Ok(event));
+    }
+    assert_eq!(to_bytes(&ContractEvent::<u8>::Standard(StandardEvent::Paused)), [10]);
+    assert_eq!(to_bytes(&ContractEvent::<u8>::Nested(StandardEvent::Paused)), [0, 10]);
+    assert_eq!(to_bytes(&ContractEvent::Message("a".into(), 1u8)), [248, 1, b'a', 1]);
+    assert!(from_bytes::<ContractEvent<u8>>(&[11]).is_err());
+    assert!(ContractEvent::<u8>::TAGS.contains(11));
+    assert!(!ContractEvent::<u8>::TAGS.has_duplicates());
+}
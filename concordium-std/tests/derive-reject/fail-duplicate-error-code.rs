@@ -0,0 +1,14 @@
+//! Ensure that `derive(Reject)` fails if two variants get the same error code.
+use concordium_std::*;
+
+#[derive(Serial, Reject)]
+enum MyError {
+    #[concordium(error_code = -2)]
+    ParseParams,
+    #[concordium(error_code = -1)]
+    InsufficientFunds,
+    // Gets the error code -2 of the previous variant minus one.
+    Unauthorized,
+}
+
+fn main() {}
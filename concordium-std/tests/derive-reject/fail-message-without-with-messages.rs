@@ -0,0 +1,11 @@
+//! Ensure that `derive(Reject)` fails if a variant has a message when the enum
+//! does not have the `with_messages` attribute.
+use concordium_std::*;
+
+#[derive(Serial, Reject)]
+enum MyError {
+    #[concordium(message = "The parameter could not be parsed.")]
+    ParseParams,
+}
+
+fn main() {}
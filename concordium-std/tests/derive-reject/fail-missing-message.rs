@@ -0,0 +1,13 @@
+//! Ensure that `derive(Reject)` fails if a variant has no message when the
+//! enum has the `with_messages` attribute.
+use concordium_std::*;
+
+#[derive(Serial, Reject)]
+#[concordium(with_messages)]
+enum MyError {
+    #[concordium(message = "The parameter could not be parsed.")]
+    ParseParams,
+    Unauthorized,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+//! Ensure that `derive(Reject)` fails if an error code is in the range reserved
+//! for the errors of concordium-std.
+use concordium_std::*;
+
+#[derive(Serial, Reject)]
+enum MyError {
+    ParseParams,
+    #[concordium(error_code = -2147483600)]
+    InsufficientFunds,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+//! Ensure that `derive(Reject)` fails if a transparent variant does not have
+//! exactly one unnamed field.
+use concordium_std::*;
+
+#[derive(Serial, Reject)]
+enum MyError {
+    ParseParams,
+    #[concordium(transparent)]
+    Inner(RolesError, u8),
+}
+
+fn main() {}
@@ -0,0 +1,58 @@
+//! Ensure that `derive(Reject)` uses explicit error codes and the error code
+//! base, and that transparent variants keep the error code of the wrapped
+//! error, but return the serialized outer error.
+use concordium_std::*;
+
+#[derive(Serial, Reject)]
+enum InnerError {
+    #[concordium(error_code = -50)]
+    Paused,
+}
+
+#[derive(Serial, Reject)]
+#[concordium(error_code_base = -10)]
+enum MyError {
+    #[from(ParseError)]
+    ParseParams,
+    #[concordium(error_code = -20)]
+    InsufficientFunds,
+    Unauthorized {
+        account: AccountAddress,
+    },
+    #[from]
+    #[concordium(transparent)]
+    Inner(InnerError),
+    #[concordium(error_code = -1)]
+    Other(u8),
+}
+
+fn error_code(reject: Reject) -> i32 { reject.error_code.get() }
+
+fn main() {
+    assert_eq!(MyError::ERROR_CODES, &[
+        (-10, "ParseParams"),
+        (-20, "InsufficientFunds"),
+        (-21, "Unauthorized"),
+        (-1, "Other")
+    ]);
+    assert_eq!(InnerError::ERROR_CODES, &[(-50, "Paused")]);
+    assert_eq!(error_code(MyError::from(ParseError::default()).into()), -10);
+    assert_eq!(error_code(MyError::InsufficientFunds.into()), -20);
+    assert_eq!(
+        error_code(
+            MyError::Unauthorized {
+                account: AccountAddress([0; 32]),
+            }
+            .into()
+        ),
+        -21
+    );
+    let inner = MyError::from(InnerError::Paused);
+    let serialized_inner = to_bytes(&inner);
+    let inner: Reject = inner.into();
+    assert_eq!(inner.error_code.get(), -50);
+    assert_eq!(inner.return_value, Some(serialized_inner));
+    let other: Reject = MyError::Other(3).into();
+    assert_eq!(other.error_code.get(), -1);
+    assert_eq!(other.return_value, Some(vec![4, 3]));
+}
@@ -0,0 +1,43 @@
+//! Ensure that `derive(Reject)` accepts messages, and that the return value is
+//! an `ErrorEnvelope` if the feature `error-messages` is enabled, and otherwise
+//! the serialized error.
+use concordium_std::*;
+
+#[derive(Serial, Reject)]
+#[concordium(with_messages)]
+enum MyError {
+    #[concordium(message = "The parameter could not be parsed.")]
+    #[from(ParseError)]
+    ParseParams,
+    #[concordium(error_code = -20, message = "Only the owner can do this.")]
+    Unauthorized {
+        account: AccountAddress,
+    },
+    #[from]
+    #[concordium(transparent)]
+    Roles(RolesError),
+}
+
+fn main() {
+    let error = MyError::Unauthorized {
+        account: AccountAddress([0; 32]),
+    };
+    let serialized_error = to_bytes(&error);
+    let reject: Reject = error.into();
+    assert_eq!(reject.error_code.get(), -20);
+    let return_value = reject.return_value.expect("The return value is always set.");
+    match ErrorEnvelope::from_return_value(&return_value) {
+        Some(envelope) => assert_eq!(envelope, ErrorEnvelope {
+            error_code: -20,
+            variant:    "Unauthorized".into(),
+            message:    "Only the owner can do this.".into(),
+            error:      serialized_error,
+        }),
+        None => assert_eq!(return_value, serialized_error),
+    }
+    let roles = MyError::from(RolesError::Unauthorized);
+    let serialized_roles = to_bytes(&roles);
+    let roles: Reject = roles.into();
+    assert_eq!(roles.error_code, Reject::from(RolesError::Unauthorized).error_code);
+    assert_eq!(roles.return_value, Some(serialized_roles));
+}
@@ -5,6 +5,7 @@
 fn deserial_with_state() {
     let t = trybuild::TestCases::new();
     t.pass("tests/derive-deserial-with-state/success-*.rs");
+    t.compile_fail("tests/derive-deserial-with-state/fail-*.rs");
 }
 
 #[test]
@@ -12,3 +13,17 @@ fn deletable() {
     let t = trybuild::TestCases::new();
     t.pass("tests/derive-deletable/success-*.rs");
 }
+
+#[test]
+fn event() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/derive-event/success-*.rs");
+    t.compile_fail("tests/derive-event/fail-*.rs");
+}
+
+#[test]
+fn reject() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/derive-reject/success-*.rs");
+    t.compile_fail("tests/derive-reject/fail-*.rs");
+}
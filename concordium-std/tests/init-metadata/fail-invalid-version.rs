@@ -0,0 +1,12 @@
+//! Ensure that `#[init]` rejects a `version` that is not a semantic version.
+use concordium_std::*;
+
+#[init(contract = "test", version = "1.2")]
+fn init<S: HasStateApi>(
+    _ctx: &impl HasInitContext,
+    _state_builder: &mut StateBuilder<S>,
+) -> InitResult<()> {
+    Ok(())
+}
+
+fn main() {}
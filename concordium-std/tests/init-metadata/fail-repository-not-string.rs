@@ -0,0 +1,12 @@
+//! Ensure that `#[init]` rejects a `repository` value that is not a string.
+use concordium_std::*;
+
+#[init(contract = "test", repository = true)]
+fn init<S: HasStateApi>(
+    _ctx: &impl HasInitContext,
+    _state_builder: &mut StateBuilder<S>,
+) -> InitResult<()> {
+    Ok(())
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+//! Ensure that `#[init]` rejects a `standards` value that is not a string.
+use concordium_std::*;
+
+#[init(contract = "test", standards = 2)]
+fn init<S: HasStateApi>(
+    _ctx: &impl HasInitContext,
+    _state_builder: &mut StateBuilder<S>,
+) -> InitResult<()> {
+    Ok(())
+}
+
+fn main() {}
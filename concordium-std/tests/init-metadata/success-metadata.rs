@@ -0,0 +1,38 @@
+//! Ensure that `#[init]` accepts the `version`, `standards` and `repository`
+//! attributes, and that documented init and receive functions are accepted.
+use concordium_std::*;
+
+/// A contract for testing metadata.
+///
+/// The description spans several lines.
+#[init(
+    contract = "test",
+    version = "1.2.0-beta.1+build.5",
+    standards = "CIS-0, CIS-2",
+    repository = "https://github.com/Concordium/concordium-rust-smart-contracts"
+)]
+fn init<S: HasStateApi>(
+    _ctx: &impl HasInitContext,
+    _state_builder: &mut StateBuilder<S>,
+) -> InitResult<()> {
+    Ok(())
+}
+
+/// Do nothing.
+#[receive(contract = "test", name = "receive")]
+fn receive<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    _host: &impl HasHost<(), StateApiType = S>,
+) -> ReceiveResult<()> {
+    Ok(())
+}
+
+#[init(contract = "other", version = "0.1.0")]
+fn init_other<S: HasStateApi>(
+    _ctx: &impl HasInitContext,
+    _state_builder: &mut StateBuilder<S>,
+) -> InitResult<()> {
+    Ok(())
+}
+
+fn main() {}
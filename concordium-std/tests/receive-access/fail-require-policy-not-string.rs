@@ -0,0 +1,15 @@
+//! Ensure that `#[receive]` rejects a policy predicate that is not given as a
+//! string.
+use concordium_std::*;
+
+fn is_adult(_policy: &impl HasPolicy, _now: Timestamp) -> bool { true }
+
+#[receive(contract = "test", name = "receive", require_policy(is_adult))]
+fn receive<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    _host: &impl HasHost<(), StateApiType = S>,
+) -> ReceiveResult<()> {
+    Ok(())
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+//! Ensure that `#[receive]` rejects a policy predicate that is not a path.
+use concordium_std::*;
+
+#[receive(contract = "test", name = "receive", require_policy = "is adult")]
+fn receive<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    _host: &impl HasHost<(), StateApiType = S>,
+) -> ReceiveResult<()> {
+    Ok(())
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+//! Ensure that `#[receive]` rejects a sender kind other than "account" and
+//! "contract".
+use concordium_std::*;
+
+#[receive(contract = "test", name = "receive", requires_sender = "anyone")]
+fn receive<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    _host: &impl HasHost<(), StateApiType = S>,
+) -> ReceiveResult<()> {
+    Ok(())
+}
+
+fn main() {}
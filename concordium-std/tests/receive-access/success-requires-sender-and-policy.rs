@@ -0,0 +1,57 @@
+//! Ensure that `#[receive]` accepts the `requires_sender` and `require_policy`
+//! attributes, including a predicate given by a path, and that the requirements
+//! are checked when the function is called in tests.
+use concordium_std::{test_infrastructure::*, *};
+
+mod predicates {
+    use concordium_std::*;
+
+    pub fn adult_in_denmark(policy: &impl HasPolicy, now: Timestamp) -> bool {
+        policy.country_of_residence() == Some(*b"DK")
+            && policy.age_at(now).map_or(false, |age| age >= 18)
+    }
+}
+
+#[receive(
+    contract = "test",
+    name = "receive",
+    requires_sender = "account",
+    require_policy = "predicates::adult_in_denmark"
+)]
+fn receive<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    _host: &impl HasHost<(), StateApiType = S>,
+) -> ReceiveResult<()> {
+    Ok(())
+}
+
+#[receive(contract = "test", name = "contracts_only", requires_sender = "contract", mutable)]
+fn contracts_only<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    _host: &mut impl HasHost<(), StateApiType = S>,
+) -> ReceiveResult<()> {
+    Ok(())
+}
+
+fn main() {
+    let mut ctx = TestReceiveContext::empty();
+    ctx.set_sender(Address::Account(AccountAddress([0u8; 32])));
+    ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+    ctx.push_policy(TestPolicyBuilder::adult_eu_resident().build());
+    let mut host = TestHost::new((), TestStateBuilder::new());
+
+    assert_eq!(receive(&ctx, &host), Ok(()));
+    assert_eq!(
+        contracts_only(&ctx, &mut host),
+        Err(Reject::from(AccessDeniedError::WrongSenderKind))
+    );
+
+    // The date of birth is after the slot time, so the age is unknown.
+    ctx.push_policy(
+        TestPolicyBuilder::new()
+            .country_of_residence(*b"DK")
+            .date_of_birth(Date::new(2000, 1, 1).unwrap())
+            .build(),
+    );
+    assert_eq!(receive(&ctx, &host), Err(Reject::from(AccessDeniedError::PolicyNotSatisfied)));
+}
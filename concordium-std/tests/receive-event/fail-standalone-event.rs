@@ -0,0 +1,20 @@
+//! Ensure that `#[receive]` rejects the `event` attribute outside of a
+//! `#[contract]` module, since the event type would not be part of the schema.
+use concordium_std::*;
+
+#[derive(Serialize, SchemaType)]
+enum CounterEvent {
+    Incremented(u64),
+}
+
+#[receive(contract = "test", name = "receive", event = "CounterEvent", enable_logger)]
+fn receive<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    _host: &impl HasHost<(), StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> ReceiveResult<()> {
+    logger.log(&CounterEvent::Incremented(1))?;
+    Ok(())
+}
+
+fn main() {}
@@ -0,0 +1,42 @@
+//! Ensure that `#[receive]` accepts the `event` attribute in a `#[contract]`
+//! module, which accepts event types on several entrypoints.
+use concordium_std::*;
+
+#[derive(Serialize, SchemaType)]
+enum InitEvent {
+    Initialized,
+}
+
+#[derive(Serialize, SchemaType)]
+enum CounterEvent {
+    Initialized,
+    Incremented(u64),
+}
+
+#[contract(name = "counter")]
+mod counter {
+    use super::{CounterEvent, InitEvent};
+    use concordium_std::*;
+
+    #[init(event = "InitEvent", enable_logger)]
+    fn init<S: HasStateApi>(
+        _ctx: &impl HasInitContext,
+        _state_builder: &mut StateBuilder<S>,
+        logger: &mut impl HasLogger,
+    ) -> InitResult<()> {
+        logger.log(&InitEvent::Initialized)?;
+        Ok(())
+    }
+
+    #[receive(name = "increment", event = "CounterEvent", enable_logger)]
+    fn increment<S: HasStateApi>(
+        _ctx: &impl HasReceiveContext,
+        _host: &impl HasHost<(), StateApiType = S>,
+        logger: &mut impl HasLogger,
+    ) -> ReceiveResult<()> {
+        logger.log(&CounterEvent::Incremented(1))?;
+        Ok(())
+    }
+}
+
+fn main() {}
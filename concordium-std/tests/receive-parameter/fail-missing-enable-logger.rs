@@ -0,0 +1,14 @@
+//! Ensure that `#[receive]` reports a missing `enable_logger` attribute
+//! instead of taking the logger for the parameter.
+use concordium_std::*;
+
+#[receive(contract = "test", name = "transfer", mutable)]
+fn transfer<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    _host: &mut impl HasHost<(), StateApiType = S>,
+    _logger: &mut impl HasLogger,
+) -> ReceiveResult<()> {
+    Ok(())
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+//! Ensure that `#[receive]` reports a missing `payable` attribute instead of
+//! taking the logger for the parameter.
+use concordium_std::*;
+
+#[receive(contract = "test", name = "deposit", enable_logger)]
+fn deposit<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    _host: &impl HasHost<(), StateApiType = S>,
+    _amount: Amount,
+    _logger: &mut impl HasLogger,
+) -> ReceiveResult<()> {
+    Ok(())
+}
+
+fn main() {}
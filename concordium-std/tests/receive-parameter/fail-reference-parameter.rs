@@ -0,0 +1,13 @@
+//! Ensure that `#[receive]` rejects a parameter that is not an owned type.
+use concordium_std::*;
+
+#[receive(contract = "test", name = "transfer")]
+fn transfer<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    _host: &impl HasHost<(), StateApiType = S>,
+    _owner: &AccountAddress,
+) -> ReceiveResult<()> {
+    Ok(())
+}
+
+fn main() {}
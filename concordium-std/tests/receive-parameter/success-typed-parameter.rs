@@ -0,0 +1,44 @@
+//! Ensure that `#[receive]` accepts the parameter as the last argument of the
+//! function, also together with the optional arguments.
+use concordium_std::*;
+
+#[derive(Serialize, SchemaType)]
+struct TransferParameter {
+    to:     AccountAddress,
+    amount: Amount,
+}
+
+#[derive(Serialize, SchemaType)]
+struct Balance(u64);
+
+#[receive(contract = "test", name = "transfer", mutable)]
+fn transfer<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    _host: &mut impl HasHost<(), StateApiType = S>,
+    params: TransferParameter,
+) -> ReceiveResult<Balance> {
+    Ok(Balance(params.amount.micro_ccd))
+}
+
+#[receive(contract = "test", name = "deposit", payable, enable_logger, crypto_primitives)]
+fn deposit<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    _host: &impl HasHost<(), StateApiType = S>,
+    _amount: Amount,
+    _logger: &mut impl HasLogger,
+    _crypto_primitives: &impl HasCryptoPrimitives,
+    _owner: AccountAddress,
+) -> ReceiveResult<()> {
+    Ok(())
+}
+
+#[receive(contract = "test", name = "low_level", low_level, return_value = "u64")]
+fn low_level(
+    _ctx: &impl HasReceiveContext,
+    _host: &mut impl HasHost<ExternStateApi, StateApiType = ExternStateApi>,
+    n: u64,
+) -> Result<u64, Reject> {
+    Ok(n)
+}
+
+fn main() {}
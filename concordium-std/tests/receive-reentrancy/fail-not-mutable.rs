@@ -0,0 +1,14 @@
+//! Ensure that `#[receive]` rejects the `non_reentrant` attribute on functions
+//! that are not mutable, since the guard is recorded in the state.
+use concordium_std::*;
+
+#[receive(contract = "test", name = "deposit", payable, non_reentrant)]
+fn deposit<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    _host: &impl HasHost<u64, StateApiType = S>,
+    _amount: Amount,
+) -> ReceiveResult<()> {
+    Ok(())
+}
+
+fn main() {}
@@ -0,0 +1,63 @@
+//! Ensure that `#[receive]` accepts the `non_reentrant` attribute, also
+//! together with the low-level interface and the optional arguments, and that
+//! the guard is used when the function is called with a `TestHost`.
+use concordium_std::{test_infrastructure::*, *};
+
+#[receive(
+    contract = "test",
+    name = "forward",
+    parameter = "ContractAddress",
+    mutable,
+    non_reentrant
+)]
+fn forward<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<u64, StateApiType = S>,
+) -> ReceiveResult<()> {
+    let to: ContractAddress = ctx.parameter_cursor().get()?;
+    *host.state_mut() += 1;
+    host.invoke_contract(&to, &(), EntrypointName::new_unchecked("callback"), Amount::zero())?;
+    Ok(())
+}
+
+#[receive(contract = "test", name = "deposit", payable, enable_logger, mutable, non_reentrant)]
+fn deposit<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<u64, StateApiType = S>,
+    amount: Amount,
+    _logger: &mut impl HasLogger,
+) -> ReceiveResult<()> {
+    if amount == Amount::zero() {
+        return Err(Reject::default());
+    }
+    *host.state_mut() += 1;
+    Ok(())
+}
+
+#[receive(contract = "test", name = "low_level", low_level, non_reentrant)]
+fn low_level(
+    _ctx: &impl HasReceiveContext,
+    _host: &mut impl HasHost<ExternStateApi, StateApiType = ExternStateApi>,
+) -> ReceiveResult<()> {
+    Ok(())
+}
+
+fn main() {
+    let ctx = TestReceiveContext::empty();
+    let mut host = TestHost::new(0u64, TestStateBuilder::new());
+    let mut logger = TestLogger::init();
+
+    // The guard is exited both when the function succeeds and when it rejects.
+    assert_eq!(deposit(&ctx, &mut host, Amount::from_micro_ccd(1), &mut logger), Ok(()));
+    assert_eq!(deposit(&ctx, &mut host, Amount::zero(), &mut logger), Err(Reject::default()));
+    assert!(!ReentrancyGuard::new(host.state_builder()).is_entered());
+
+    // A nested invocation is rejected.
+    let mut guard = ReentrancyGuard::new(host.state_builder());
+    guard.enter().unwrap();
+    assert_eq!(
+        deposit(&ctx, &mut host, Amount::from_micro_ccd(1), &mut logger),
+        Err(Reject::from(ReentrancyError))
+    );
+    assert_eq!(*host.state(), 1);
+}
@@ -0,0 +1,31 @@
+//! Test correct functioning of the `#[receive]` attribute macro from the
+//! `concordium-std-derive` package. Test cases presented here check successful
+//! (or failed) compilation for the code which uses the macro, not its
+//! functioning.
+#[test]
+fn access() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/receive-access/success-*.rs");
+    t.compile_fail("tests/receive-access/fail-*.rs");
+}
+
+#[test]
+fn event() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/receive-event/success-*.rs");
+    t.compile_fail("tests/receive-event/fail-*.rs");
+}
+
+#[test]
+fn parameter() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/receive-parameter/success-*.rs");
+    t.compile_fail("tests/receive-parameter/fail-*.rs");
+}
+
+#[test]
+fn non_reentrant() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/receive-reentrancy/success-*.rs");
+    t.compile_fail("tests/receive-reentrancy/fail-*.rs");
+}
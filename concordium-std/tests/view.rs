@@ -0,0 +1,10 @@
+//! Test correct functioning of the `#[view]` attribute macro from the
+//! `concordium-std-derive` package. Test cases presented here check successful
+//! (or failed) compilation for the code which uses the macro, not its
+//! functioning.
+#[test]
+fn view() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/view/success-*.rs");
+    t.compile_fail("tests/view/fail-*.rs");
+}
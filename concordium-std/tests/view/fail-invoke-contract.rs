@@ -0,0 +1,16 @@
+//! Ensure that a `#[view]` cannot invoke other contracts, since its host only
+//! implements `HasViewHost`.
+use concordium_std::*;
+
+#[view(contract = "test", name = "forward")]
+fn forward(_ctx: &impl HasReceiveContext, host: &impl HasViewHost<u64>) -> ReceiveResult<()> {
+    let _ = host.invoke_contract(
+        &ContractAddress::new(0, 0),
+        &(),
+        EntrypointName::new_unchecked("get"),
+        Amount::zero(),
+    );
+    Ok(())
+}
+
+fn main() {}
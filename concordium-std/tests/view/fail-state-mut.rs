@@ -0,0 +1,11 @@
+//! Ensure that a `#[view]` cannot modify the state, since its host only
+//! implements `HasViewHost`.
+use concordium_std::*;
+
+#[view(contract = "test", name = "increment")]
+fn increment(_ctx: &impl HasReceiveContext, host: &impl HasViewHost<u64>) -> ReceiveResult<()> {
+    *host.state_mut() += 1;
+    Ok(())
+}
+
+fn main() {}
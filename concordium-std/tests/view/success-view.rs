@@ -0,0 +1,36 @@
+//! Ensure that `#[view]` accepts functions which only read the state and the
+//! balance through `HasViewHost`, also together with the optional arguments,
+//! and that such functions can be tested with a `TestHost`.
+use concordium_std::{test_infrastructure::*, *};
+
+#[derive(Serialize, SchemaType)]
+struct Summary {
+    count:   u64,
+    balance: Amount,
+}
+
+/// Get the state and the balance of the contract.
+#[view(contract = "test", name = "summary", return_value = "Summary")]
+fn summary(_ctx: &impl HasReceiveContext, host: &impl HasViewHost<u64>) -> ReceiveResult<Summary> {
+    Ok(Summary {
+        count:   *host.state(),
+        balance: host.self_balance(),
+    })
+}
+
+#[view(contract = "test", name = "is_count", requires_sender = "account", crypto_primitives)]
+fn is_count(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasViewHost<u64>,
+    _crypto_primitives: &impl HasCryptoPrimitives,
+    count: u64,
+) -> ReceiveResult<bool> {
+    Ok(*host.state() == count)
+}
+
+fn main() {
+    let ctx = TestReceiveContext::empty();
+    let mut host = TestHost::new(3u64, TestStateBuilder::new());
+    host.set_self_balance(Amount::from_micro_ccd(10));
+    let _ = summary(&ctx, &host);
+}
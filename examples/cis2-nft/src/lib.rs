@@ -294,7 +294,7 @@ fn build_token_metadata_url(token_id: &ContractTokenId) -> String {
 // Contract functions
 
 /// Initialize contract instance with no token types initially.
-#[init(contract = "cis2_nft")]
+#[init(contract = "cis2_nft", version = "0.1.0", standards = "CIS-0, CIS-2")]
 fn contract_init<S: HasStateApi>(
     _ctx: &impl HasInitContext,
     state_builder: &mut StateBuilder<S>,
@@ -317,10 +317,10 @@ struct ViewState {
 
 /// View function that returns the entire contents of the state. Meant for
 /// testing.
-#[receive(contract = "cis2_nft", name = "view", return_value = "ViewState")]
+#[view(contract = "cis2_nft", name = "view", return_value = "ViewState")]
 fn contract_view<S: HasStateApi>(
     _ctx: &impl HasReceiveContext,
-    host: &impl HasHost<State<S>, StateApiType = S>,
+    host: &impl HasViewHost<State<S>>,
 ) -> ReceiveResult<ViewState> {
     let state = host.state();
 
@@ -530,7 +530,7 @@ fn contract_update_operator<S: HasStateApi>(
 ///
 /// It rejects if:
 /// - It fails to parse the parameter.
-#[receive(
+#[view(
     contract = "cis2_nft",
     name = "operatorOf",
     parameter = "OperatorOfQueryParams",
@@ -539,7 +539,7 @@ fn contract_update_operator<S: HasStateApi>(
 )]
 fn contract_operator_of<S: HasStateApi>(
     ctx: &impl HasReceiveContext,
-    host: &impl HasHost<State<S>, StateApiType = S>,
+    host: &impl HasViewHost<State<S>>,
 ) -> ContractResult<OperatorOfQueryResponse> {
     // Parse the parameter.
     let params: OperatorOfQueryParams = ctx.parameter_cursor().get()?;
@@ -566,7 +566,7 @@ type ContractBalanceOfQueryResponse = BalanceOfQueryResponse<ContractTokenAmount
 /// It rejects if:
 /// - It fails to parse the parameter.
 /// - Any of the queried `token_id` does not exist.
-#[receive(
+#[view(
     contract = "cis2_nft",
     name = "balanceOf",
     parameter = "ContractBalanceOfQueryParams",
@@ -575,7 +575,7 @@ type ContractBalanceOfQueryResponse = BalanceOfQueryResponse<ContractTokenAmount
 )]
 fn contract_balance_of<S: HasStateApi>(
     ctx: &impl HasReceiveContext,
-    host: &impl HasHost<State<S>, StateApiType = S>,
+    host: &impl HasViewHost<State<S>>,
 ) -> ContractResult<ContractBalanceOfQueryResponse> {
     // Parse the parameter.
     let params: ContractBalanceOfQueryParams = ctx.parameter_cursor().get()?;
@@ -599,7 +599,7 @@ type ContractTokenMetadataQueryParams = TokenMetadataQueryParams<ContractTokenId
 /// It rejects if:
 /// - It fails to parse the parameter.
 /// - Any of the queried `token_id` does not exist.
-#[receive(
+#[view(
     contract = "cis2_nft",
     name = "tokenMetadata",
     parameter = "ContractTokenMetadataQueryParams",
@@ -608,7 +608,7 @@ type ContractTokenMetadataQueryParams = TokenMetadataQueryParams<ContractTokenId
 )]
 fn contract_token_metadata<S: HasStateApi>(
     ctx: &impl HasReceiveContext,
-    host: &impl HasHost<State<S>, StateApiType = S>,
+    host: &impl HasViewHost<State<S>>,
 ) -> ContractResult<TokenMetadataQueryResponse> {
     // Parse the parameter.
     let params: ContractTokenMetadataQueryParams = ctx.parameter_cursor().get()?;
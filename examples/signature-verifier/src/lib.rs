@@ -52,11 +52,10 @@ mod tests {
     use concordium_std::test_infrastructure::*;
 
     #[concordium_test]
-    #[cfg(not(feature = "crypto-primitives"))]
     fn test_receive_with_mocks() {
         let mut ctx = TestReceiveContext::empty();
         let host = TestHost::new((), TestStateBuilder::new());
-        let crypto_primitives = TestCryptoPrimitives::new();
+        let crypto_primitives = TestCryptoPrimitives::mocked();
 
         let param = VerificationParameter {
             public_key: PublicKeyEd25519([0; 32]),
@@ -69,7 +68,12 @@ mod tests {
         crypto_primitives.setup_verify_ed25519_signature_mock(|_, _, _| true);
 
         let res = contract_receive(&ctx, &host, &crypto_primitives);
-        claim_eq!(res, Ok(true))
+        claim_eq!(res, Ok(true));
+        claim_eq!(crypto_primitives.verify_ed25519_signature_calls(), vec![(
+            param.public_key,
+            param.signature,
+            param.message
+        )]);
     }
 
     #[concordium_test]
@@ -77,7 +81,7 @@ mod tests {
     fn test_receive() {
         let mut ctx = TestReceiveContext::empty();
         let host = TestHost::new((), TestStateBuilder::new());
-        let crypto_primitives = TestCryptoPrimitives::new();
+        let crypto_primitives = TestCryptoPrimitives::real();
 
         let param = VerificationParameter {
             public_key: PublicKeyEd25519([
@@ -98,4 +102,36 @@ mod tests {
         let res = contract_receive(&ctx, &host, &crypto_primitives);
         claim_eq!(res, Ok(true))
     }
+
+    #[concordium_test]
+    #[cfg(feature = "crypto-primitives")]
+    fn test_receive_with_test_key_pair() {
+        let mut ctx = TestReceiveContext::empty();
+        let host = TestHost::new((), TestStateBuilder::new());
+        let crypto_primitives = TestCryptoPrimitives::real();
+
+        let key_pair = TestKeyPairEd25519::from_seed(0);
+        let message = b"Concordium".to_vec();
+        let param = VerificationParameter {
+            public_key: key_pair.public_key(),
+            signature: key_pair.sign(&message),
+            message,
+        };
+        let param_bytes = to_bytes(&param);
+        ctx.set_parameter(&param_bytes);
+
+        let res = contract_receive(&ctx, &host, &crypto_primitives);
+        claim_eq!(res, Ok(true));
+
+        // A signature from a different key is rejected.
+        let param = VerificationParameter {
+            public_key: TestKeyPairEd25519::from_seed(1).public_key(),
+            ..param
+        };
+        let param_bytes = to_bytes(&param);
+        ctx.set_parameter(&param_bytes);
+
+        let res = contract_receive(&ctx, &host, &crypto_primitives);
+        claim_eq!(res, Ok(false))
+    }
 }
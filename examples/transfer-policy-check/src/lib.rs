@@ -6,11 +6,12 @@
  * The contract is initialised with `init`, in which an account address is
  * provided, which will be set as the state.
  *
- * The contract has a single receive entrypoint, `receive`,
- * which checks whether all sender policies have their country of residence
- * attribute set to `LOCAL_COUNTRY`. If that is the case, then the `amount`
- * will be forwarded to the account address held in the state. Otherwise,
- * the receive function will reject with `ContractError::NotLocalSender`.
+ * The contract has a single receive entrypoint, `receive`, which uses the
+ * `require_policy` attribute to check whether all sender policies have
+ * their country of residence attribute set to `LOCAL_COUNTRY`. If that is
+ * the case, then the `amount` will be forwarded to the account address held
+ * in the state. Otherwise, the receive function will reject with
+ * `ContractError::NotLocalSender`.
  */
 #![cfg_attr(not(feature = "std"), no_std)]
 use concordium_std::*;
@@ -20,6 +21,7 @@ const LOCAL_COUNTRY: [u8; 2] = *b"DK";
 
 #[derive(Serial, Reject, PartialEq, Eq, Debug, SchemaType)]
 enum ContractError {
+    #[from(AccessDeniedError)]
     NotLocalSender,
     TransferErrorAmountTooLarge,
     TransferErrorAccountMissing,
@@ -43,21 +45,25 @@ fn init<S: HasStateApi>(
     Ok(ctx.parameter_cursor().get()?)
 }
 
+/// Whether the policy has the country of residence in `LOCAL_COUNTRY`.
+fn is_local(policy: &impl HasPolicy, _now: Timestamp) -> bool {
+    policy.country_of_residence() == Some(LOCAL_COUNTRY)
+}
+
 /// Forward the `amount` to the account defined in the state iff all sender
 /// policies have the country of residence in `LOCAL_COUNTRY`.
-#[receive(contract = "transfer-policy-check", name = "receive", payable, error = "ContractError")]
+#[receive(
+    contract = "transfer-policy-check",
+    name = "receive",
+    payable,
+    error = "ContractError",
+    require_policy = "is_local"
+)]
 fn receive<S: HasStateApi>(
-    ctx: &impl HasReceiveContext,
+    _ctx: &impl HasReceiveContext,
     host: &impl HasHost<State, StateApiType = S>,
     amount: Amount,
 ) -> Result<(), ContractError> {
-    for policy in ctx.policies() {
-        if !policy.attributes().any(|(tag, val)| {
-            tag == attributes::COUNTRY_OF_RESIDENCE && val.as_ref() == &LOCAL_COUNTRY[..]
-        }) {
-            return Err(ContractError::NotLocalSender);
-        }
-    }
     Ok(host.invoke_transfer(host.state(), amount)?)
 }
 
@@ -71,18 +77,9 @@ mod tests {
     #[concordium_test]
     fn receive_with_correct_policies() {
         let mut ctx = TestReceiveContext::empty();
-        ctx.push_policy(Policy {
-            identity_provider: 0,
-            created_at:        Timestamp::from_timestamp_millis(0),
-            valid_to:          Timestamp::from_timestamp_millis(1000),
-            items:             vec![(attributes::COUNTRY_OF_RESIDENCE, LOCAL_COUNTRY.into())],
-        });
-        ctx.push_policy(Policy {
-            identity_provider: 0,
-            created_at:        Timestamp::from_timestamp_millis(0),
-            valid_to:          Timestamp::from_timestamp_millis(1000),
-            items:             vec![(attributes::COUNTRY_OF_RESIDENCE, LOCAL_COUNTRY.into())],
-        });
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        ctx.push_policy(TestPolicyBuilder::new().country_of_residence(LOCAL_COUNTRY).build());
+        ctx.push_policy(TestPolicyBuilder::new().country_of_residence(LOCAL_COUNTRY).build());
 
         let state = ACCOUNT_0;
         let state_builder = TestStateBuilder::new();
@@ -97,22 +94,15 @@ mod tests {
     #[concordium_test]
     fn receive_with_incorrect_policies() {
         let mut ctx = TestReceiveContext::empty();
-        ctx.push_policy(Policy {
-            identity_provider: 0,
-            created_at:        Timestamp::from_timestamp_millis(0),
-            valid_to:          Timestamp::from_timestamp_millis(1000),
-            items:             vec![(attributes::COUNTRY_OF_RESIDENCE, LOCAL_COUNTRY.into())],
-        });
-        ctx.push_policy(Policy {
-            identity_provider: 0,
-            created_at:        Timestamp::from_timestamp_millis(0),
-            valid_to:          Timestamp::from_timestamp_millis(1000),
-            items:             vec![(
-                attributes::COUNTRY_OF_RESIDENCE,
-                b"NOT_LOCAL_COUNTRY".into(), /* Chose an invalid country to avoid conflicts
-                                              * with valid settings of `LOCAL_COUNTRY`. */
-            )],
-        });
+        ctx.set_metadata_slot_time(Timestamp::from_timestamp_millis(0));
+        ctx.push_policy(TestPolicyBuilder::new().country_of_residence(LOCAL_COUNTRY).build());
+        ctx.push_policy(
+            TestPolicyBuilder::new()
+                // Chose an invalid country to avoid conflicts with valid settings of
+                // `LOCAL_COUNTRY`.
+                .attribute(attributes::COUNTRY_OF_RESIDENCE, b"NOT_LOCAL_COUNTRY")
+                .build(),
+        );
 
         let state = ACCOUNT_0;
         let state_builder = TestStateBuilder::new();
@@ -121,5 +111,22 @@ mod tests {
         host.set_self_balance(transfer_amount);
         let res = receive(&ctx, &host, transfer_amount);
         assert_eq!(res, Err(ContractError::NotLocalSender));
+        assert_eq!(host.get_transfers(), vec![]);
+    }
+
+    #[concordium_test]
+    fn local_policy_is_accepted() {
+        let policy = TestPolicyBuilder::new().country_of_residence(LOCAL_COUNTRY).build();
+        assert!(is_local(&policy, Timestamp::from_timestamp_millis(0)));
+    }
+
+    #[concordium_test]
+    fn other_policy_is_rejected() {
+        let policy = TestPolicyBuilder::new()
+            // Chose an invalid country to avoid conflicts with valid settings of
+            // `LOCAL_COUNTRY`.
+            .attribute(attributes::COUNTRY_OF_RESIDENCE, b"NOT_LOCAL_COUNTRY")
+            .build();
+        assert!(!is_local(&policy, Timestamp::from_timestamp_millis(0)));
     }
 }